@@ -1,5 +1,5 @@
-/// Color palette demo using crossterm directly.
-/// Shows a grid of foreground/background color combinations.
+//! Color palette demo using crossterm directly.
+//! Shows a grid of foreground/background color combinations.
 
 use std::collections::HashMap;
 use std::io::{self, Write};
@@ -9,7 +9,7 @@ use crossterm::{
     execute, queue,
     style::{Color, Colors, Print, ResetColor, SetColors},
     terminal::{
-        disable_raw_mode, enable_raw_mode, size,
+        disable_raw_mode, enable_raw_mode,
         Clear, ClearType,
         EnterAlternateScreen, LeaveAlternateScreen,
     },
@@ -0,0 +1,30 @@
+//!
+//! Print what the typed accessors of the executable trait return, in the
+//! manner of `readelf -h -l -d`
+//!
+
+use ncexe::exe_types;
+
+fn main() {
+    for fname in std::env::args().skip(1) {
+        let exe = exe_types::new(&fname);
+        println!("{}: {}", fname, exe);
+
+        let unknown = || String::from("-");
+        println!("  Architecture: {}", exe.architecture().map_or_else(unknown, |a| a.to_string()));
+        println!("  Endianness:   {}", exe.endianness().map_or_else(unknown, |e| format!("{:?}", e)));
+        println!("  Bits:         {}", exe.bitness().map_or_else(unknown, |b| b.to_string()));
+        println!("  Entry point:  {}", exe.entry_point().map_or_else(unknown, |e| format!("{:#x}", e)));
+
+        for segment in exe.segments() {
+            println!(
+                "  Segment {:<14} {:#012x} {:>10} bytes {}",
+                segment.name, segment.addr, segment.size, segment.permissions(),
+            );
+        }
+        println!("  {} sections, {} symbols", exe.sections().len(), exe.symbols().len());
+        for library in exe.dependencies() {
+            println!("  Needs {}", library);
+        }
+    }
+}
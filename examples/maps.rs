@@ -110,11 +110,7 @@ fn main() {
                 String::from("None")
             },
             if let Some((tfn, tbl)) = info.val_fn {
-                if let Some(s) = Info::lookup(val, tfn, tbl ) {
-                    s
-                } else {
-                    "Not Found"
-                }
+                Info::lookup(val, tfn, tbl).unwrap_or("Not Found")
             } else {
                 "No lookup"
             },
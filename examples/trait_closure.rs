@@ -39,7 +39,7 @@ fn main() {
     
     let str1 = "Hello World";
     let b3 = | x: usize | -> usize { 
-        x + str1.len() as usize
+        x + str1.len()
       };
     let sv5 = S2{tref: &b3};
     
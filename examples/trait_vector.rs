@@ -7,6 +7,7 @@ struct MyStruct<'s> {
     
 impl<'ms> MyStruct<'ms> {
     
+    #[allow(clippy::new_ret_no_self)]
     fn new(s: &'ms str) -> Box<dyn MyTrait + 'ms> {
         Box::new(Self{s: s.to_string() + " as string",
             ss: s
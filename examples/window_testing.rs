@@ -59,6 +59,7 @@ fn main() {
     let mut ftr_win = footer::Footer::new(&cs1.footer, Box::new(footer_fn));
 
     screens::show(
+        None,
         &mut hdr_win,
         &mut scr_win,
         &mut ftr_win).unwrap();
@@ -0,0 +1,29 @@
+//!
+//! Open the input as an executable and decode what the library offers:
+//! the header structures and the format independent accessors
+//!
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::{env, fs, process};
+
+use ncexe::{decoders::structures, exe_types};
+
+fuzz_target!(|data: &[u8]| {
+    // The executables are memory mapped files
+    let path = env::temp_dir().join(format!("ncexe-fuzz-open-{}", process::id()));
+    fs::write(&path, data).unwrap();
+
+    let exe = exe_types::new(&path);
+    exe.architecture();
+    exe.endianness();
+    exe.bitness();
+    exe.entry_point();
+    exe.segments();
+    exe.sections();
+    exe.symbols();
+    exe.dependencies();
+    exe.address_space();
+    structures::decode(exe.as_ref());
+});
@@ -0,0 +1,24 @@
+//!
+//! Open the input as an executable and walk every screen it leads to, with
+//! every line expanded and every field opening a screen entered
+//!
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::{env, fs, process, sync::Once};
+
+use ncexe::{color, exe_types, screens::dump};
+
+static COLORS: Once = Once::new();
+
+fuzz_target!(|data: &[u8]| {
+    COLORS.call_once(|| color::init("dark"));
+
+    // The executables are memory mapped files
+    let path = env::temp_dir().join(format!("ncexe-fuzz-walk-{}", process::id()));
+    fs::write(&path, data).unwrap();
+
+    // Errors are expected, they are reported to the user
+    let _ = dump::walk(&Vec::from([exe_types::new(&path)]));
+});
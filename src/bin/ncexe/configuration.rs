@@ -36,7 +36,6 @@ struct YamlConfig {
 ///     1. Standard location `~/.config/ncexe.yaml`
 ///     2. File pointed to by the `NCEXE_CONFIG` environment variable
 ///     3. File pointed to by the `--config` command line argument
-
 impl Configuration {
 
     /// Create a new configuratio object from
@@ -112,10 +111,11 @@ mod tests {
         env::set_var("NCEXE_CONFIG", "tests/goodconfig.yaml");
         assert!(env::var("NCEXE_CONFIG") == Ok("tests/goodconfig.yaml".to_string()));
 
-        let arg = Arguments{exe_filename: vec!("blah".to_string()), 
+        let arg = Arguments{exe_filename: vec!(PathBuf::from("blah")), 
                             config: None, 
                             show_notexe: false,
-                            theme: String::from("Dark") }; 
+                            theme: String::from("Dark"),
+                            ..Default::default() }; 
         let cfg = Configuration::new(&arg).unwrap();
 
         println!("{:?}", cfg);
@@ -126,10 +126,11 @@ mod tests {
 
     fn test_2() {
 
-        let arg = Arguments{exe_filename: vec!("blah".to_string()), 
+        let arg = Arguments{exe_filename: vec!(PathBuf::from("blah")), 
                             config: None, 
                             show_notexe: true,
-                            theme: String::from("Dark") }; 
+                            theme: String::from("Dark"),
+                            ..Default::default() }; 
 
         let cfg = Configuration::new(&arg).unwrap();
 
@@ -143,10 +144,11 @@ mod tests {
         env::set_var("NCEXE_CONFIG", "tests/goodconfig.yaml");
         assert!(env::var("NCEXE_CONFIG") == Ok("tests/goodconfig.yaml".to_string()));
 
-        let arg = Arguments{exe_filename: vec!("blah".to_string()), 
+        let arg = Arguments{exe_filename: vec!(PathBuf::from("blah")), 
                             config: None, 
                             show_notexe: true,
-                            theme: String::from("Dark") }; 
+                            theme: String::from("Dark"),
+                            ..Default::default() }; 
 
         let cfg = Configuration::new(&arg).unwrap();
 
@@ -4,15 +4,18 @@
 
 mod configuration;
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{bail, Result};
+use clap::{Parser, ValueEnum};
 use once_cell::sync::Lazy;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use ncexe::{
     color,
-    exe_types::{self, ExeVec},
+    decoders::structures,
+    exe_types::{self, scan, ExeVec},
     screens::{
+        compare,
+        dump,
         file_header,
         file_list,
         terminal::TERMWIN,
@@ -24,9 +27,21 @@ use ncexe::{
 
 #[derive(Parser, Default, Debug)]
 pub struct Arguments {
-    /// Name of the executable file(s)
-    #[arg(required = true)]
-    exe_filename: Vec<String>,
+    /// Name of the executable file(s), directories are searched recursively
+    #[arg(required_unless_present = "diff")]
+    exe_filename: Vec<PathBuf>,
+
+    /// Only take the files of directories matching this glob, can be repeated
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Leave out the files and directories matching this glob, can be repeated
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Follow symbolic links when searching directories
+    #[arg(short = 'L', long, action)]
+    follow_symlinks: bool,
 
     /// Sets a custom config file
     #[arg(short, long, value_name = "FILE")]
@@ -39,6 +54,33 @@ pub struct Arguments {
     /// Theme to use
     #[arg(short, long, default_value = "dark")]
     theme: String,
+
+    /// Write the screens to stdout as text instead of showing them
+    #[arg(long, action)]
+    dump: bool,
+
+    /// View to include in the dump, by name or "all", can be repeated
+    #[arg(long, value_name = "NAME", requires = "dump")]
+    view: Vec<String>,
+
+    /// Include in the dump the expansions of lines and the tables they open
+    #[arg(long, action, requires = "dump")]
+    nested: bool,
+
+    /// Write the decoded structures to stdout in this format instead of
+    /// showing them
+    #[arg(long, value_enum, conflicts_with = "dump")]
+    format: Option<Format>,
+
+    /// Compare two executable files side by side
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with_all = ["dump", "format"])]
+    diff: Option<Vec<PathBuf>>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    Json,
+    Yaml,
 }
 
 // ------------------------------------------------------------------------
@@ -48,21 +90,33 @@ fn main() -> Result<()> {
     let args: Arguments = Arguments::parse();
     let config = configuration::Configuration::new(&args)?;
 
+    if let Some(files) = &args.diff {
+        return diff(&files[0], &files[1], &config.theme);
+    }
+
     // Setup the list of executable objects
-    let executables: ExeVec = args
-        .exe_filename
-        .iter()
-        .map(|fname| exe_types::new(fname))
+    let filters = scan::Filters::new(&args.include, &args.exclude, args.follow_symlinks)?;
+    let filenames = scan::files(&args.exe_filename, &filters);
+    let executables: ExeVec = scan::open(&filenames)
+        .into_iter()
         .filter(|exe| config.show_notexe || !exe.is_empty())
         .collect();
 
     if executables.is_empty() || (executables.len() == 1 && executables[0].is_empty()) {
-        panic!("No executable files of interest found");
+        bail!("No executable files of interest found");
     }
 
     // Initialize colors (before the terminal so errors go to stderr)
     color::init(&config.theme);
 
+    if let Some(format) = args.format {
+        return export(&executables, format);
+    }
+
+    if args.dump {
+        return dump::run(&executables, dump::Options { views: args.view, nested: args.nested });
+    }
+
     // Initialize terminal (force lazy initialization)
     Lazy::force(&TERMWIN);
 
@@ -77,3 +131,41 @@ fn main() -> Result<()> {
 
     rc
 }
+
+// ------------------------------------------------------------------------
+/// Show the comparison of two executable files
+fn diff(old: &Path, new: &Path, theme: &str) -> Result<()> {
+    let (old, new) = (exe_types::new(old), exe_types::new(new));
+    for exe in [&old, &new] {
+        if exe.is_empty() {
+            bail!("{} is not an executable file", exe.filename().display());
+        }
+    }
+
+    color::init(theme);
+    Lazy::force(&TERMWIN);
+    let rc = compare::show(old.as_ref(), new.as_ref());
+    TERMWIN.term();
+
+    rc
+}
+
+// ------------------------------------------------------------------------
+/// Write the decoded structures of the executable files to stdout
+fn export(executables: &ExeVec, format: Format) -> Result<()> {
+    let files: Vec<_> = executables
+        .iter()
+        .filter(|exe| !exe.is_empty())
+        .map(|exe| structures::decode(exe.as_ref()))
+        .collect();
+
+    let stdout = std::io::stdout().lock();
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(stdout, &files)?;
+            println!();
+        }
+        Format::Yaml => serde_yaml::to_writer(stdout, &files)?,
+    }
+    Ok(())
+}
@@ -16,11 +16,11 @@
 //! ## API
 //!
 //! - A window set owner will request a WindowColorSet by the window's name
-//!     (e.g. file_list).
+//!   (e.g. file_list).
 //! - Each portion of the window set (header, etc.) will be passed
-//!     a generated set of styles for the title, text, and value
+//!   a generated set of styles for the title, text, and value
 //! - The theme in use will be set during instantiation of the Colors object in the main
-//!     from data in the configuration
+//!   from data in the configuration
 
 use anyhow::{anyhow, bail, Result};
 use once_cell::sync::OnceCell;
@@ -240,6 +240,22 @@ dark:
             title: [160, Bold]
             text: [127, Normal]
             value: [166, Bold]
+    compare:
+        header:
+            bkgr: 232
+            title: [160, Bold]
+            text: [127, Normal]
+            value: [166, Normal]
+        scrollable_region:
+            bkgr: 236
+            title: [203, Bold]
+            text: [250, Normal]
+            value: [114, Bold]
+        footer:
+            bkgr: 232
+            title: [160, Bold]
+            text: [127, Normal]
+            value: [166, Bold]
 
 ";
 
@@ -0,0 +1,235 @@
+//!
+//! Map a virtual address to its function, source file and line, with the
+//! chain of inlined calls, in the manner of `addr2line -f -i`
+//!
+
+use anyhow::Result;
+
+use crate::exe_types::{ExeRef, SymbolMap};
+
+use super::dwarf::{self, constants::*, line::LineProgram, AttrValue, Die, Dwarf, Unit};
+
+// ------------------------------------------------------------------------
+
+/// Limit on the nesting of entries searched for the address
+const MAX_DEPTH: usize = 256;
+
+/// Limit on `DW_AT_abstract_origin`/`DW_AT_specification` chains
+const MAX_REFS: usize = 8;
+
+/// Source position, the first frame is the innermost inlined call
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: u64,
+    pub column: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Location {
+    pub address: u64,
+    pub frames: Vec<Frame>,
+    /// Symbol table entry and the offset into it
+    pub symbol: Option<(String, u64)>,
+}
+
+/// Is there anything to look addresses up in?
+pub fn is_present(exe: ExeRef) -> bool {
+    dwarf::is_present(exe) || !exe.symbols().is_empty()
+}
+
+pub fn lookup(exe: ExeRef, address: u64) -> Result<Location> {
+    let symbols = exe.symbols();
+    let symbol = SymbolMap::new(&symbols).lookup(address).map(|(s, offset)| (s.name.clone(), offset));
+
+    let mut frames = match Dwarf::load(exe) {
+        Some(dwarf) => dwarf_frames(&dwarf?, address)?,
+        None => Vec::new(),
+    };
+
+    // Name the outermost function from the symbol table if DWARF didn't
+    if let (Some(frame), Some((name, _))) = (frames.last_mut(), &symbol) {
+        if frame.function.is_none() {
+            frame.function = Some(name.clone());
+        }
+    }
+
+    Ok(Location { address, frames, symbol })
+}
+
+// ------------------------------------------------------------------------
+
+fn dwarf_frames(dwarf: &Dwarf, address: u64) -> Result<Vec<Frame>> {
+    let units = dwarf.units()?;
+
+    // Prefer the unit whose ranges cover the address, otherwise search
+    // the line tables of those without ranges
+    let mut found = None;
+    for unit in &units {
+        let Some(cu) = dwarf.die(unit, unit.first_die)? else { continue };
+        let ranges = dwarf.die_ranges(unit, &cu).unwrap_or_default();
+        if ranges.iter().any(|r| contains(*r, address)) {
+            found = Some((unit, cu, line_program(dwarf, unit)));
+            break;
+        }
+    }
+    if found.is_none() {
+        for unit in &units {
+            let Some(cu) = dwarf.die(unit, unit.first_die)? else { continue };
+            let program = line_program(dwarf, unit);
+            if program.as_ref().is_some_and(|p| p.find_row(address).is_some()) {
+                found = Some((unit, cu, program));
+                break;
+            }
+        }
+    }
+    let Some((unit, cu, program)) = found else {
+        return Ok(Vec::new());
+    };
+
+    let mut chain = Vec::new();
+    scopes(dwarf, unit, &cu, address, 0, &mut chain)?;
+
+    let file_name = |index| program.as_ref().map(|p| p.file_name(index));
+    let row = program.as_ref().and_then(|p| p.find_row(address));
+    let (mut file, mut line, mut column) = match row {
+        Some(row) => (file_name(row.file), row.line, row.column),
+        None => (None, 0, 0),
+    };
+
+    if chain.is_empty() {
+        return Ok(match row {
+            Some(_) => Vec::from([Frame { function: None, file, line, column }]),
+            None => Vec::new(),
+        });
+    }
+
+    // Walk outwards, each inlined call supplies the position of its caller
+    let mut frames = Vec::new();
+    for die in chain.iter().rev() {
+        frames.push(Frame {
+            function: function_name(dwarf, &units, unit, die),
+            file: file.take(),
+            line,
+            column,
+        });
+        let udata = |name| die.attr(name).and_then(|a| a.udata());
+        file = udata(DW_AT_CALL_FILE).and_then(file_name);
+        line = udata(DW_AT_CALL_LINE).unwrap_or(0);
+        column = udata(DW_AT_CALL_COLUMN).unwrap_or(0);
+    }
+
+    Ok(frames)
+}
+
+fn line_program<'d>(dwarf: &'d Dwarf<'d>, unit: &Unit) -> Option<LineProgram<'d>> {
+    let cu = dwarf.die(unit, unit.first_die).ok()??;
+    let offset = cu.attr(DW_AT_STMT_LIST)?.udata()?;
+    dwarf.line_program(usize::try_from(offset).ok()?, unit.addr_size).ok()
+}
+
+/// Collect the subprogram and inlined subroutine entries containing
+/// `address`, outermost first
+fn scopes<'d>(
+    dwarf: &'d Dwarf<'d>,
+    unit: &Unit,
+    die: &Die<'d>,
+    address: u64,
+    depth: usize,
+    chain: &mut Vec<Die<'d>>,
+) -> Result<bool> {
+    if depth > MAX_DEPTH {
+        return Ok(false);
+    }
+
+    for child in dwarf.children(unit, die)? {
+        let ranges = dwarf.die_ranges(unit, &child).unwrap_or_default();
+        let is_function = matches!(child.tag, DW_TAG_SUBPROGRAM | DW_TAG_INLINED_SUBROUTINE);
+
+        if ranges.is_empty() {
+            // Namespaces and types may hold functions, declarations can't
+            if !is_function && child.has_children && scopes(dwarf, unit, &child, address, depth + 1, chain)? {
+                return Ok(true);
+            }
+            continue;
+        }
+        if !ranges.iter().any(|r| contains(*r, address)) {
+            continue;
+        }
+
+        if is_function {
+            chain.push(child.clone());
+        }
+        scopes(dwarf, unit, &child, address, depth + 1, chain)?;
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Name of a function entry, following references to its declaration
+fn function_name(dwarf: &Dwarf, units: &[Unit], unit: &Unit, die: &Die) -> Option<String> {
+    let mut unit = unit;
+    let mut die = die.clone();
+
+    for _ in 0..MAX_REFS {
+        for name in [DW_AT_NAME, DW_AT_LINKAGE_NAME, DW_AT_MIPS_LINKAGE_NAME] {
+            if let Some(s) = die.attr(name).and_then(|a| dwarf.attr_string(unit, a)) {
+                return Some(s.into());
+            }
+        }
+
+        let target = [DW_AT_ABSTRACT_ORIGIN, DW_AT_SPECIFICATION]
+            .iter()
+            .find_map(|n| match die.attr(*n)?.value {
+                AttrValue::Ref(offset) => Some(offset),
+                _ => None,
+            })?;
+        unit = units.iter().find(|u| u.offset <= target && target < u.end)?;
+        die = dwarf.die(unit, target).ok()??;
+    }
+
+    None
+}
+
+fn contains((start, end): (u64, u64), address: u64) -> bool {
+    start <= address && address < end
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exe_types;
+
+    fn frames(location: &Location) -> Vec<(String, String, u64)> {
+        location
+            .frames
+            .iter()
+            .map(|f| (f.function.clone().unwrap_or_default(), f.file.clone().unwrap_or_default(), f.line))
+            .collect()
+    }
+
+    #[test]
+    fn inlined_frames() {
+        let exe = exe_types::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/samples/inline-x86_64.elf"));
+        assert!(is_present(exe.as_ref()));
+
+        // scale() inlined into main() at its first instruction, the
+        // multiply
+        let location = lookup(exe.as_ref(), 0x5f0).unwrap();
+        assert_eq!(location.symbol, Some((String::from("main"), 0)));
+        assert_eq!(frames(&location), [
+            (String::from("scale"), String::from("/tmp/smp/inline.c"), 5),
+            (String::from("main"), String::from("/tmp/smp/inline.c"), 10),
+        ]);
+        assert_eq!((location.frames[0].column, location.frames[1].column), (14, 5));
+
+        // The call to printf() is main() alone
+        let location = lookup(exe.as_ref(), 0x603).unwrap();
+        assert_eq!(location.symbol, Some((String::from("main"), 0x13)));
+        assert_eq!(frames(&location), [(String::from("main"), String::from("/tmp/smp/inline.c"), 10)]);
+    }
+}
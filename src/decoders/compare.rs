@@ -0,0 +1,300 @@
+//!
+//! Differences between two executables, entry by entry
+//!
+//! Each group of entries, such as the sections, is keyed by name and the
+//! entries of the two files with the same key are paired up. The header
+//! fields come from the same tables as the header screens, so they cover
+//! the Mach-O load commands as well. Symbols are compared on their kind,
+//! binding and size but not their address, which moves with any change to
+//! the code before them.
+//!
+
+use std::collections::HashMap;
+
+use crate::{
+    decoders::structures::{self, Structure},
+    exe_types::{
+        elf::{self, DynamicEntry, DT_NEEDED},
+        ExeRef, SymbolKind,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+/// What happened to an entry from the old file to the new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Same,
+    Changed,
+    Added,
+    Removed,
+}
+
+/// Entry of a group, the key is unique within the group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+}
+
+/// Entry of either file or both, with its value in each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl Row {
+    pub fn change(&self) -> Change {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) if old == new => Change::Same,
+            (Some(_), Some(_)) => Change::Changed,
+            (None, _) => Change::Added,
+            (Some(_), None) => Change::Removed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub name: &'static str,
+    pub rows: Vec<Row>,
+}
+
+impl Group {
+    /// Number of rows with the change
+    pub fn count(&self, change: Change) -> usize {
+        self.rows.iter().filter(|row| row.change() == change).count()
+    }
+}
+
+/// Entries of a group in a file
+type EntriesFn = fn(ExeRef) -> Vec<Entry>;
+
+/// Compare the groups of entries of the two files, leaving out the groups
+/// neither file has
+pub fn compare(old: ExeRef, new: ExeRef) -> Vec<Group> {
+    let groups: [(&'static str, EntriesFn); 5] = [
+        ("Headers", headers),
+        ("Segments", segments),
+        ("Sections", sections),
+        ("Dynamic Entries", dynamic),
+        ("Symbols", symbols),
+    ];
+
+    groups
+        .iter()
+        .map(|(name, entries)| Group { name, rows: align(entries(old), entries(new)) })
+        .filter(|group| !group.rows.is_empty())
+        .collect()
+}
+
+/// Pair up the entries with the same key, keeping the order of the old
+/// entries with each added entry after the one before it in the new file
+pub fn align(old: Vec<Entry>, new: Vec<Entry>) -> Vec<Row> {
+    let new_idx: HashMap<&str, usize> = new.iter().enumerate().map(|(idx, e)| (e.key.as_str(), idx)).collect();
+    let old_keys: HashMap<&str, usize> = old.iter().enumerate().map(|(idx, e)| (e.key.as_str(), idx)).collect();
+
+    // Added entries by the old entry they follow, the first slot for those
+    // before any old entry
+    let mut added: Vec<Vec<Row>> = vec![Vec::new(); old.len() + 1];
+    let mut after = 0;
+    for entry in &new {
+        match old_keys.get(entry.key.as_str()) {
+            Some(&idx) => after = idx + 1,
+            None => added[after].push(Row { key: entry.key.clone(), old: None, new: Some(entry.value.clone()) }),
+        }
+    }
+
+    let mut rows: Vec<Row> = added[0].drain(..).collect();
+    for (idx, entry) in old.iter().enumerate() {
+        rows.push(Row {
+            key: entry.key.clone(),
+            old: Some(entry.value.clone()),
+            new: new_idx.get(entry.key.as_str()).map(|&n| new[n].value.clone()),
+        });
+        rows.append(&mut added[idx + 1]);
+    }
+    rows
+}
+
+/// Make the keys unique by numbering the repeats
+fn entries(items: impl Iterator<Item = (String, String)>) -> Vec<Entry> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    items
+        .map(|(key, value)| {
+            let count = seen.entry(key.clone()).or_default();
+            *count += 1;
+            match *count {
+                1 => Entry { key, value },
+                n => Entry { key: format!("{} #{}", key, n), value },
+            }
+        })
+        .collect()
+}
+
+// ------------------------------------------------------------------------
+
+/// Structures compared in groups of their own
+const OWN_GROUP: &[&str] = &["Program Header", "Section Header"];
+
+fn headers(exe: ExeRef) -> Vec<Entry> {
+    fn flatten<'s>(structures: &'s [Structure], flat: &mut Vec<&'s Structure>) {
+        for structure in structures.iter().filter(|s| !OWN_GROUP.contains(&s.name.as_str())) {
+            flat.push(structure);
+            flatten(&structure.children, flat);
+        }
+    }
+
+    let file = structures::decode(exe);
+    let mut flat = Vec::new();
+    flatten(&file.structures, &mut flat);
+    entries(flat.into_iter().flat_map(|structure| {
+        structure.fields.iter().map(|field| {
+            let value = match &field.description {
+                Some(desc) => format!("{} ({})", field.value, desc),
+                None => field.value.clone(),
+            };
+            (format!("{}: {}", structure.name, field.name), value)
+        })
+    }))
+}
+
+fn segments(exe: ExeRef) -> Vec<Entry> {
+    entries(exe.segments().iter().map(|segment| {
+        let value = format!(
+            "{:#x}, {} bytes at {:#x}, {}",
+            segment.addr, segment.file_size, segment.offset, segment.permissions(),
+        );
+        (segment.name.clone(), value)
+    }))
+}
+
+fn sections(exe: ExeRef) -> Vec<Entry> {
+    entries(exe.sections().iter().filter(|s| !s.name.is_empty()).map(|section| {
+        (section.full_name(), format!("{:#x}, {} bytes, flags {:#x}", section.addr, section.size, section.flags))
+    }))
+}
+
+fn dynamic(exe: ExeRef) -> Vec<Entry> {
+    if !exe.mmap().starts_with(b"\x7fELF") {
+        return Vec::new();
+    }
+    entries(elf::dynamic_entries(exe).into_iter().map(|DynamicEntry { tag, value, string }| {
+        let name = DYNAMIC_TAGS
+            .iter()
+            .find(|t| t.0 == tag)
+            .map_or_else(|| format!("{:#x}", tag), |t| t.1.to_string());
+        match (tag, string) {
+            // Libraries are told apart by name rather than by their order
+            (DT_NEEDED, Some(library)) => (format!("{} {}", name, library), String::from("needed")),
+            (_, Some(string)) => (name, string),
+            (_, None) => (name, format!("{:#x}", value)),
+        }
+    }))
+}
+
+fn symbols(exe: ExeRef) -> Vec<Entry> {
+    entries(exe.symbols().iter().filter(|s| !s.name.is_empty()).map(|symbol| {
+        let key = match symbol.dynamic {
+            true => format!("{} (dynamic)", symbol.name),
+            false => symbol.name.clone(),
+        };
+        let kind = match symbol.kind {
+            SymbolKind::Function => "function",
+            SymbolKind::Object => "object",
+            SymbolKind::Other => "other",
+        };
+        let value = format!(
+            "{} {}, {}, {} bytes",
+            if symbol.global { "global" } else { "local" },
+            kind,
+            if symbol.defined { "defined" } else { "imported" },
+            symbol.size,
+        );
+        (key, value)
+    }))
+}
+
+// ------------------------------------------------------------------------
+
+const DYNAMIC_TAGS: &[(u64, &str)] = &[
+    (1, "NEEDED"),
+    (2, "PLTRELSZ"),
+    (3, "PLTGOT"),
+    (4, "HASH"),
+    (5, "STRTAB"),
+    (6, "SYMTAB"),
+    (7, "RELA"),
+    (8, "RELASZ"),
+    (9, "RELAENT"),
+    (10, "STRSZ"),
+    (11, "SYMENT"),
+    (12, "INIT"),
+    (13, "FINI"),
+    (14, "SONAME"),
+    (15, "RPATH"),
+    (16, "SYMBOLIC"),
+    (17, "REL"),
+    (18, "RELSZ"),
+    (19, "RELENT"),
+    (20, "PLTREL"),
+    (21, "DEBUG"),
+    (22, "TEXTREL"),
+    (23, "JMPREL"),
+    (24, "BIND_NOW"),
+    (25, "INIT_ARRAY"),
+    (26, "FINI_ARRAY"),
+    (27, "INIT_ARRAYSZ"),
+    (28, "FINI_ARRAYSZ"),
+    (29, "RUNPATH"),
+    (30, "FLAGS"),
+    (32, "PREINIT_ARRAY"),
+    (33, "PREINIT_ARRAYSZ"),
+    (0x6ffffef5, "GNU_HASH"),
+    (0x6ffffff0, "VERSYM"),
+    (0x6ffffff9, "RELACOUNT"),
+    (0x6ffffffa, "RELCOUNT"),
+    (0x6ffffffb, "FLAGS_1"),
+    (0x6ffffffc, "VERDEF"),
+    (0x6ffffffd, "VERDEFNUM"),
+    (0x6ffffffe, "VERNEED"),
+    (0x6fffffff, "VERNEEDNUM"),
+];
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, value: &str) -> Entry {
+        Entry { key: key.into(), value: value.into() }
+    }
+
+    #[test]
+    fn alignment() {
+        let old = Vec::from([entry(".init", "1"), entry(".text", "2"), entry(".old", "3"), entry(".data", "4")]);
+        let new = Vec::from([entry(".first", "0"), entry(".init", "1"), entry(".text", "5"), entry(".new", "6"), entry(".data", "4")]);
+
+        let rows = align(old, new);
+        let found: Vec<_> = rows.iter().map(|r| (r.key.as_str(), r.change())).collect();
+        assert_eq!(found, [
+            (".first", Change::Added),
+            (".init", Change::Same),
+            (".text", Change::Changed),
+            (".new", Change::Added),
+            (".old", Change::Removed),
+            (".data", Change::Same),
+        ]);
+        assert_eq!(rows[2].old.as_deref(), Some("2"));
+        assert_eq!(rows[2].new.as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn repeated_keys() {
+        let found = entries([("LOAD", "a"), ("NOTE", "b"), ("LOAD", "c")].into_iter().map(|(k, v)| (k.into(), v.into())));
+        assert_eq!(found, [entry("LOAD", "a"), entry("NOTE", "b"), entry("LOAD #2", "c")]);
+    }
+}
@@ -0,0 +1,272 @@
+//!
+//! Instruction decoding of the code sections, using Capstone
+//!
+//! - <https://www.capstone-engine.org/>
+//!
+
+use anyhow::{anyhow, Result};
+use capstone::{arch::{self, ArchOperand, BuildsCapstone}, Capstone};
+use std::{collections::BTreeMap, fmt};
+
+use crate::exe_types::{Architecture, ExeRef, Section, Symbol, SymbolKind};
+
+// ------------------------------------------------------------------------
+
+/// Instruction set of the code in an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86,
+    X86_64,
+    AArch64,
+}
+
+impl Arch {
+
+    /// The instruction sets Capstone is set up for
+    pub fn from_exe(exe: ExeRef) -> Option<Self> {
+        match exe.architecture()? {
+            Architecture::X86 => Some(Self::X86),
+            Architecture::X86_64 => Some(Self::X86_64),
+            Architecture::AArch64 => Some(Self::AArch64),
+            _ => None,
+        }
+    }
+
+    /// Bytes in the longest instruction, also the most shown in a line
+    pub fn max_len(self) -> usize {
+        match self {
+            Self::X86 | Self::X86_64 => 15,
+            Self::AArch64 => 4,
+        }
+    }
+
+    /// Bytes skipped over when an instruction cannot be decoded
+    fn step(self) -> usize {
+        match self {
+            Self::X86 | Self::X86_64 => 1,
+            Self::AArch64 => 4,
+        }
+    }
+
+    fn capstone(self) -> Result<Capstone> {
+        let cs = match self {
+            Self::X86 => Capstone::new().x86().mode(arch::x86::ArchMode::Mode32).detail(true).build(),
+            Self::X86_64 => Capstone::new().x86().mode(arch::x86::ArchMode::Mode64).detail(true).build(),
+            Self::AArch64 => Capstone::new().arm64().mode(arch::arm64::ArchMode::Arm).detail(true).build(),
+        };
+        cs.map_err(|err| anyhow!("Unable to create the disassembler: {}", err))
+    }
+
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::X86 => "x86",
+            Self::X86_64 => "x86-64",
+            Self::AArch64 => "AArch64",
+        })
+    }
+}
+
+// Section flags marking code
+const SHF_EXECINSTR: u64 = 0x4;
+const S_ATTR_PURE_INSTRUCTIONS: u64 = 0x8000_0000;
+const S_ATTR_SOME_INSTRUCTIONS: u64 = 0x400;
+const IMAGE_SCN_CNT_CODE: u64 = 0x20;
+const IMAGE_SCN_MEM_EXECUTE: u64 = 0x2000_0000;
+
+// Capstone instruction groups, `cs_group_type`
+const CS_GRP_JUMP: u8 = 1;
+const CS_GRP_CALL: u8 = 2;
+const CS_GRP_RET: u8 = 3;
+const CS_GRP_BRANCH_RELATIVE: u8 = 7;
+
+// ------------------------------------------------------------------------
+
+/// How an instruction passes control on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Next,
+    Jump,
+    Call,
+    Return,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub addr: u64,
+    /// File offset of the first byte
+    pub offset: usize,
+    /// Bytes in the instruction, from `offset` in the file
+    pub len: usize,
+    pub mnemonic: String,
+    pub operands: String,
+    pub flow: Flow,
+    /// Destination of a direct jump or call
+    pub target: Option<u64>,
+}
+
+pub fn is_present(exe: ExeRef) -> bool {
+    Arch::from_exe(exe).is_some() && !code_sections(exe).is_empty()
+}
+
+/// Sections holding instructions that are present in the file
+pub fn code_sections(exe: ExeRef) -> Vec<Section> {
+    let code_flags = match exe.mmap().get(..4) {
+        Some([0x7f, b'E', b'L', b'F']) => SHF_EXECINSTR,
+        Some([b'M', b'Z', ..]) => IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE,
+        Some(_) => S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS,
+        None => 0,
+    };
+    exe.sections()
+        .into_iter()
+        .filter(|s| s.flags & code_flags != 0 && s.file_size > 0)
+        .collect()
+}
+
+/// Decode the instructions of a section. Bytes that do not decode are
+/// shown as `.byte` or `.inst` and decoding carries on after them.
+pub fn disassemble(exe: ExeRef, arch: Arch, section: &Section) -> Result<Vec<Instruction>> {
+    let data = section
+        .data(exe.mmap())
+        .ok_or_else(|| anyhow!("Section {} is outside the file", section.full_name()))?;
+    let cs = arch.capstone()?;
+
+    let mut insns = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let decoded = cs
+            .disasm_all(&data[pos..], section.addr.wrapping_add(pos as u64))
+            .map_err(|err| anyhow!("Unable to disassemble {}: {}", section.full_name(), err))?;
+        for insn in decoded.iter() {
+            let (flow, target) = match cs.insn_detail(&insn) {
+                Ok(detail) => {
+                    let groups: Vec<u8> = detail.groups().map(|g| g.0).collect();
+                    let flow = if groups.contains(&CS_GRP_CALL) {
+                        Flow::Call
+                    } else if groups.contains(&CS_GRP_JUMP) {
+                        Flow::Jump
+                    } else if groups.contains(&CS_GRP_RET) {
+                        Flow::Return
+                    } else {
+                        Flow::Next
+                    };
+                    let target = if groups.contains(&CS_GRP_BRANCH_RELATIVE) {
+                        detail.arch_detail().operands().iter().rev().find_map(immediate)
+                    } else {
+                        None
+                    };
+                    (flow, target)
+                }
+                Err(_) => (Flow::Next, None),
+            };
+            insns.push(Instruction {
+                addr: insn.address(),
+                offset: section.offset as usize + pos,
+                len: insn.bytes().len(),
+                mnemonic: insn.mnemonic().unwrap_or_default().to_string(),
+                operands: insn.op_str().unwrap_or_default().to_string(),
+                flow,
+                target,
+            });
+            pos += insn.bytes().len();
+        }
+
+        // Decoding stops at the first invalid instruction
+        if pos < data.len() {
+            let bytes = &data[pos..(pos + arch.step()).min(data.len())];
+            insns.push(undecoded(arch, section, pos, bytes));
+            pos += bytes.len();
+        }
+    }
+
+    Ok(insns)
+}
+
+fn immediate(op: &ArchOperand) -> Option<u64> {
+    match op {
+        ArchOperand::X86Operand(op) => match op.op_type {
+            arch::x86::X86OperandType::Imm(imm) => Some(imm as u64),
+            _ => None,
+        },
+        ArchOperand::Arm64Operand(op) => match op.op_type {
+            arch::arm64::Arm64OperandType::Imm(imm) => Some(imm as u64),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn undecoded(arch: Arch, section: &Section, pos: usize, bytes: &[u8]) -> Instruction {
+    let (mnemonic, operands) = match (arch, bytes) {
+        (Arch::AArch64, &[a, b, c, d]) => (".inst", format!("{:#010x}", u32::from_le_bytes([a, b, c, d]))),
+        _ => (".byte", bytes.iter().map(|b| format!("{:#04x}", b)).collect::<Vec<_>>().join(", ")),
+    };
+    Instruction {
+        addr: section.addr.wrapping_add(pos as u64),
+        offset: section.offset as usize + pos,
+        len: bytes.len(),
+        mnemonic: mnemonic.into(),
+        operands,
+        flow: Flow::Next,
+        target: None,
+    }
+}
+
+/// Names of the functions, by start address, to label the listing with.
+/// Global names are preferred where several symbols share an address.
+pub fn labels(symbols: &[Symbol]) -> BTreeMap<u64, &str> {
+    let mut labels: BTreeMap<u64, (&str, bool)> = BTreeMap::new();
+    for symbol in symbols
+        .iter()
+        .filter(|s| s.defined && s.kind == SymbolKind::Function && s.addr != 0 && !s.name.is_empty())
+    {
+        labels
+            .entry(symbol.addr)
+            .and_modify(|(name, global)| {
+                if symbol.global && !*global {
+                    *name = symbol.name.as_str();
+                    *global = true;
+                }
+            })
+            .or_insert((symbol.name.as_str(), symbol.global));
+    }
+    labels.into_iter().map(|(addr, (name, _))| (addr, name)).collect()
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(addr: u64, size: u64) -> Section {
+        Section { name: ".text".into(), addr, size, offset: 0, file_size: size, ..Default::default() }
+    }
+
+    #[test]
+    fn undecodable_bytes() {
+        // stp; bl; an invalid word; ret
+        let section = text(0x1000, 16);
+        let data = [0xfd, 0x7b, 0xbf, 0xa9, 0x02, 0x00, 0x00, 0x94, 0xff, 0xff, 0xff, 0xff, 0xc0, 0x03, 0x5f, 0xd6];
+        let cs = Arch::AArch64.capstone().unwrap();
+        assert_eq!(cs.disasm_all(&data, 0x1000).unwrap().len(), 2);
+
+        let insn = undecoded(Arch::AArch64, &section, 8, &data[8..12]);
+        assert_eq!((insn.addr, insn.mnemonic.as_str(), insn.operands.as_str()), (0x1008, ".inst", "0xffffffff"));
+        let insn = undecoded(Arch::X86_64, &section, 8, &data[8..9]);
+        assert_eq!(insn.operands, "0xff");
+    }
+
+    #[test]
+    fn preferred_labels() {
+        let symbol = |name: &str, addr, global| Symbol {
+            name: name.into(), addr, kind: SymbolKind::Function, global, defined: true, ..Default::default()
+        };
+        let symbols = [symbol("local", 0x10, false), symbol("global", 0x10, true), symbol("other", 0x20, false)];
+        let labels = labels(&symbols);
+        assert_eq!(labels.get(&0x10), Some(&"global"));
+        assert_eq!(labels.get(&0x20), Some(&"other"));
+    }
+}
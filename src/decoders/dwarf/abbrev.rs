@@ -0,0 +1,62 @@
+//!
+//! `.debug_abbrev` abbreviation tables
+//!
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::decoders::reader::{Endian, Reader};
+
+use super::constants::DW_FORM_IMPLICIT_CONST;
+
+// ------------------------------------------------------------------------
+
+/// Attribute specification, implicit constants carry their value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttrSpec {
+    pub name: u64,
+    pub form: u64,
+    pub implicit_const: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbrev {
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<AttrSpec>,
+}
+
+/// Abbreviations keyed by code
+pub type Abbrevs = HashMap<u64, Abbrev>;
+
+// ------------------------------------------------------------------------
+
+/// Parse the table starting at `offset`
+pub fn parse(data: &[u8], offset: usize) -> Result<Abbrevs> {
+    let mut rdr = Reader::at(data, offset, Endian::Little);
+    let mut abbrevs = HashMap::new();
+
+    loop {
+        let code = rdr.uleb128()?;
+        if code == 0 {
+            break;
+        }
+        let tag = rdr.uleb128()?;
+        let has_children = rdr.u8()? != 0;
+
+        let mut attrs = Vec::new();
+        loop {
+            let name = rdr.uleb128()?;
+            let form = rdr.uleb128()?;
+            if name == 0 && form == 0 {
+                break;
+            }
+            let implicit_const = if form == DW_FORM_IMPLICIT_CONST { rdr.sleb128()? } else { 0 };
+            attrs.push(AttrSpec { name, form, implicit_const });
+        }
+
+        abbrevs.insert(code, Abbrev { tag, has_children, attrs });
+    }
+
+    Ok(abbrevs)
+}
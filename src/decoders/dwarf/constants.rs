@@ -0,0 +1,371 @@
+//!
+//! DWARF tag, attribute, form and language names
+//!
+//! - <https://dwarfstd.org/doc/DWARF5.pdf> section 7
+//!
+
+use crate::formatter::ValTable;
+
+// ------------------------------------------------------------------------
+
+/// Name of `value` in `tbl`, or the value in hex
+pub fn name(tbl: &ValTable, value: u64) -> String {
+    tbl.iter()
+        .find(|v| v.0 as u64 == value)
+        .map_or_else(|| format!("{:#x}", value), |v| v.1.into())
+}
+
+// ------------------------------------------------------------------------
+
+pub const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+pub const DW_TAG_INLINED_SUBROUTINE: u64 = 0x1d;
+pub const DW_TAG_SUBPROGRAM: u64 = 0x2e;
+pub const DW_TAG_SKELETON_UNIT: u64 = 0x4a;
+
+pub const TAGS: &ValTable = &[
+    (0x01, "DW_TAG_array_type", None),
+    (0x02, "DW_TAG_class_type", None),
+    (0x03, "DW_TAG_entry_point", None),
+    (0x04, "DW_TAG_enumeration_type", None),
+    (0x05, "DW_TAG_formal_parameter", None),
+    (0x08, "DW_TAG_imported_declaration", None),
+    (0x0a, "DW_TAG_label", None),
+    (0x0b, "DW_TAG_lexical_block", None),
+    (0x0d, "DW_TAG_member", None),
+    (0x0f, "DW_TAG_pointer_type", None),
+    (0x10, "DW_TAG_reference_type", None),
+    (0x11, "DW_TAG_compile_unit", None),
+    (0x12, "DW_TAG_string_type", None),
+    (0x13, "DW_TAG_structure_type", None),
+    (0x15, "DW_TAG_subroutine_type", None),
+    (0x16, "DW_TAG_typedef", None),
+    (0x17, "DW_TAG_union_type", None),
+    (0x18, "DW_TAG_unspecified_parameters", None),
+    (0x19, "DW_TAG_variant", None),
+    (0x1a, "DW_TAG_common_block", None),
+    (0x1b, "DW_TAG_common_inclusion", None),
+    (0x1c, "DW_TAG_inheritance", None),
+    (0x1d, "DW_TAG_inlined_subroutine", None),
+    (0x1e, "DW_TAG_module", None),
+    (0x1f, "DW_TAG_ptr_to_member_type", None),
+    (0x20, "DW_TAG_set_type", None),
+    (0x21, "DW_TAG_subrange_type", None),
+    (0x22, "DW_TAG_with_stmt", None),
+    (0x23, "DW_TAG_access_declaration", None),
+    (0x24, "DW_TAG_base_type", None),
+    (0x25, "DW_TAG_catch_block", None),
+    (0x26, "DW_TAG_const_type", None),
+    (0x27, "DW_TAG_constant", None),
+    (0x28, "DW_TAG_enumerator", None),
+    (0x29, "DW_TAG_file_type", None),
+    (0x2a, "DW_TAG_friend", None),
+    (0x2b, "DW_TAG_namelist", None),
+    (0x2c, "DW_TAG_namelist_item", None),
+    (0x2d, "DW_TAG_packed_type", None),
+    (0x2e, "DW_TAG_subprogram", None),
+    (0x2f, "DW_TAG_template_type_parameter", None),
+    (0x30, "DW_TAG_template_value_parameter", None),
+    (0x31, "DW_TAG_thrown_type", None),
+    (0x32, "DW_TAG_try_block", None),
+    (0x33, "DW_TAG_variant_part", None),
+    (0x34, "DW_TAG_variable", None),
+    (0x35, "DW_TAG_volatile_type", None),
+    (0x36, "DW_TAG_dwarf_procedure", None),
+    (0x37, "DW_TAG_restrict_type", None),
+    (0x38, "DW_TAG_interface_type", None),
+    (0x39, "DW_TAG_namespace", None),
+    (0x3a, "DW_TAG_imported_module", None),
+    (0x3b, "DW_TAG_unspecified_type", None),
+    (0x3c, "DW_TAG_partial_unit", None),
+    (0x3d, "DW_TAG_imported_unit", None),
+    (0x3f, "DW_TAG_condition", None),
+    (0x40, "DW_TAG_shared_type", None),
+    (0x41, "DW_TAG_type_unit", None),
+    (0x42, "DW_TAG_rvalue_reference_type", None),
+    (0x43, "DW_TAG_template_alias", None),
+    (0x44, "DW_TAG_coarray_type", None),
+    (0x45, "DW_TAG_generic_subrange", None),
+    (0x46, "DW_TAG_dynamic_type", None),
+    (0x47, "DW_TAG_atomic_type", None),
+    (0x48, "DW_TAG_call_site", None),
+    (0x49, "DW_TAG_call_site_parameter", None),
+    (0x4a, "DW_TAG_skeleton_unit", None),
+    (0x4b, "DW_TAG_immutable_type", None),
+    (0x4106, "DW_TAG_GNU_template_template_param", None),
+    (0x4107, "DW_TAG_GNU_template_parameter_pack", None),
+    (0x4108, "DW_TAG_GNU_formal_parameter_pack", None),
+    (0x4109, "DW_TAG_GNU_call_site", None),
+    (0x410a, "DW_TAG_GNU_call_site_parameter", None),
+];
+
+// ------------------------------------------------------------------------
+
+pub const DW_AT_SIBLING: u64 = 0x01;
+pub const DW_AT_NAME: u64 = 0x03;
+pub const DW_AT_STMT_LIST: u64 = 0x10;
+pub const DW_AT_LOW_PC: u64 = 0x11;
+pub const DW_AT_HIGH_PC: u64 = 0x12;
+pub const DW_AT_LANGUAGE: u64 = 0x13;
+pub const DW_AT_COMP_DIR: u64 = 0x1b;
+pub const DW_AT_PRODUCER: u64 = 0x25;
+pub const DW_AT_ABSTRACT_ORIGIN: u64 = 0x31;
+pub const DW_AT_SPECIFICATION: u64 = 0x47;
+pub const DW_AT_RANGES: u64 = 0x55;
+pub const DW_AT_CALL_COLUMN: u64 = 0x57;
+pub const DW_AT_CALL_FILE: u64 = 0x58;
+pub const DW_AT_CALL_LINE: u64 = 0x59;
+pub const DW_AT_LINKAGE_NAME: u64 = 0x6e;
+pub const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+pub const DW_AT_ADDR_BASE: u64 = 0x73;
+pub const DW_AT_RNGLISTS_BASE: u64 = 0x74;
+pub const DW_AT_MIPS_LINKAGE_NAME: u64 = 0x2007;
+pub const DW_AT_GNU_ADDR_BASE: u64 = 0x2134;
+
+pub const ATTRIBUTES: &ValTable = &[
+    (0x01, "DW_AT_sibling", None),
+    (0x02, "DW_AT_location", None),
+    (0x03, "DW_AT_name", None),
+    (0x09, "DW_AT_ordering", None),
+    (0x0b, "DW_AT_byte_size", None),
+    (0x0d, "DW_AT_bit_size", None),
+    (0x10, "DW_AT_stmt_list", None),
+    (0x11, "DW_AT_low_pc", None),
+    (0x12, "DW_AT_high_pc", None),
+    (0x13, "DW_AT_language", None),
+    (0x15, "DW_AT_discr", None),
+    (0x16, "DW_AT_discr_value", None),
+    (0x17, "DW_AT_visibility", None),
+    (0x18, "DW_AT_import", None),
+    (0x19, "DW_AT_string_length", None),
+    (0x1a, "DW_AT_common_reference", None),
+    (0x1b, "DW_AT_comp_dir", None),
+    (0x1c, "DW_AT_const_value", None),
+    (0x1d, "DW_AT_containing_type", None),
+    (0x1e, "DW_AT_default_value", None),
+    (0x20, "DW_AT_inline", None),
+    (0x21, "DW_AT_is_optional", None),
+    (0x22, "DW_AT_lower_bound", None),
+    (0x25, "DW_AT_producer", None),
+    (0x27, "DW_AT_prototyped", None),
+    (0x2a, "DW_AT_return_addr", None),
+    (0x2c, "DW_AT_start_scope", None),
+    (0x2e, "DW_AT_bit_stride", None),
+    (0x2f, "DW_AT_upper_bound", None),
+    (0x31, "DW_AT_abstract_origin", None),
+    (0x32, "DW_AT_accessibility", None),
+    (0x33, "DW_AT_address_class", None),
+    (0x34, "DW_AT_artificial", None),
+    (0x35, "DW_AT_base_types", None),
+    (0x36, "DW_AT_calling_convention", None),
+    (0x37, "DW_AT_count", None),
+    (0x38, "DW_AT_data_member_location", None),
+    (0x39, "DW_AT_decl_column", None),
+    (0x3a, "DW_AT_decl_file", None),
+    (0x3b, "DW_AT_decl_line", None),
+    (0x3c, "DW_AT_declaration", None),
+    (0x3d, "DW_AT_discr_list", None),
+    (0x3e, "DW_AT_encoding", None),
+    (0x3f, "DW_AT_external", None),
+    (0x40, "DW_AT_frame_base", None),
+    (0x41, "DW_AT_friend", None),
+    (0x42, "DW_AT_identifier_case", None),
+    (0x43, "DW_AT_macro_info", None),
+    (0x44, "DW_AT_namelist_item", None),
+    (0x45, "DW_AT_priority", None),
+    (0x46, "DW_AT_segment", None),
+    (0x47, "DW_AT_specification", None),
+    (0x48, "DW_AT_static_link", None),
+    (0x49, "DW_AT_type", None),
+    (0x4a, "DW_AT_use_location", None),
+    (0x4b, "DW_AT_variable_parameter", None),
+    (0x4c, "DW_AT_virtuality", None),
+    (0x4d, "DW_AT_vtable_elem_location", None),
+    (0x4e, "DW_AT_allocated", None),
+    (0x4f, "DW_AT_associated", None),
+    (0x50, "DW_AT_data_location", None),
+    (0x51, "DW_AT_byte_stride", None),
+    (0x52, "DW_AT_entry_pc", None),
+    (0x53, "DW_AT_use_UTF8", None),
+    (0x54, "DW_AT_extension", None),
+    (0x55, "DW_AT_ranges", None),
+    (0x56, "DW_AT_trampoline", None),
+    (0x57, "DW_AT_call_column", None),
+    (0x58, "DW_AT_call_file", None),
+    (0x59, "DW_AT_call_line", None),
+    (0x5a, "DW_AT_description", None),
+    (0x5b, "DW_AT_binary_scale", None),
+    (0x5c, "DW_AT_decimal_scale", None),
+    (0x5d, "DW_AT_small", None),
+    (0x5e, "DW_AT_decimal_sign", None),
+    (0x5f, "DW_AT_digit_count", None),
+    (0x60, "DW_AT_picture_string", None),
+    (0x61, "DW_AT_mutable", None),
+    (0x62, "DW_AT_threads_scaled", None),
+    (0x63, "DW_AT_explicit", None),
+    (0x64, "DW_AT_object_pointer", None),
+    (0x65, "DW_AT_endianity", None),
+    (0x66, "DW_AT_elemental", None),
+    (0x67, "DW_AT_pure", None),
+    (0x68, "DW_AT_recursive", None),
+    (0x69, "DW_AT_signature", None),
+    (0x6a, "DW_AT_main_subprogram", None),
+    (0x6b, "DW_AT_data_bit_offset", None),
+    (0x6c, "DW_AT_const_expr", None),
+    (0x6d, "DW_AT_enum_class", None),
+    (0x6e, "DW_AT_linkage_name", None),
+    (0x6f, "DW_AT_string_length_bit_size", None),
+    (0x70, "DW_AT_string_length_byte_size", None),
+    (0x71, "DW_AT_rank", None),
+    (0x72, "DW_AT_str_offsets_base", None),
+    (0x73, "DW_AT_addr_base", None),
+    (0x74, "DW_AT_rnglists_base", None),
+    (0x76, "DW_AT_dwo_name", None),
+    (0x77, "DW_AT_reference", None),
+    (0x78, "DW_AT_rvalue_reference", None),
+    (0x79, "DW_AT_macros", None),
+    (0x7a, "DW_AT_call_all_calls", None),
+    (0x7b, "DW_AT_call_all_source_calls", None),
+    (0x7c, "DW_AT_call_all_tail_calls", None),
+    (0x7d, "DW_AT_call_return_pc", None),
+    (0x7e, "DW_AT_call_value", None),
+    (0x7f, "DW_AT_call_origin", None),
+    (0x80, "DW_AT_call_parameter", None),
+    (0x81, "DW_AT_call_pc", None),
+    (0x82, "DW_AT_call_tail_call", None),
+    (0x83, "DW_AT_call_target", None),
+    (0x84, "DW_AT_call_target_clobbered", None),
+    (0x85, "DW_AT_call_data_location", None),
+    (0x86, "DW_AT_call_data_value", None),
+    (0x87, "DW_AT_noreturn", None),
+    (0x88, "DW_AT_alignment", None),
+    (0x89, "DW_AT_export_symbols", None),
+    (0x8a, "DW_AT_deleted", None),
+    (0x8b, "DW_AT_defaulted", None),
+    (0x8c, "DW_AT_loclists_base", None),
+    (0x2007, "DW_AT_MIPS_linkage_name", None),
+    (0x2107, "DW_AT_GNU_template_name", None),
+    (0x2111, "DW_AT_GNU_call_site_value", None),
+    (0x2113, "DW_AT_GNU_call_site_target", None),
+    (0x2115, "DW_AT_GNU_tail_call", None),
+    (0x2116, "DW_AT_GNU_all_tail_call_sites", None),
+    (0x2117, "DW_AT_GNU_all_call_sites", None),
+    (0x2119, "DW_AT_GNU_macros", None),
+    (0x211a, "DW_AT_GNU_deleted", None),
+    (0x2131, "DW_AT_GNU_dwo_name", None),
+    (0x2132, "DW_AT_GNU_dwo_id", None),
+    (0x2133, "DW_AT_GNU_ranges_base", None),
+    (0x2134, "DW_AT_GNU_addr_base", None),
+    (0x2135, "DW_AT_GNU_pubnames", None),
+    (0x2136, "DW_AT_GNU_pubtypes", None),
+    (0x2137, "DW_AT_GNU_discriminator", None),
+    (0x2138, "DW_AT_GNU_locviews", None),
+    (0x2139, "DW_AT_GNU_entry_view", None),
+    (0x3e00, "DW_AT_LLVM_include_path", None),
+    (0x3fe1, "DW_AT_APPLE_optimized", None),
+    (0x3fe7, "DW_AT_APPLE_sdk", None),
+    (0x3fef, "DW_AT_APPLE_sysroot", None),
+];
+
+// ------------------------------------------------------------------------
+
+pub const DW_FORM_ADDR: u64 = 0x01;
+pub const DW_FORM_BLOCK2: u64 = 0x03;
+pub const DW_FORM_BLOCK4: u64 = 0x04;
+pub const DW_FORM_DATA2: u64 = 0x05;
+pub const DW_FORM_DATA4: u64 = 0x06;
+pub const DW_FORM_DATA8: u64 = 0x07;
+pub const DW_FORM_STRING: u64 = 0x08;
+pub const DW_FORM_BLOCK: u64 = 0x09;
+pub const DW_FORM_BLOCK1: u64 = 0x0a;
+pub const DW_FORM_DATA1: u64 = 0x0b;
+pub const DW_FORM_FLAG: u64 = 0x0c;
+pub const DW_FORM_SDATA: u64 = 0x0d;
+pub const DW_FORM_STRP: u64 = 0x0e;
+pub const DW_FORM_UDATA: u64 = 0x0f;
+pub const DW_FORM_REF_ADDR: u64 = 0x10;
+pub const DW_FORM_REF1: u64 = 0x11;
+pub const DW_FORM_REF2: u64 = 0x12;
+pub const DW_FORM_REF4: u64 = 0x13;
+pub const DW_FORM_REF8: u64 = 0x14;
+pub const DW_FORM_REF_UDATA: u64 = 0x15;
+pub const DW_FORM_INDIRECT: u64 = 0x16;
+pub const DW_FORM_SEC_OFFSET: u64 = 0x17;
+pub const DW_FORM_EXPRLOC: u64 = 0x18;
+pub const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+pub const DW_FORM_STRX: u64 = 0x1a;
+pub const DW_FORM_ADDRX: u64 = 0x1b;
+pub const DW_FORM_REF_SUP4: u64 = 0x1c;
+pub const DW_FORM_STRP_SUP: u64 = 0x1d;
+pub const DW_FORM_DATA16: u64 = 0x1e;
+pub const DW_FORM_LINE_STRP: u64 = 0x1f;
+pub const DW_FORM_REF_SIG8: u64 = 0x20;
+pub const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+pub const DW_FORM_LOCLISTX: u64 = 0x22;
+pub const DW_FORM_RNGLISTX: u64 = 0x23;
+pub const DW_FORM_REF_SUP8: u64 = 0x24;
+pub const DW_FORM_STRX1: u64 = 0x25;
+pub const DW_FORM_STRX2: u64 = 0x26;
+pub const DW_FORM_STRX3: u64 = 0x27;
+pub const DW_FORM_STRX4: u64 = 0x28;
+pub const DW_FORM_ADDRX1: u64 = 0x29;
+pub const DW_FORM_ADDRX2: u64 = 0x2a;
+pub const DW_FORM_ADDRX3: u64 = 0x2b;
+pub const DW_FORM_ADDRX4: u64 = 0x2c;
+pub const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1f01;
+pub const DW_FORM_GNU_STR_INDEX: u64 = 0x1f02;
+pub const DW_FORM_GNU_REF_ALT: u64 = 0x1f20;
+pub const DW_FORM_GNU_STRP_ALT: u64 = 0x1f21;
+
+// ------------------------------------------------------------------------
+
+pub const LANGUAGES: &ValTable = &[
+    (0x01, "C89", None),
+    (0x02, "C", None),
+    (0x03, "Ada83", None),
+    (0x04, "C++", None),
+    (0x05, "Cobol74", None),
+    (0x06, "Cobol85", None),
+    (0x07, "Fortran77", None),
+    (0x08, "Fortran90", None),
+    (0x09, "Pascal83", None),
+    (0x0a, "Modula2", None),
+    (0x0b, "Java", None),
+    (0x0c, "C99", None),
+    (0x0d, "Ada95", None),
+    (0x0e, "Fortran95", None),
+    (0x0f, "PLI", None),
+    (0x10, "ObjC", None),
+    (0x11, "ObjC++", None),
+    (0x12, "UPC", None),
+    (0x13, "D", None),
+    (0x14, "Python", None),
+    (0x15, "OpenCL", None),
+    (0x16, "Go", None),
+    (0x17, "Modula3", None),
+    (0x18, "Haskell", None),
+    (0x19, "C++03", None),
+    (0x1a, "C++11", None),
+    (0x1b, "OCaml", None),
+    (0x1c, "Rust", None),
+    (0x1d, "C11", None),
+    (0x1e, "Swift", None),
+    (0x1f, "Julia", None),
+    (0x20, "Dylan", None),
+    (0x21, "C++14", None),
+    (0x22, "Fortran03", None),
+    (0x23, "Fortran08", None),
+    (0x24, "RenderScript", None),
+    (0x25, "BLISS", None),
+    (0x26, "Kotlin", None),
+    (0x27, "Zig", None),
+    (0x28, "Crystal", None),
+    (0x2a, "C++17", None),
+    (0x2b, "C++20", None),
+    (0x2c, "C17", None),
+    (0x2d, "Fortran18", None),
+    (0x2e, "Ada2005", None),
+    (0x2f, "Ada2012", None),
+    (0x8001, "Mips Assembler", None),
+    (0x8e57, "GOOGLE RenderScript", None),
+    (0xb000, "BORLAND Delphi", None),
+];
@@ -0,0 +1,398 @@
+//!
+//! `.debug_info` units and debugging information entries
+//!
+
+use anyhow::{anyhow, bail, Result};
+use std::{collections::HashMap, rc::Rc};
+
+use crate::decoders::reader::{self, Reader};
+
+use super::{
+    abbrev::{self, Abbrevs, AttrSpec},
+    constants::*,
+    Dwarf, INVALID_STR,
+};
+
+// ------------------------------------------------------------------------
+
+const DW_UT_TYPE: u8 = 0x02;
+const DW_UT_SKELETON: u8 = 0x04;
+const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+/// Unit header
+#[derive(Debug, Clone)]
+pub struct Unit {
+    /// Offset of the header in `.debug_info`
+    pub offset: usize,
+    /// Offset of the next unit
+    pub end: usize,
+    pub version: u16,
+    pub unit_type: u8,
+    pub addr_size: u8,
+    /// 4 for 32 bit DWARF, 8 for 64 bit DWARF
+    pub offset_size: u8,
+    pub abbrev_offset: usize,
+    /// Offset of the unit entry
+    pub first_die: usize,
+    pub abbrevs: Rc<Abbrevs>,
+    pub str_offsets_base: u64,
+    pub addr_base: u64,
+    pub rnglists_base: u64,
+    /// `DW_AT_low_pc` of the unit entry, the base for its range lists
+    pub base_address: u64,
+}
+
+/// Debugging information entry
+#[derive(Debug, Clone)]
+pub struct Die<'d> {
+    pub offset: usize,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<Attr<'d>>,
+    /// Offset following the attributes, the first child if there are any
+    pub next: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Attr<'d> {
+    pub name: u64,
+    pub form: u64,
+    pub value: AttrValue<'d>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrValue<'d> {
+    Addr(u64),
+    AddrIndex(u64),
+    Udata(u64),
+    Sdata(i64),
+    Block(&'d [u8]),
+    Flag(bool),
+    Str(&'d str),
+    StrIndex(u64),
+    /// Offset of the referenced entry in `.debug_info`
+    Ref(usize),
+    SecOffset(u64),
+    Signature(u64),
+}
+
+impl<'d> Die<'d> {
+    pub fn attr(&self, name: u64) -> Option<&Attr<'d>> {
+        self.attrs.iter().find(|a| a.name == name)
+    }
+}
+
+impl Attr<'_> {
+    /// Value of a constant or section offset
+    pub fn udata(&self) -> Option<u64> {
+        match self.value {
+            AttrValue::Udata(v) | AttrValue::SecOffset(v) => Some(v),
+            AttrValue::Sdata(v) => u64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+
+impl<'d> Dwarf<'d> {
+
+    /// Parse every unit header in `.debug_info`
+    pub fn units(&'d self) -> Result<Vec<Unit>> {
+        let mut units = Vec::new();
+        let mut cache: HashMap<usize, Rc<Abbrevs>> = HashMap::new();
+        let mut offset = 0;
+
+        while offset < self.info.len() {
+            let unit = self.unit(offset, &mut cache)?;
+            offset = unit.end;
+            units.push(unit);
+        }
+
+        Ok(units)
+    }
+
+    fn unit(&'d self, offset: usize, cache: &mut HashMap<usize, Rc<Abbrevs>>) -> Result<Unit> {
+        let mut rdr = self.reader(&self.info, offset);
+
+        let (length, offset_size) = match rdr.u32()? {
+            0xffffffff => (rdr.u64()?, 8),
+            v if v >= 0xfffffff0 => bail!("Reserved unit length {:#x} at {:#x}", v, offset),
+            v => (u64::from(v), 4),
+        };
+        let end = rdr.pos()
+            .checked_add(usize::try_from(length)?)
+            .filter(|end| *end <= self.info.len())
+            .ok_or_else(|| anyhow!("Unit at {:#x} extends past .debug_info", offset))?;
+
+        let version = rdr.u16()?;
+        let (unit_type, addr_size, abbrev_offset) = match version {
+            2..=4 => {
+                let abbrev_offset = rdr.uint(offset_size)?;
+                (0, rdr.u8()?, abbrev_offset)
+            }
+            5 => {
+                let unit_type = rdr.u8()?;
+                let addr_size = rdr.u8()?;
+                let abbrev_offset = rdr.uint(offset_size)?;
+                match unit_type {
+                    DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => rdr.skip(8)?,
+                    DW_UT_TYPE | DW_UT_SPLIT_TYPE => rdr.skip(8 + offset_size)?,
+                    _ => {}
+                }
+                (unit_type, addr_size, abbrev_offset)
+            }
+            v => bail!("Unsupported DWARF version {} at {:#x}", v, offset),
+        };
+
+        let abbrev_offset = usize::try_from(abbrev_offset)?;
+        let abbrevs = match cache.get(&abbrev_offset) {
+            Some(a) => a.clone(),
+            None => {
+                let a = Rc::new(abbrev::parse(&self.abbrev, abbrev_offset)?);
+                cache.insert(abbrev_offset, a.clone());
+                a
+            }
+        };
+
+        let mut unit = Unit {
+            offset, end, version, unit_type, addr_size, abbrev_offset, abbrevs,
+            offset_size: offset_size as u8,
+            first_die: rdr.pos(),
+            // Default to just past the header of the offsets and address tables
+            str_offsets_base: if version >= 5 { 2 * offset_size as u64 } else { 0 },
+            addr_base: if version >= 5 { 2 * offset_size as u64 } else { 0 },
+            rnglists_base: if version >= 5 { 2 * offset_size as u64 + 4 } else { 0 },
+            base_address: 0,
+        };
+
+        if let Some(die) = self.die(&unit, unit.first_die)? {
+            let base = |names: &[u64]| names.iter()
+                .find_map(|n| die.attr(*n))
+                .and_then(|a| a.udata());
+            if let Some(v) = base(&[DW_AT_STR_OFFSETS_BASE]) {
+                unit.str_offsets_base = v;
+            }
+            if let Some(v) = base(&[DW_AT_ADDR_BASE, DW_AT_GNU_ADDR_BASE]) {
+                unit.addr_base = v;
+            }
+            if let Some(v) = base(&[DW_AT_RNGLISTS_BASE]) {
+                unit.rnglists_base = v;
+            }
+            if let Some(v) = die.attr(DW_AT_LOW_PC).and_then(|a| self.attr_address(&unit, a)) {
+                unit.base_address = v;
+            }
+        }
+
+        Ok(unit)
+    }
+
+    // --------------------------------------------------------------------
+
+    /// Entry at `offset`, `None` for the null entry ending a list of children
+    pub fn die(&'d self, unit: &Unit, offset: usize) -> Result<Option<Die<'d>>> {
+        let mut rdr = self.reader(&self.info[..unit.end], offset);
+
+        let code = rdr.uleb128()?;
+        if code == 0 {
+            return Ok(None);
+        }
+        let abbrev = unit.abbrevs
+            .get(&code)
+            .ok_or_else(|| anyhow!("Unknown abbreviation {} at {:#x}", code, offset))?;
+
+        let attrs = abbrev.attrs
+            .iter()
+            .map(|spec| self.attr(unit, &mut rdr, spec))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Die {
+            offset,
+            tag: abbrev.tag,
+            has_children: abbrev.has_children,
+            attrs,
+            next: rdr.pos(),
+        }))
+    }
+
+    /// The immediate children of `die`
+    pub fn children(&'d self, unit: &Unit, die: &Die) -> Result<Vec<Die<'d>>> {
+        let mut children = Vec::new();
+        if !die.has_children {
+            return Ok(children);
+        }
+
+        let mut pos = die.next;
+        while let Some(child) = self.die(unit, pos)? {
+            pos = self.skip(unit, &child)?;
+            children.push(child);
+        }
+
+        Ok(children)
+    }
+
+    /// Offset following `die` and all of its descendants
+    pub fn skip(&'d self, unit: &Unit, die: &Die) -> Result<usize> {
+        let mut pos = die.next;
+        let mut depth = usize::from(die.has_children);
+        while depth > 0 {
+            match self.die(unit, pos)? {
+                None => {
+                    depth -= 1;
+                    pos += 1;
+                }
+                Some(d) => {
+                    pos = d.next;
+                    depth += usize::from(d.has_children);
+                }
+            }
+        }
+        Ok(pos)
+    }
+
+    // --------------------------------------------------------------------
+
+    fn attr(&'d self, unit: &Unit, rdr: &mut Reader<'d>, spec: &AttrSpec) -> Result<Attr<'d>> {
+        let value = self.form_value(unit, rdr, spec.form, spec.implicit_const)?;
+        Ok(Attr { name: spec.name, form: spec.form, value })
+    }
+
+    fn form_value(
+        &'d self,
+        unit: &Unit,
+        rdr: &mut Reader<'d>,
+        form: u64,
+        implicit_const: i64,
+    ) -> Result<AttrValue<'d>> {
+        let offset_size = usize::from(unit.offset_size);
+        let unit_ref = |v: u64| -> Result<AttrValue> {
+            unit.offset
+                .checked_add(usize::try_from(v)?)
+                .map(AttrValue::Ref)
+                .ok_or_else(|| anyhow!("Reference {:#x} overflows in the unit at {:#x}", v, unit.offset))
+        };
+
+        Ok(match form {
+            DW_FORM_ADDR => AttrValue::Addr(rdr.uint(unit.addr_size.into())?),
+            DW_FORM_BLOCK1 => { let len = rdr.u8()?.into(); AttrValue::Block(rdr.bytes(len)?) }
+            DW_FORM_BLOCK2 => { let len = rdr.u16()?.into(); AttrValue::Block(rdr.bytes(len)?) }
+            DW_FORM_BLOCK4 => { let len = rdr.u32()? as usize; AttrValue::Block(rdr.bytes(len)?) }
+            DW_FORM_BLOCK | DW_FORM_EXPRLOC => {
+                let len = usize::try_from(rdr.uleb128()?)?;
+                AttrValue::Block(rdr.bytes(len)?)
+            }
+            DW_FORM_DATA1 => AttrValue::Udata(rdr.u8()?.into()),
+            DW_FORM_DATA2 => AttrValue::Udata(rdr.u16()?.into()),
+            DW_FORM_DATA4 => AttrValue::Udata(rdr.u32()?.into()),
+            DW_FORM_DATA8 => AttrValue::Udata(rdr.u64()?),
+            DW_FORM_DATA16 => AttrValue::Block(rdr.bytes(16)?),
+            DW_FORM_SDATA => AttrValue::Sdata(rdr.sleb128()?),
+            DW_FORM_UDATA | DW_FORM_LOCLISTX | DW_FORM_RNGLISTX => AttrValue::Udata(rdr.uleb128()?),
+            DW_FORM_IMPLICIT_CONST => AttrValue::Sdata(implicit_const),
+            DW_FORM_FLAG => AttrValue::Flag(rdr.u8()? != 0),
+            DW_FORM_FLAG_PRESENT => AttrValue::Flag(true),
+            DW_FORM_STRING => AttrValue::Str(rdr.cstr().unwrap_or(INVALID_STR)),
+            DW_FORM_STRP => AttrValue::Str(self.string(rdr.uint(offset_size)?)),
+            DW_FORM_LINE_STRP => AttrValue::Str(self.line_string(rdr.uint(offset_size)?)),
+            DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => AttrValue::StrIndex(rdr.uleb128()?),
+            DW_FORM_STRX1 => AttrValue::StrIndex(rdr.u8()?.into()),
+            DW_FORM_STRX2 => AttrValue::StrIndex(rdr.u16()?.into()),
+            DW_FORM_STRX3 => AttrValue::StrIndex(u24(rdr)?),
+            DW_FORM_STRX4 => AttrValue::StrIndex(rdr.u32()?.into()),
+            DW_FORM_ADDRX | DW_FORM_GNU_ADDR_INDEX => AttrValue::AddrIndex(rdr.uleb128()?),
+            DW_FORM_ADDRX1 => AttrValue::AddrIndex(rdr.u8()?.into()),
+            DW_FORM_ADDRX2 => AttrValue::AddrIndex(rdr.u16()?.into()),
+            DW_FORM_ADDRX3 => AttrValue::AddrIndex(u24(rdr)?),
+            DW_FORM_ADDRX4 => AttrValue::AddrIndex(rdr.u32()?.into()),
+            DW_FORM_REF1 => unit_ref(rdr.u8()?.into())?,
+            DW_FORM_REF2 => unit_ref(rdr.u16()?.into())?,
+            DW_FORM_REF4 => unit_ref(rdr.u32()?.into())?,
+            DW_FORM_REF8 => unit_ref(rdr.u64()?)?,
+            DW_FORM_REF_UDATA => unit_ref(rdr.uleb128()?)?,
+            DW_FORM_REF_ADDR => {
+                let size = if unit.version <= 2 { unit.addr_size.into() } else { offset_size };
+                AttrValue::Ref(usize::try_from(rdr.uint(size)?)?)
+            }
+            DW_FORM_REF_SIG8 => AttrValue::Signature(rdr.u64()?),
+            DW_FORM_SEC_OFFSET | DW_FORM_STRP_SUP | DW_FORM_GNU_STRP_ALT | DW_FORM_GNU_REF_ALT => {
+                AttrValue::SecOffset(rdr.uint(offset_size)?)
+            }
+            DW_FORM_REF_SUP4 => AttrValue::SecOffset(rdr.u32()?.into()),
+            DW_FORM_REF_SUP8 => AttrValue::SecOffset(rdr.u64()?),
+            DW_FORM_INDIRECT => {
+                let form = rdr.uleb128()?;
+                if form == DW_FORM_INDIRECT {
+                    bail!("Nested DW_FORM_indirect at {:#x}", rdr.pos());
+                }
+                self.form_value(unit, rdr, form, implicit_const)?
+            }
+            v => bail!("Unknown attribute form {:#x} at {:#x}", v, rdr.pos()),
+        })
+    }
+
+}
+
+fn u24(rdr: &mut Reader) -> Result<u64> {
+    let b = rdr.bytes(3)?;
+    Ok(match rdr.endian {
+        reader::Endian::Little => u64::from(b[0]) | u64::from(b[1]) << 8 | u64::from(b[2]) << 16,
+        reader::Endian::Big => u64::from(b[2]) | u64::from(b[1]) << 8 | u64::from(b[0]) << 16,
+    })
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::reader::Endian;
+
+    fn dwarf<'d>(info: &'d [u8], abbrev: &'d [u8], str: &'d [u8]) -> Dwarf<'d> {
+        Dwarf { info: info.into(), abbrev: abbrev.into(), str: str.into(), ..Dwarf::empty(Endian::Little) }
+    }
+
+    #[test]
+    fn unit_tree() {
+        let abbrev = [
+            // 1: compile_unit, children, name strp, language data1
+            1, 0x11, 1, 0x03, 0x0e, 0x13, 0x0b, 0, 0,
+            // 2: subprogram, no children, name string, low_pc addr
+            2, 0x2e, 0, 0x03, 0x08, 0x11, 0x01, 0, 0,
+            0,
+        ];
+        let str = b"main.c\0";
+        let info = [
+            21, 0, 0, 0,            // unit length
+            4, 0,                   // version
+            0, 0, 0, 0,             // abbrev offset
+            4,                      // address size
+            1, 0, 0, 0, 0, 0x0c,    // compile unit
+            2, b'f', 0, 0x10, 0, 0, 0, // subprogram
+            0,
+        ];
+        let dwarf = dwarf(&info, &abbrev, str);
+
+        let units = dwarf.units().unwrap();
+        assert_eq!(units.len(), 1);
+        let unit = &units[0];
+        assert_eq!((unit.version, unit.addr_size, unit.end), (4, 4, 25));
+
+        let summary = dwarf.unit_summary(unit).unwrap();
+        assert_eq!(summary.name, Some("main.c"));
+        assert_eq!(summary.language.as_deref(), Some("C99"));
+
+        let cu = dwarf.die(unit, unit.first_die).unwrap().unwrap();
+        let children = dwarf.children(unit, &cu).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].tag, DW_TAG_SUBPROGRAM);
+        assert_eq!(children[0].attr(DW_AT_LOW_PC).unwrap().value, AttrValue::Addr(0x10));
+        assert_eq!(dwarf.skip(unit, &cu).unwrap(), unit.end);
+    }
+
+    #[test]
+    fn truncated() {
+        let info = [0xff, 0, 0, 0, 4, 0];
+        assert!(dwarf(&info, &[], &[]).units().is_err());
+    }
+}
@@ -0,0 +1,358 @@
+//!
+//! `.debug_line` line number programs
+//!
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::decoders::reader::Reader;
+
+use super::{constants::*, Dwarf, INVALID_STR};
+
+// ------------------------------------------------------------------------
+
+const DW_LNS_COPY: u8 = 0x01;
+const DW_LNS_ADVANCE_PC: u8 = 0x02;
+const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+const DW_LNS_SET_FILE: u8 = 0x04;
+const DW_LNS_SET_COLUMN: u8 = 0x05;
+const DW_LNS_NEGATE_STMT: u8 = 0x06;
+const DW_LNS_SET_BASIC_BLOCK: u8 = 0x07;
+const DW_LNS_CONST_ADD_PC: u8 = 0x08;
+const DW_LNS_FIXED_ADVANCE_PC: u8 = 0x09;
+const DW_LNS_SET_PROLOGUE_END: u8 = 0x0a;
+const DW_LNS_SET_EPILOGUE_BEGIN: u8 = 0x0b;
+const DW_LNS_SET_ISA: u8 = 0x0c;
+
+const DW_LNE_END_SEQUENCE: u8 = 0x01;
+const DW_LNE_SET_ADDRESS: u8 = 0x02;
+const DW_LNE_DEFINE_FILE: u8 = 0x03;
+const DW_LNE_SET_DISCRIMINATOR: u8 = 0x04;
+
+const DW_LNCT_PATH: u64 = 0x1;
+const DW_LNCT_DIRECTORY_INDEX: u64 = 0x2;
+
+/// Entry of the file name table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry<'d> {
+    pub name: &'d str,
+    pub dir: u64,
+}
+
+/// Row of the line number matrix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Row {
+    pub address: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    pub prologue_end: bool,
+    pub end_sequence: bool,
+}
+
+/// Decoded line number program of one unit
+#[derive(Debug, Clone)]
+pub struct LineProgram<'d> {
+    pub offset: usize,
+    pub version: u16,
+    pub dirs: Vec<&'d str>,
+    pub files: Vec<FileEntry<'d>>,
+    pub rows: Vec<Row>,
+}
+
+impl LineProgram<'_> {
+
+    /// Path of file `index`, joined with its directory when relative.
+    /// File numbers are one based before DWARF 5
+    pub fn file_name(&self, index: u64) -> String {
+        let index = if self.version >= 5 { Some(index) } else { index.checked_sub(1) };
+        let Some(file) = index.and_then(|i| self.files.get(usize::try_from(i).ok()?)) else {
+            return format!("<file {}>", index.unwrap_or(0));
+        };
+
+        // Before DWARF 5 directory 0 is the compilation directory, not listed
+        let dir = if self.version >= 5 {
+            self.dirs.get(file.dir as usize)
+        } else {
+            (file.dir as usize).checked_sub(1).and_then(|d| self.dirs.get(d))
+        };
+        let mut path = match dir {
+            Some(dir) if !file.name.starts_with('/') && !dir.is_empty() => format!("{}/{}", dir, file.name),
+            _ => file.name.to_string(),
+        };
+
+        // DWARF 5 directory 0 is the compilation directory, the others may be relative to it
+        if let Some(comp_dir) = self.dirs.first().filter(|_| self.version >= 5 && file.dir != 0) {
+            if !path.starts_with('/') {
+                path = format!("{}/{}", comp_dir, path);
+            }
+        }
+        path
+    }
+
+    /// The row covering `addr`, excluding end of sequence markers
+    pub fn find_row(&self, addr: u64) -> Option<&Row> {
+        self.rows
+            .windows(2)
+            .find(|w| !w[0].end_sequence && w[0].address <= addr && addr < w[1].address)
+            .map(|w| &w[0])
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+impl<'d> Dwarf<'d> {
+
+    /// Decode the line number program at `offset` in `.debug_line`
+    pub fn line_program(&'d self, offset: usize, addr_size: u8) -> Result<LineProgram<'d>> {
+        let mut rdr = self.reader(&self.line, offset);
+
+        let (length, offset_size) = match rdr.u32()? {
+            0xffffffff => (rdr.u64()?, 8),
+            v => (u64::from(v), 4),
+        };
+        let end = rdr.pos()
+            .checked_add(usize::try_from(length)?)
+            .filter(|end| *end <= self.line.len())
+            .ok_or_else(|| anyhow!("Line program at {:#x} extends past .debug_line", offset))?;
+
+        let version = rdr.u16()?;
+        if !(2..=5).contains(&version) {
+            bail!("Unsupported line program version {} at {:#x}", version, offset);
+        }
+        let addr_size = if version >= 5 {
+            let size = rdr.u8()?;
+            let _seg_sel_size = rdr.u8()?;
+            size
+        } else {
+            addr_size
+        };
+
+        let header_length = usize::try_from(rdr.uint(offset_size)?)?;
+        let program_start = rdr.pos().saturating_add(header_length);
+        let min_inst_length = u64::from(rdr.u8()?);
+        if version >= 4 {
+            let _max_ops_per_inst = rdr.u8()?;
+        }
+        let default_is_stmt = rdr.u8()? != 0;
+        let line_base = i64::from(rdr.i8()?);
+        let line_range = rdr.u8()?;
+        let opcode_base = rdr.u8()?;
+        if line_range == 0 {
+            bail!("Line program at {:#x} has a zero line range", offset);
+        }
+        let std_lengths = rdr.bytes(usize::from(opcode_base.saturating_sub(1)))?;
+
+        let (dirs, mut files) = if version >= 5 {
+            let dirs = self.entry_table(&mut rdr, offset_size)?
+                .into_iter()
+                .map(|e| e.name)
+                .collect();
+            (dirs, self.entry_table(&mut rdr, offset_size)?)
+        } else {
+            let mut dirs = Vec::new();
+            loop {
+                let dir = rdr.cstr()?;
+                if dir.is_empty() {
+                    break;
+                }
+                dirs.push(dir);
+            }
+            let mut files = Vec::new();
+            while let Some(file) = file_entry(&mut rdr)? {
+                files.push(file);
+            }
+            (dirs, files)
+        };
+
+        // Run the state machine
+        let mut rdr = self.reader(&self.line[..end], program_start);
+        let mut rows = Vec::new();
+        let initial = Row { file: 1, line: 1, is_stmt: default_is_stmt, ..Default::default() };
+        let mut row = initial;
+
+        while !rdr.is_empty() {
+            let opcode = rdr.u8()?;
+            if opcode >= opcode_base {
+                let adjusted = opcode - opcode_base;
+                row.address = row.address
+                    .wrapping_add(u64::from(adjusted / line_range) * min_inst_length);
+                row.line = row.line.wrapping_add_signed(line_base + i64::from(adjusted % line_range));
+                rows.push(row);
+                row.prologue_end = false;
+                continue;
+            }
+
+            match opcode {
+                0 => {
+                    let len = usize::try_from(rdr.uleb128()?)?;
+                    let next = rdr.pos().saturating_add(len);
+                    if len == 0 {
+                        continue;
+                    }
+                    match rdr.u8()? {
+                        DW_LNE_END_SEQUENCE => {
+                            row.end_sequence = true;
+                            rows.push(row);
+                            row = initial;
+                        }
+                        DW_LNE_SET_ADDRESS => {
+                            let size = if len > 1 { len - 1 } else { usize::from(addr_size) };
+                            row.address = rdr.uint(size)?;
+                        }
+                        DW_LNE_DEFINE_FILE => {
+                            if let Some(file) = file_entry(&mut rdr)? {
+                                files.push(file);
+                            }
+                        }
+                        DW_LNE_SET_DISCRIMINATOR => {}
+                        _ => {}
+                    }
+                    rdr.seek(next);
+                }
+                DW_LNS_COPY => {
+                    rows.push(row);
+                    row.prologue_end = false;
+                }
+                DW_LNS_ADVANCE_PC => {
+                    row.address = row.address.wrapping_add(rdr.uleb128()?.wrapping_mul(min_inst_length));
+                }
+                DW_LNS_ADVANCE_LINE => row.line = row.line.wrapping_add_signed(rdr.sleb128()?),
+                DW_LNS_SET_FILE => row.file = rdr.uleb128()?,
+                DW_LNS_SET_COLUMN => row.column = rdr.uleb128()?,
+                DW_LNS_NEGATE_STMT => row.is_stmt = !row.is_stmt,
+                DW_LNS_SET_BASIC_BLOCK | DW_LNS_SET_EPILOGUE_BEGIN => {}
+                DW_LNS_CONST_ADD_PC => {
+                    row.address = row.address
+                        .wrapping_add(u64::from((255 - opcode_base) / line_range) * min_inst_length);
+                }
+                DW_LNS_FIXED_ADVANCE_PC => row.address = row.address.wrapping_add(rdr.u16()?.into()),
+                DW_LNS_SET_PROLOGUE_END => row.prologue_end = true,
+                DW_LNS_SET_ISA => {
+                    rdr.uleb128()?;
+                }
+                _ => {
+                    // Unknown standard opcode, skip its operands
+                    for _ in 0..std_lengths[usize::from(opcode - 1)] {
+                        rdr.uleb128()?;
+                    }
+                }
+            }
+        }
+
+        Ok(LineProgram { offset, version, dirs, files, rows })
+    }
+
+    /// DWARF 5 directory or file name table
+    fn entry_table(&'d self, rdr: &mut Reader<'d>, offset_size: usize) -> Result<Vec<FileEntry<'d>>> {
+        let format_count = rdr.u8()?;
+        let mut formats = Vec::new();
+        for _ in 0..format_count {
+            formats.push((rdr.uleb128()?, rdr.uleb128()?));
+        }
+
+        let count = rdr.uleb128()?;
+        if formats.is_empty() && count > 0 {
+            bail!("Line program entry table has {} entries and no formats", count);
+        }
+
+        // Every entry takes at least a byte, a larger count runs out of data
+        let count = usize::try_from(count).unwrap_or(usize::MAX).min(rdr.remaining());
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut entry = FileEntry { name: INVALID_STR, dir: 0 };
+            for (content, form) in &formats {
+                let value = match *form {
+                    DW_FORM_STRING => Some(FormValue::Str(rdr.cstr().unwrap_or(INVALID_STR))),
+                    DW_FORM_LINE_STRP => Some(FormValue::Str(self.line_string(rdr.uint(offset_size)?))),
+                    DW_FORM_STRP => Some(FormValue::Str(self.string(rdr.uint(offset_size)?))),
+                    DW_FORM_UDATA => Some(FormValue::Num(rdr.uleb128()?)),
+                    DW_FORM_DATA1 => Some(FormValue::Num(rdr.u8()?.into())),
+                    DW_FORM_DATA2 => Some(FormValue::Num(rdr.u16()?.into())),
+                    DW_FORM_DATA4 => Some(FormValue::Num(rdr.u32()?.into())),
+                    DW_FORM_DATA8 => Some(FormValue::Num(rdr.u64()?)),
+                    DW_FORM_DATA16 => {
+                        rdr.skip(16)?;
+                        None
+                    }
+                    DW_FORM_BLOCK => {
+                        let len = usize::try_from(rdr.uleb128()?)?;
+                        rdr.skip(len)?;
+                        None
+                    }
+                    v => bail!("Unsupported form {:#x} in line program header", v),
+                };
+                match (*content, value) {
+                    (DW_LNCT_PATH, Some(FormValue::Str(name))) => entry.name = name,
+                    (DW_LNCT_DIRECTORY_INDEX, Some(FormValue::Num(dir))) => entry.dir = dir,
+                    _ => {}
+                }
+            }
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+}
+
+/// Value of an entry table field, by its form
+enum FormValue<'d> {
+    Str(&'d str),
+    Num(u64),
+}
+
+/// Pre DWARF 5 file entry, `None` at the end of the table
+fn file_entry<'d>(rdr: &mut Reader<'d>) -> Result<Option<FileEntry<'d>>> {
+    let name = rdr.cstr()?;
+    if name.is_empty() {
+        return Ok(None);
+    }
+    let dir = rdr.uleb128()?;
+    let _mtime = rdr.uleb128()?;
+    let _length = rdr.uleb128()?;
+    Ok(Some(FileEntry { name, dir }))
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::reader::Endian;
+
+    #[test]
+    fn version_4_program() {
+        let mut line = Vec::from([
+            0, 0, 0, 0,     // unit length, patched below
+            4, 0,           // version
+            0, 0, 0, 0,     // header length, patched below
+            1, 1, 1,        // min inst length, max ops, default is_stmt
+            0xfb, 14, 13,   // line base -5, line range, opcode base
+            0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1,
+        ]);
+        line.extend(b"src\0\0a.c\0\x01\0\0\0");
+        let header_end = line.len();
+        line.extend([
+            0, 9, DW_LNE_SET_ADDRESS, 0x00, 0x10, 0, 0, 0, 0, 0, 0,
+            DW_LNS_COPY,
+            DW_LNS_ADVANCE_LINE, 2,
+            13 + 4 * 14 + 5,    // special: address += 4, line += 0
+            DW_LNS_ADVANCE_PC, 4,
+            0, 1, DW_LNE_END_SEQUENCE,
+        ]);
+        let unit_len = (line.len() - 4) as u32;
+        line[..4].copy_from_slice(&unit_len.to_le_bytes());
+        line[6..10].copy_from_slice(&((header_end - 10) as u32).to_le_bytes());
+
+        let dwarf = Dwarf { line: line.into(), ..Dwarf::empty(Endian::Little) };
+        let program = dwarf.line_program(0, 8).unwrap();
+
+        assert_eq!(program.file_name(1), "src/a.c");
+        let lines: Vec<_> = program.rows.iter().map(|r| (r.address, r.line, r.end_sequence)).collect();
+        assert_eq!(lines, [(0x1000, 1, false), (0x1004, 3, false), (0x1008, 3, true)]);
+        assert_eq!(program.find_row(0x1006).unwrap().line, 3);
+        assert!(program.find_row(0x1008).is_none());
+    }
+}
@@ -0,0 +1,324 @@
+//!
+//! DWARF debugging information, versions 2 to 5
+//!
+//! Sections are taken from the ELF `.debug_*` sections or, for dSYM
+//! bundles and unlinked objects, the Mach-O `__DWARF,__debug_*` sections.
+//! ELF sections compressed with zlib, by `-gz` or as the older
+//! `.zdebug_*` sections, are inflated.
+//!
+//! - <https://dwarfstd.org/doc/DWARF5.pdf>
+
+pub mod abbrev;
+pub mod constants;
+pub mod info;
+pub mod line;
+pub mod ranges;
+
+use anyhow::{anyhow, bail, Result};
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+use std::borrow::Cow;
+
+use crate::{
+    decoders::reader::{self, Endian, Reader},
+    exe_types::{ExeRef, Section},
+};
+
+pub use info::{Attr, AttrValue, Die, Unit};
+
+// ------------------------------------------------------------------------
+
+/// Shown in place of a string that can't be read
+pub const INVALID_STR: &str = "<invalid string>";
+
+/// Header flag of an ELF section compressed with an `Elf*_Chdr` in front
+const SHF_COMPRESSED: u64 = 0x800;
+const ELFCOMPRESS_ZLIB: u32 = 1;
+const ELFCLASS64: u8 = 2;
+
+/// Most a deflate stream can expand each byte to
+const MAX_DEFLATE_RATIO: usize = 1032;
+
+/// The DWARF sections of an image, inflated when they are compressed
+#[derive(Debug, Clone)]
+pub struct Dwarf<'d> {
+    pub endian: Endian,
+    pub info: Cow<'d, [u8]>,
+    pub abbrev: Cow<'d, [u8]>,
+    pub str: Cow<'d, [u8]>,
+    pub line_str: Cow<'d, [u8]>,
+    pub str_offsets: Cow<'d, [u8]>,
+    pub addr: Cow<'d, [u8]>,
+    pub line: Cow<'d, [u8]>,
+    pub ranges: Cow<'d, [u8]>,
+    pub rnglists: Cow<'d, [u8]>,
+    /// File offset of `.debug_info`
+    pub info_offset: u64,
+}
+
+impl<'d> Dwarf<'d> {
+
+    /// The DWARF sections of `exe`, `None` if there is no `.debug_info`
+    pub fn load(exe: ExeRef<'d>) -> Option<Result<Self>> {
+        let sections = exe.sections();
+        let info_section = section(&sections, "debug_info")?;
+        let is_elf = exe.mmap().starts_with(b"\x7fELF");
+        let endian = exe.endianness().unwrap_or(Endian::Little);
+        let contents = |section: Option<&Section>| match section {
+            Some(section) => contents(exe.mmap(), section, is_elf, endian),
+            None => Ok(Cow::Borrowed(&[][..])),
+        };
+        let data = |name| contents(section(&sections, name));
+
+        Some((|| Ok(Self {
+            endian,
+            info: contents(Some(info_section))?,
+            abbrev: data("debug_abbrev")?,
+            str: data("debug_str")?,
+            line_str: data("debug_line_str")?,
+            // Mach-O section names are limited to 16 characters
+            str_offsets: contents(section(&sections, "debug_str_offsets")
+                .or_else(|| section(&sections, "debug_str_offs")))?,
+            addr: data("debug_addr")?,
+            line: data("debug_line")?,
+            ranges: data("debug_ranges")?,
+            rnglists: data("debug_rnglists")?,
+            info_offset: info_section.offset,
+        }))())
+    }
+
+    /// No sections, for filling in with struct update syntax
+    pub fn empty(endian: Endian) -> Self {
+        let none = || Cow::Borrowed(&[][..]);
+        Self {
+            endian,
+            info: none(), abbrev: none(), str: none(), line_str: none(), str_offsets: none(),
+            addr: none(), line: none(), ranges: none(), rnglists: none(),
+            info_offset: 0,
+        }
+    }
+
+    pub fn reader(&self, data: &'d [u8], offset: usize) -> Reader<'d> {
+        Reader::at(data, offset, self.endian)
+    }
+
+    // --------------------------------------------------------------------
+
+    /// String at `offset` in `.debug_str`
+    pub fn string(&'d self, offset: u64) -> &'d str {
+        reader::cstr_at(&self.str, offset as usize).unwrap_or(INVALID_STR)
+    }
+
+    /// String at `offset` in `.debug_line_str`
+    pub fn line_string(&'d self, offset: u64) -> &'d str {
+        reader::cstr_at(&self.line_str, offset as usize).unwrap_or(INVALID_STR)
+    }
+
+    /// String from a `DW_FORM_strx*` index
+    pub fn string_index(&'d self, unit: &Unit, index: u64) -> Result<&'d str> {
+        let offset = index
+            .checked_mul(u64::from(unit.offset_size))
+            .and_then(|v| v.checked_add(unit.str_offsets_base))
+            .ok_or_else(|| anyhow!("String index {:#x} overflows .debug_str_offsets", index))?;
+        let mut rdr = self.reader(&self.str_offsets, usize::try_from(offset)?);
+        let str_offset = rdr.uint(unit.offset_size.into())?;
+        reader::cstr_at(&self.str, usize::try_from(str_offset)?)
+    }
+
+    /// Address from a `DW_FORM_addrx*` index
+    pub fn address_index(&'d self, unit: &Unit, index: u64) -> Result<u64> {
+        let offset = index
+            .checked_mul(u64::from(unit.addr_size))
+            .and_then(|v| v.checked_add(unit.addr_base))
+            .ok_or_else(|| anyhow!("Address index {:#x} overflows .debug_addr", index))?;
+        let mut rdr = self.reader(&self.addr, usize::try_from(offset)?);
+        rdr.uint(unit.addr_size.into())
+    }
+
+    // --------------------------------------------------------------------
+
+    /// Attribute value as a string
+    pub fn attr_string(&'d self, unit: &Unit, attr: &Attr<'d>) -> Option<&'d str> {
+        match attr.value {
+            AttrValue::Str(s) => Some(s),
+            AttrValue::StrIndex(idx) => Some(self.string_index(unit, idx).unwrap_or(INVALID_STR)),
+            _ => None,
+        }
+    }
+
+    /// Attribute value as an address
+    pub fn attr_address(&'d self, unit: &Unit, attr: &Attr) -> Option<u64> {
+        match attr.value {
+            AttrValue::Addr(a) => Some(a),
+            AttrValue::AddrIndex(idx) => self.address_index(unit, idx).ok(),
+            _ => None,
+        }
+    }
+
+    /// Readable form of an attribute value
+    pub fn value_string(&'d self, unit: &Unit, attr: &Attr) -> String {
+        use constants::*;
+
+        match attr.value {
+            AttrValue::Addr(_) | AttrValue::AddrIndex(_) => match self.attr_address(unit, attr) {
+                Some(a) => format!("{:#x}", a),
+                None => "<invalid address index>".into(),
+            },
+            AttrValue::Str(_) | AttrValue::StrIndex(_) => {
+                self.attr_string(unit, attr).unwrap_or(INVALID_STR).into()
+            }
+            AttrValue::Udata(v) => match attr.name {
+                DW_AT_LANGUAGE => name(LANGUAGES, v),
+                DW_AT_HIGH_PC => format!("{:#x} (length)", v),
+                DW_AT_STMT_LIST | DW_AT_RANGES => format!("{:#x}", v),
+                _ => v.to_string(),
+            },
+            AttrValue::Sdata(v) => v.to_string(),
+            AttrValue::Flag(v) => v.to_string(),
+            AttrValue::Ref(o) => format!("<{:#x}>", o),
+            AttrValue::SecOffset(o) => format!("{:#x}", o),
+            AttrValue::Signature(s) => format!("signature {:#018x}", s),
+            AttrValue::Block(b) => format!("[{}] {}", b.len(), hex_bytes(b)),
+        }
+    }
+
+    // --------------------------------------------------------------------
+
+    /// Compilation unit summary: name, producer, language and directory
+    pub fn unit_summary(&'d self, unit: &Unit) -> Result<UnitSummary<'d>> {
+        let die = match self.die(unit, unit.first_die)? {
+            Some(die) => die,
+            None => bail!("Unit at {:#x} has no entries", unit.offset),
+        };
+
+        let string = |name| die.attr(name).and_then(|a| self.attr_string(unit, a));
+
+        Ok(UnitSummary {
+            name: string(constants::DW_AT_NAME),
+            producer: string(constants::DW_AT_PRODUCER),
+            comp_dir: string(constants::DW_AT_COMP_DIR),
+            language: die.attr(constants::DW_AT_LANGUAGE)
+                .and_then(|a| a.udata())
+                .map(|l| constants::name(constants::LANGUAGES, l)),
+        })
+    }
+
+}
+
+/// Descriptive attributes of the unit entry
+#[derive(Debug, Default, Clone)]
+pub struct UnitSummary<'d> {
+    pub name: Option<&'d str>,
+    pub producer: Option<&'d str>,
+    pub language: Option<String>,
+    pub comp_dir: Option<&'d str>,
+}
+
+// ------------------------------------------------------------------------
+
+/// Does the image have a `.debug_info` section?
+pub fn is_present(exe: ExeRef) -> bool {
+    section(&exe.sections(), "debug_info").is_some()
+}
+
+/// Look up an ELF `.<name>` or `.z<name>`, or Mach-O `__DWARF,__<name>`
+/// section
+pub fn section<'s>(sections: &'s [Section], name: &str) -> Option<&'s Section> {
+    sections.iter().find(|s| {
+        s.name.strip_prefix('.').map(|n| n.strip_prefix('z').unwrap_or(n)) == Some(name)
+            || s.name.strip_prefix("__") == Some(name)
+    })
+}
+
+/// Contents of a section, inflated if it is compressed with a
+/// `SHF_COMPRESSED` header or, in `.zdebug_*` sections, a `ZLIB` header
+fn contents<'d>(mmap: &'d [u8], section: &Section, is_elf: bool, endian: Endian) -> Result<Cow<'d, [u8]>> {
+    let Some(data) = section.data(mmap) else {
+        return Ok(Cow::Borrowed(&[]));
+    };
+
+    let (size, stream) = if is_elf && section.flags & SHF_COMPRESSED != 0 {
+        let mut rdr = Reader::new(data, endian);
+        let kind = rdr.u32()?;
+        if kind != ELFCOMPRESS_ZLIB {
+            bail!("{} is compressed with unsupported type {}", section.name, kind);
+        }
+        // Elf64_Chdr has a reserved word before the 64 bit size
+        let (size, header_len) = if mmap.get(4) == Some(&ELFCLASS64) {
+            rdr.skip(4)?;
+            (rdr.u64()?, 24)
+        } else {
+            (rdr.u32()?.into(), 12)
+        };
+        (size, data.get(header_len..).unwrap_or(&[]))
+    } else if section.name.starts_with(".zdebug") && data.starts_with(b"ZLIB") {
+        let mut rdr = Reader::at(data, 4, Endian::Big);
+        (rdr.u64()?, data.get(12..).unwrap_or(&[]))
+    } else {
+        return Ok(Cow::Borrowed(data));
+    };
+
+    let limit = usize::try_from(size)?.min(stream.len().saturating_mul(MAX_DEFLATE_RATIO));
+    let inflated = decompress_to_vec_zlib_with_limit(stream, limit)
+        .map_err(|e| anyhow!("Unable to inflate {}: {:?}", section.name, e.status))?;
+    if inflated.len() as u64 != size {
+        bail!("{} inflated to {} bytes, the header gives {}", section.name, inflated.len(), size);
+    }
+    Ok(Cow::Owned(inflated))
+}
+
+fn hex_bytes(data: &[u8]) -> String {
+    const MAX: usize = 16;
+    let mut rc = data.iter()
+        .take(MAX)
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if data.len() > MAX {
+        rc += " ...";
+    }
+    rc
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    #[test]
+    fn compressed_sections() {
+        let text = b"DWARF contents ".repeat(20);
+        let stream = compress_to_vec_zlib(&text, 6);
+
+        // ELF64 class byte, then an Elf64_Chdr and the stream
+        let mut mmap = Vec::from(*b"\x7fELF\x02");
+        let offset = mmap.len() as u64;
+        mmap.extend_from_slice(&ELFCOMPRESS_ZLIB.to_le_bytes());
+        mmap.extend_from_slice(&[0; 4]);
+        mmap.extend_from_slice(&(text.len() as u64).to_le_bytes());
+        mmap.extend_from_slice(&1u64.to_le_bytes());
+        mmap.extend_from_slice(&stream);
+        let chdr = Section {
+            name: ".debug_info".into(),
+            offset,
+            file_size: mmap.len() as u64 - offset,
+            flags: SHF_COMPRESSED,
+            ..Default::default()
+        };
+        assert_eq!(contents(&mmap, &chdr, true, Endian::Little).unwrap(), &text[..]);
+
+        // GNU .zdebug sections have a big endian size after ZLIB
+        let mut mmap = Vec::from(*b"ZLIB");
+        mmap.extend_from_slice(&(text.len() as u64).to_be_bytes());
+        mmap.extend_from_slice(&stream);
+        let zdebug = Section { name: ".zdebug_info".into(), file_size: mmap.len() as u64, ..Default::default() };
+        assert_eq!(contents(&mmap, &zdebug, true, Endian::Little).unwrap(), &text[..]);
+        assert_eq!(section(&[zdebug], "debug_info").map(|s| s.name.as_str()), Some(".zdebug_info"));
+
+        // A size the stream doesn't inflate to
+        mmap[11] += 1;
+        let zdebug = Section { name: ".zdebug_info".into(), file_size: mmap.len() as u64, ..Default::default() };
+        assert!(contents(&mmap, &zdebug, true, Endian::Little).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//!
+//! Address ranges of entries, from `DW_AT_low_pc`/`DW_AT_high_pc` or the
+//! `.debug_ranges` and `.debug_rnglists` range lists
+//!
+
+use anyhow::{anyhow, bail, Result};
+
+use super::{constants::*, AttrValue, Die, Dwarf, Unit};
+
+// ------------------------------------------------------------------------
+
+const DW_RLE_END_OF_LIST: u8 = 0x00;
+const DW_RLE_BASE_ADDRESSX: u8 = 0x01;
+const DW_RLE_STARTX_ENDX: u8 = 0x02;
+const DW_RLE_STARTX_LENGTH: u8 = 0x03;
+const DW_RLE_OFFSET_PAIR: u8 = 0x04;
+const DW_RLE_BASE_ADDRESS: u8 = 0x05;
+const DW_RLE_START_END: u8 = 0x06;
+const DW_RLE_START_LENGTH: u8 = 0x07;
+
+/// Half open address range
+pub type Range = (u64, u64);
+
+impl<'d> Dwarf<'d> {
+
+    /// Address ranges covered by `die`, empty if it has none
+    pub fn die_ranges(&'d self, unit: &Unit, die: &Die) -> Result<Vec<Range>> {
+        if let Some(low) = die.attr(DW_AT_LOW_PC).and_then(|a| self.attr_address(unit, a)) {
+            let high = die.attr(DW_AT_HIGH_PC).and_then(|a| match a.value {
+                AttrValue::Udata(len) => Some(low.wrapping_add(len)),
+                _ => self.attr_address(unit, a),
+            });
+            return Ok(high.map(|high| Vec::from([(low, high)])).unwrap_or_default());
+        }
+
+        let Some(attr) = die.attr(DW_AT_RANGES) else {
+            return Ok(Vec::new());
+        };
+        let Some(value) = attr.udata() else {
+            bail!("Invalid DW_AT_ranges at {:#x}", die.offset);
+        };
+
+        if unit.version < 5 {
+            return self.range_list(unit, usize::try_from(value)?);
+        }
+
+        let offset = if attr.form == DW_FORM_RNGLISTX {
+            let overflow = || anyhow!("Range list index {:#x} overflows .debug_rnglists", value);
+            let entry = value
+                .checked_mul(u64::from(unit.offset_size))
+                .and_then(|v| v.checked_add(unit.rnglists_base))
+                .ok_or_else(overflow)?;
+            let mut rdr = self.reader(&self.rnglists, usize::try_from(entry)?);
+            rdr.uint(unit.offset_size.into())?
+                .checked_add(unit.rnglists_base)
+                .ok_or_else(overflow)?
+        } else {
+            value
+        };
+        self.rnglist(unit, usize::try_from(offset)?)
+    }
+
+    /// Pre DWARF 5 `.debug_ranges` list
+    fn range_list(&'d self, unit: &Unit, offset: usize) -> Result<Vec<Range>> {
+        let size = usize::from(unit.addr_size);
+        let base_marker = if size == 4 { u64::from(u32::MAX) } else { u64::MAX };
+        let mut rdr = self.reader(&self.ranges, offset);
+        let mut base = unit.base_address;
+        let mut ranges = Vec::new();
+
+        loop {
+            let start = rdr.uint(size)?;
+            let end = rdr.uint(size)?;
+            match (start, end) {
+                (0, 0) => break,
+                (s, e) if s == base_marker => base = e,
+                (s, e) => ranges.push((base.wrapping_add(s), base.wrapping_add(e))),
+            }
+        }
+
+        Ok(ranges)
+    }
+
+    /// DWARF 5 `.debug_rnglists` list
+    fn rnglist(&'d self, unit: &Unit, offset: usize) -> Result<Vec<Range>> {
+        let size = usize::from(unit.addr_size);
+        let mut rdr = self.reader(&self.rnglists, offset);
+        let mut base = unit.base_address;
+        let mut ranges = Vec::new();
+
+        loop {
+            match rdr.u8()? {
+                DW_RLE_END_OF_LIST => break,
+                DW_RLE_BASE_ADDRESSX => base = self.address_index(unit, rdr.uleb128()?)?,
+                DW_RLE_STARTX_ENDX => {
+                    let start = self.address_index(unit, rdr.uleb128()?)?;
+                    ranges.push((start, self.address_index(unit, rdr.uleb128()?)?));
+                }
+                DW_RLE_STARTX_LENGTH => {
+                    let start = self.address_index(unit, rdr.uleb128()?)?;
+                    ranges.push((start, start.wrapping_add(rdr.uleb128()?)));
+                }
+                DW_RLE_OFFSET_PAIR => {
+                    let start = base.wrapping_add(rdr.uleb128()?);
+                    ranges.push((start, base.wrapping_add(rdr.uleb128()?)));
+                }
+                DW_RLE_BASE_ADDRESS => base = rdr.uint(size)?,
+                DW_RLE_START_END => ranges.push((rdr.uint(size)?, rdr.uint(size)?)),
+                DW_RLE_START_LENGTH => {
+                    let start = rdr.uint(size)?;
+                    ranges.push((start, start.wrapping_add(rdr.uleb128()?)));
+                }
+                v => bail!("Unknown range list entry {:#x} at {:#x}", v, rdr.pos() - 1),
+            }
+        }
+
+        Ok(ranges)
+    }
+
+}
@@ -0,0 +1,494 @@
+//!
+//! Call frame information from `.eh_frame` and the `.eh_frame_hdr`
+//! search table
+//!
+//! - <https://refspecs.linuxfoundation.org/LSB_5.0.0/LSB-Core-generic/LSB-Core-generic/ehframechpt.html>
+//! - DWARF 5 section 6.4, Call Frame Information
+//!
+
+use anyhow::{anyhow, bail, Result};
+use std::collections::{hash_map, HashMap};
+
+use crate::{
+    decoders::reader::{Endian, Reader},
+    exe_types::{Architecture, ExeRef},
+};
+
+// ------------------------------------------------------------------------
+
+const DW_EH_PE_OMIT: u8 = 0xff;
+const DW_EH_PE_ULEB128: u8 = 0x01;
+const DW_EH_PE_UDATA2: u8 = 0x02;
+const DW_EH_PE_UDATA4: u8 = 0x03;
+const DW_EH_PE_UDATA8: u8 = 0x04;
+const DW_EH_PE_SLEB128: u8 = 0x09;
+const DW_EH_PE_SDATA2: u8 = 0x0a;
+const DW_EH_PE_SDATA4: u8 = 0x0b;
+const DW_EH_PE_SDATA8: u8 = 0x0c;
+const DW_EH_PE_PCREL: u8 = 0x10;
+const DW_EH_PE_DATAREL: u8 = 0x30;
+const DW_EH_PE_INDIRECT: u8 = 0x80;
+
+/// Registers as numbered by the DWARF ABI supplement of an architecture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterSet {
+    X86,
+    X86_64,
+    AArch64,
+    Other,
+}
+
+impl RegisterSet {
+
+    /// Pick the register set from the architecture of the image
+    pub fn from_exe(exe: ExeRef) -> Self {
+        match exe.architecture() {
+            Some(Architecture::X86) => Self::X86,
+            Some(Architecture::X86_64) => Self::X86_64,
+            Some(Architecture::AArch64) => Self::AArch64,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn name(self, reg: u64) -> String {
+        const X86: &[&str] = &["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip"];
+        const X86_64: &[&str] = &[
+            "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp",
+            "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15", "rip",
+        ];
+
+        let name = match self {
+            Self::X86 => X86.get(reg as usize).map(|s| s.to_string()),
+            Self::X86_64 => X86_64.get(reg as usize).map(|s| s.to_string()),
+            Self::AArch64 => match reg {
+                0..=28 => Some(format!("x{}", reg)),
+                29 => Some("fp".into()),
+                30 => Some("lr".into()),
+                31 => Some("sp".into()),
+                64..=95 => Some(format!("v{}", reg - 64)),
+                _ => None,
+            },
+            Self::Other => None,
+        };
+        match name {
+            Some(name) => format!("r{} ({})", reg, name),
+            None => format!("r{}", reg),
+        }
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+/// Common information entry
+#[derive(Debug, Clone)]
+pub struct Cie<'d> {
+    pub offset: usize,
+    pub version: u8,
+    pub augmentation: &'d str,
+    pub code_align: u64,
+    pub data_align: i64,
+    pub ra_register: u64,
+    pub fde_encoding: u8,
+    pub lsda_encoding: u8,
+    pub personality: Option<u64>,
+    pub signal_frame: bool,
+    pub instructions: &'d [u8],
+}
+
+/// Frame description entry
+#[derive(Debug, Clone)]
+pub struct Fde<'d> {
+    pub offset: usize,
+    pub cie_offset: usize,
+    pub pc_begin: u64,
+    pub pc_range: u64,
+    pub lsda: Option<u64>,
+    pub instructions: &'d [u8],
+}
+
+#[derive(Debug, Clone)]
+pub enum Entry<'d> {
+    Cie(Cie<'d>),
+    Fde(Fde<'d>),
+}
+
+/// Contents of an `.eh_frame` section
+#[derive(Debug, Clone)]
+pub struct EhFrame<'d> {
+    pub data: &'d [u8],
+    /// Virtual address of the section, the base for pc relative pointers
+    pub addr: u64,
+    pub endian: Endian,
+    pub addr_size: usize,
+}
+
+impl<'d> EhFrame<'d> {
+
+    /// The `.eh_frame` (ELF) or `__TEXT,__eh_frame` (Mach-O) section
+    pub fn load(exe: ExeRef<'d>) -> Option<Self> {
+        let section = exe.section(".eh_frame").or_else(|| exe.section("__TEXT,__eh_frame"))?;
+        let (endian, addr_size) = image_layout(exe);
+        Some(Self { data: section.data(exe.mmap())?, addr: section.addr, endian, addr_size })
+    }
+
+    /// Every CIE and FDE up to the zero terminator or end of section
+    pub fn entries(&self) -> Result<Vec<Entry<'d>>> {
+        let mut entries = Vec::new();
+        let mut cies: HashMap<usize, Cie<'d>> = HashMap::new();
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let mut rdr = Reader::at(self.data, offset, self.endian);
+            let length = match rdr.u32()? {
+                0 => break,
+                0xffffffff => rdr.u64()?,
+                v => v.into(),
+            };
+            let end = rdr.pos()
+                .checked_add(usize::try_from(length)?)
+                .filter(|end| *end <= self.data.len())
+                .ok_or_else(|| anyhow!("Entry at {:#x} extends past .eh_frame", offset))?;
+
+            let id_pos = rdr.pos();
+            let id = rdr.u32()?;
+            let mut rdr = Reader::at(&self.data[..end], rdr.pos(), self.endian);
+            entries.push(if id == 0 {
+                let cie = self.cie(offset, &mut rdr)?;
+                cies.insert(offset, cie.clone());
+                Entry::Cie(cie)
+            } else {
+                let cie_offset = id_pos
+                    .checked_sub(id as usize)
+                    .ok_or_else(|| anyhow!("FDE at {:#x} has an invalid CIE pointer", offset))?;
+                // A CIE after its FDEs is parsed once and kept for the rest
+                let cie = match cies.entry(cie_offset) {
+                    hash_map::Entry::Occupied(e) => e.into_mut(),
+                    hash_map::Entry::Vacant(e) => e.insert(self.cie(cie_offset, &mut self.cie_reader(cie_offset)?)?),
+                };
+                Entry::Fde(self.fde(offset, cie, &mut rdr)?)
+            });
+
+            offset = end;
+        }
+
+        Ok(entries)
+    }
+
+    /// Reader positioned after the length and id of the CIE at `offset`
+    fn cie_reader(&self, offset: usize) -> Result<Reader<'d>> {
+        let mut rdr = Reader::at(self.data, offset, self.endian);
+        let length = rdr.u32()? as usize;
+        let end = rdr.pos().saturating_add(length).min(self.data.len());
+        let pos = rdr.pos() + 4;
+        Ok(Reader::at(&self.data[..end], pos, self.endian))
+    }
+
+    fn cie(&self, offset: usize, rdr: &mut Reader<'d>) -> Result<Cie<'d>> {
+        let version = rdr.u8()?;
+        let augmentation = rdr.cstr()?;
+        if augmentation.contains("eh") {
+            rdr.skip(self.addr_size)?;
+        }
+        if version >= 4 {
+            let _addr_size = rdr.u8()?;
+            let _seg_size = rdr.u8()?;
+        }
+        let code_align = rdr.uleb128()?;
+        let data_align = rdr.sleb128()?;
+        let ra_register = if version == 1 { rdr.u8()?.into() } else { rdr.uleb128()? };
+
+        let mut cie = Cie {
+            offset, version, augmentation, code_align, data_align, ra_register,
+            fde_encoding: 0,
+            lsda_encoding: DW_EH_PE_OMIT,
+            personality: None,
+            signal_frame: false,
+            instructions: &[],
+        };
+
+        if augmentation.starts_with('z') {
+            let len = usize::try_from(rdr.uleb128()?)?;
+            let data_end = rdr.pos().saturating_add(len);
+            for c in augmentation.chars().skip(1) {
+                match c {
+                    'L' => cie.lsda_encoding = rdr.u8()?,
+                    'R' => cie.fde_encoding = rdr.u8()?,
+                    'P' => {
+                        let encoding = rdr.u8()?;
+                        cie.personality = self.pointer(rdr, encoding)?;
+                    }
+                    'S' => cie.signal_frame = true,
+                    _ => break,
+                }
+            }
+            rdr.seek(data_end);
+        }
+
+        cie.instructions = rdr.bytes(rdr.remaining())?;
+        Ok(cie)
+    }
+
+    fn fde(&self, offset: usize, cie: &Cie, rdr: &mut Reader<'d>) -> Result<Fde<'d>> {
+        let pc_begin = self.pointer(rdr, cie.fde_encoding)?.unwrap_or(0);
+        // The range is an unsigned value of the same size, never relative
+        let pc_range = self.pointer(rdr, cie.fde_encoding & 0x0f)?.unwrap_or(0);
+
+        let mut lsda = None;
+        if cie.augmentation.starts_with('z') {
+            let len = usize::try_from(rdr.uleb128()?)?;
+            let data_end = rdr.pos().saturating_add(len);
+            if cie.lsda_encoding != DW_EH_PE_OMIT && len > 0 {
+                lsda = self.pointer(rdr, cie.lsda_encoding)?;
+            }
+            rdr.seek(data_end);
+        }
+
+        Ok(Fde {
+            offset,
+            cie_offset: cie.offset,
+            pc_begin, pc_range, lsda,
+            instructions: rdr.bytes(rdr.remaining())?,
+        })
+    }
+
+    /// Read a pointer encoded with a `DW_EH_PE_*` value
+    pub fn pointer(&self, rdr: &mut Reader, encoding: u8) -> Result<Option<u64>> {
+        read_pointer(rdr, encoding, self.addr, self.addr, self.addr_size)
+    }
+
+}
+
+/// Read an encoded pointer, `base` is the address of the start of `rdr`
+/// used for pc relative values and `data_base` for data relative ones
+fn read_pointer(
+    rdr: &mut Reader,
+    encoding: u8,
+    base: u64,
+    data_base: u64,
+    addr_size: usize,
+) -> Result<Option<u64>> {
+    if encoding == DW_EH_PE_OMIT {
+        return Ok(None);
+    }
+    // Indirect pointers give the location of the value, which is only
+    // filled in at load time, so the location is what's reported
+    let encoding = encoding & !DW_EH_PE_INDIRECT;
+
+    let pos = rdr.pos() as u64;
+    let value = match encoding & 0x0f {
+        0 => rdr.uint(addr_size)?,
+        DW_EH_PE_ULEB128 => rdr.uleb128()?,
+        DW_EH_PE_UDATA2 => rdr.u16()?.into(),
+        DW_EH_PE_UDATA4 => rdr.u32()?.into(),
+        DW_EH_PE_UDATA8 => rdr.u64()?,
+        DW_EH_PE_SLEB128 => rdr.sleb128()? as u64,
+        DW_EH_PE_SDATA2 => rdr.u16()? as i16 as u64,
+        DW_EH_PE_SDATA4 => rdr.i32()? as u64,
+        DW_EH_PE_SDATA8 => rdr.i64()? as u64,
+        v => bail!("Unknown pointer format {:#x}", v),
+    };
+
+    let value = match encoding & 0x70 {
+        0 => value,
+        DW_EH_PE_PCREL => value.wrapping_add(base).wrapping_add(pos),
+        DW_EH_PE_DATAREL => value.wrapping_add(data_base),
+        // Text and function relative values are left as offsets
+        _ => value,
+    };
+
+    Ok(Some(value))
+}
+
+/// Byte order and pointer size of the image
+fn image_layout(exe: ExeRef) -> (Endian, usize) {
+    (exe.endianness().unwrap_or(Endian::Little), exe.bitness().unwrap_or(64) as usize / 8)
+}
+
+// ------------------------------------------------------------------------
+
+/// Binary search table from `.eh_frame_hdr`
+#[derive(Debug, Clone, Default)]
+pub struct EhFrameHdr {
+    pub version: u8,
+    pub eh_frame_ptr: Option<u64>,
+    /// Pairs of initial location and FDE address, sorted by location
+    pub table: Vec<(u64, u64)>,
+}
+
+pub fn eh_frame_hdr(exe: ExeRef) -> Option<Result<EhFrameHdr>> {
+    let section = exe.section(".eh_frame_hdr")?;
+    let data = section.data(exe.mmap())?;
+    let (endian, addr_size) = image_layout(exe);
+    Some(parse_eh_frame_hdr(data, section.addr, endian, addr_size))
+}
+
+fn parse_eh_frame_hdr(data: &[u8], addr: u64, endian: Endian, addr_size: usize) -> Result<EhFrameHdr> {
+    let mut rdr = Reader::new(data, endian);
+    let version = rdr.u8()?;
+    if version != 1 {
+        bail!("Unsupported .eh_frame_hdr version {}", version);
+    }
+    let ptr_enc = rdr.u8()?;
+    let count_enc = rdr.u8()?;
+    let table_enc = rdr.u8()?;
+
+    let eh_frame_ptr = read_pointer(&mut rdr, ptr_enc, addr, addr, addr_size)?;
+    let count = read_pointer(&mut rdr, count_enc, addr, addr, addr_size)?.unwrap_or(0);
+
+    let mut table = Vec::new();
+    if table_enc != DW_EH_PE_OMIT {
+        for _ in 0..count.min(data.len() as u64) {
+            let location = read_pointer(&mut rdr, table_enc, addr, addr, addr_size)?.unwrap_or(0);
+            let fde = read_pointer(&mut rdr, table_enc, addr, addr, addr_size)?.unwrap_or(0);
+            table.push((location, fde));
+        }
+    }
+
+    Ok(EhFrameHdr { version, eh_frame_ptr, table })
+}
+
+// ------------------------------------------------------------------------
+
+/// Describe the call frame instructions, one line each, tracking the
+/// location from `pc`
+pub fn instructions(
+    frame: &EhFrame,
+    cie: &Cie,
+    data: &[u8],
+    pc: u64,
+    regs: RegisterSet,
+) -> Result<Vec<String>> {
+    let mut rdr = Reader::new(data, frame.endian);
+    let mut lines = Vec::new();
+    let mut loc = pc;
+    let reg = |r| regs.name(r);
+    let advance = |v: u64| v.checked_mul(cie.code_align)
+        .ok_or_else(|| anyhow!("Malformed advance {} with code alignment {}", v, cie.code_align));
+    let signed = |v: i64| v.checked_mul(cie.data_align)
+        .ok_or_else(|| anyhow!("Malformed offset {} with data alignment {}", v, cie.data_align));
+    let factored = |v: u64| signed(i64::try_from(v)?);
+
+    while !rdr.is_empty() {
+        let op = rdr.u8()?;
+        let line = match (op >> 6, op & 0x3f) {
+            (1, delta) => {
+                let delta = advance(delta.into())?;
+                loc = loc.wrapping_add(delta);
+                format!("DW_CFA_advance_loc: {} to {:#x}", delta, loc)
+            }
+            (2, r) => format!("DW_CFA_offset: {} at cfa{:+}", reg(r.into()), factored(rdr.uleb128()?)?),
+            (3, r) => format!("DW_CFA_restore: {}", reg(r.into())),
+            (_, _) => match op {
+                0x00 => "DW_CFA_nop".into(),
+                0x01 => {
+                    loc = frame.pointer(&mut rdr, cie.fde_encoding)?.unwrap_or(0);
+                    format!("DW_CFA_set_loc: {:#x}", loc)
+                }
+                0x02..=0x04 => {
+                    let delta = advance(match op {
+                        0x02 => u64::from(rdr.u8()?),
+                        0x03 => u64::from(rdr.u16()?),
+                        _ => u64::from(rdr.u32()?),
+                    })?;
+                    loc = loc.wrapping_add(delta);
+                    format!("DW_CFA_advance_loc{}: {} to {:#x}", 1 << (op - 2), delta, loc)
+                }
+                0x05 => format!("DW_CFA_offset_extended: {} at cfa{:+}", reg(rdr.uleb128()?), factored(rdr.uleb128()?)?),
+                0x06 => format!("DW_CFA_restore_extended: {}", reg(rdr.uleb128()?)),
+                0x07 => format!("DW_CFA_undefined: {}", reg(rdr.uleb128()?)),
+                0x08 => format!("DW_CFA_same_value: {}", reg(rdr.uleb128()?)),
+                0x09 => format!("DW_CFA_register: {} in {}", reg(rdr.uleb128()?), reg(rdr.uleb128()?)),
+                0x0a => "DW_CFA_remember_state".into(),
+                0x0b => "DW_CFA_restore_state".into(),
+                0x0c => format!("DW_CFA_def_cfa: {} ofs {}", reg(rdr.uleb128()?), rdr.uleb128()?),
+                0x0d => format!("DW_CFA_def_cfa_register: {}", reg(rdr.uleb128()?)),
+                0x0e => format!("DW_CFA_def_cfa_offset: {}", rdr.uleb128()?),
+                0x0f => format!("DW_CFA_def_cfa_expression: {}", expression(&mut rdr)?),
+                0x10 => format!("DW_CFA_expression: {} {}", reg(rdr.uleb128()?), expression(&mut rdr)?),
+                0x11 => format!("DW_CFA_offset_extended_sf: {} at cfa{:+}",
+                    reg(rdr.uleb128()?), signed(rdr.sleb128()?)?),
+                0x12 => format!("DW_CFA_def_cfa_sf: {} ofs {}", reg(rdr.uleb128()?), signed(rdr.sleb128()?)?),
+                0x13 => format!("DW_CFA_def_cfa_offset_sf: {}", signed(rdr.sleb128()?)?),
+                0x14 => format!("DW_CFA_val_offset: {} is cfa{:+}", reg(rdr.uleb128()?), factored(rdr.uleb128()?)?),
+                0x15 => format!("DW_CFA_val_offset_sf: {} is cfa{:+}",
+                    reg(rdr.uleb128()?), signed(rdr.sleb128()?)?),
+                0x16 => format!("DW_CFA_val_expression: {} {}", reg(rdr.uleb128()?), expression(&mut rdr)?),
+                0x2d if regs == RegisterSet::AArch64 => "DW_CFA_AARCH64_negate_ra_state".into(),
+                0x2d => "DW_CFA_GNU_window_save".into(),
+                0x2e => format!("DW_CFA_GNU_args_size: {}", rdr.uleb128()?),
+                0x2f => format!("DW_CFA_GNU_negative_offset_extended: {} at cfa{:+}",
+                    reg(rdr.uleb128()?), factored(rdr.uleb128()?)?.wrapping_neg()),
+                v => {
+                    lines.push(format!("Unknown instruction {:#04x}, remaining {} bytes skipped", v, rdr.remaining()));
+                    break;
+                }
+            },
+        };
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+/// DWARF expression block as hex bytes
+fn expression(rdr: &mut Reader) -> Result<String> {
+    let len = usize::try_from(rdr.uleb128()?)?;
+    let bytes = rdr.bytes(len)?;
+    Ok(format!("[{}] {}", len, bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")))
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cie_and_fde() {
+        let data = [
+            // CIE: length 20, id 0, version 1, "zR", code 1, data -8, ra 16
+            20, 0, 0, 0, 0, 0, 0, 0, 1, b'z', b'R', 0, 1, 0x78, 16,
+            1, 0x1b,                        // augmentation data, pcrel sdata4
+            0x0c, 7, 8, 0x90, 1, 0, 0,      // def_cfa rsp+8, offset rip cfa-8
+            // FDE: length 20, CIE pointer 28 back
+            20, 0, 0, 0, 28, 0, 0, 0,
+            0xd8, 0xff, 0xff, 0xff,         // pc begin -40 relative
+            0x10, 0, 0, 0,                  // pc range
+            0,                              // augmentation data length
+            0x41, 0x0e, 16,                 // advance 1, def_cfa_offset 16
+            0, 0, 0, 0,
+        ];
+        let frame = EhFrame { data: &data, addr: 0x2000, endian: Endian::Little, addr_size: 8 };
+        let entries = frame.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let Entry::Cie(cie) = &entries[0] else { panic!("Expected a CIE") };
+        assert_eq!((cie.data_align, cie.ra_register, cie.fde_encoding), (-8, 16, 0x1b));
+
+        let Entry::Fde(fde) = &entries[1] else { panic!("Expected an FDE") };
+        assert_eq!((fde.cie_offset, fde.pc_begin, fde.pc_range), (0, 0x2000 + 32 - 40, 0x10));
+
+        let lines = instructions(&frame, cie, cie.instructions, 0, RegisterSet::X86_64).unwrap();
+        assert_eq!(lines[..2], ["DW_CFA_def_cfa: r7 (rsp) ofs 8", "DW_CFA_offset: r16 (rip) at cfa-8"]);
+        let lines = instructions(&frame, cie, fde.instructions, fde.pc_begin, RegisterSet::X86_64).unwrap();
+        assert_eq!(lines[..2], ["DW_CFA_advance_loc: 1 to 0x1ff9", "DW_CFA_def_cfa_offset: 16"]);
+    }
+
+    #[test]
+    fn overflowing_factors() {
+        let mut data = Vec::from([27, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+        data.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]); // code u64::MAX
+        data.extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f]); // data i64::MIN
+        data.push(16);
+        let frame = EhFrame { data: &data, addr: 0, endian: Endian::Little, addr_size: 8 };
+        let entries = frame.entries().unwrap();
+        let Entry::Cie(cie) = &entries[0] else { panic!("Expected a CIE") };
+        assert_eq!((cie.code_align, cie.data_align), (u64::MAX, i64::MIN));
+
+        for ops in [&[0x42][..], &[0x02, 2], &[0x81, 2], &[0x13, 2], &[0x2f, 1, u8::MAX, 1]] {
+            let err = instructions(&frame, cie, ops, 0, RegisterSet::X86_64).unwrap_err();
+            assert!(err.to_string().starts_with("Malformed"), "{:02x?}: {}", ops, err);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//!
+//! Decoder for the build information embedded in Go executables
+//!
+//! The Go linker places a `\xff Go buildinf:` header in the data
+//! segment, followed (since Go 1.18) by the Go version and the module
+//! information as varint prefixed strings. Before that the header points
+//! to the headers of the two strings in memory, which are found through
+//! the address space of the image.
+//!
+//! - <https://go.dev/src/debug/buildinfo/buildinfo.go>
+//! - <https://go.dev/src/runtime/debug/mod.go>
+
+use anyhow::{anyhow, bail, Result};
+
+use super::reader::{self, Endian, Reader};
+use crate::exe_types::{AddressSpace, ExeRef};
+
+// ------------------------------------------------------------------------
+
+pub const MAGIC: &[u8] = b"\xff Go buildinf:";
+
+const HEADER_SIZE: usize = 32;
+/// The header is aligned to 16 bytes in its section
+const HEADER_ALIGN: usize = 16;
+const FLAGS_ENDIAN_BIG: u8 = 0x1;
+const FLAGS_VERSION_INL: u8 = 0x2;
+
+/// Sentinel length wrapped around the module information string
+const MODINFO_SENTINEL: usize = 16;
+
+// ------------------------------------------------------------------------
+
+/// Module path, version and checksum, with an optional replacement
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Module {
+    pub path: String,
+    pub version: String,
+    pub sum: String,
+    pub replace: Option<Box<Module>>,
+}
+
+/// Decoded build information
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// File offset of the build information header
+    pub offset: usize,
+    pub ptr_size: u8,
+    pub endian: Option<Endian>,
+    pub go_version: String,
+    /// Package path of the main package
+    pub path: String,
+    pub main: Option<Module>,
+    pub deps: Vec<Module>,
+    pub settings: Vec<(String, String)>,
+}
+
+// ------------------------------------------------------------------------
+
+/// Locate the build information header in `data`, which starts at an
+/// aligned address such as the start of a section
+pub fn find(data: &[u8]) -> Option<usize> {
+    let mut start = 0;
+    loop {
+        let pos = reader::find(data, MAGIC, start)?;
+        if pos % HEADER_ALIGN == 0
+            && pos + HEADER_SIZE <= data.len()
+            && matches!(data[pos + MAGIC.len()], 4 | 8)
+        {
+            return Some(pos);
+        }
+        start = pos + 1;
+    }
+}
+
+/// File offset of the build information header, looked for in the
+/// `.go.buildinfo` or `__DATA,__go_buildinfo` section, or in the whole
+/// file when there is neither, as in PE images
+pub fn find_in(exe: ExeRef) -> Option<usize> {
+    match exe.section(".go.buildinfo").or_else(|| exe.section("__DATA,__go_buildinfo")) {
+        Some(section) => {
+            let start = usize::try_from(section.offset).ok()?;
+            find(section.data(exe.mmap())?).map(|pos| start + pos)
+        }
+        None => find(exe.mmap()),
+    }
+}
+
+// ------------------------------------------------------------------------
+
+/// Find and decode the build information, `None` if there is none
+pub fn decode(data: &[u8], space: &AddressSpace) -> Result<Option<BuildInfo>> {
+    match find(data) {
+        Some(offset) => decode_at(data, offset, space).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn decode_at(data: &[u8], offset: usize, space: &AddressSpace) -> Result<BuildInfo> {
+    let mut rdr = Reader::at(data, offset + MAGIC.len(), Endian::Little);
+    let ptr_size = rdr.u8()?;
+    let flags = rdr.u8()?;
+    let endian = if flags & FLAGS_ENDIAN_BIG != 0 { Endian::Big } else { Endian::Little };
+
+    let (go_version, modinfo) = if flags & FLAGS_VERSION_INL != 0 {
+        rdr.seek(offset + HEADER_SIZE);
+        (varint_string(&mut rdr)?, varint_string(&mut rdr)?)
+    } else {
+        let mut rdr = Reader::at(data, offset + MAGIC.len() + 2, endian);
+        let ptr_size = usize::from(ptr_size);
+        let version_addr = rdr.uint(ptr_size)?;
+        let modinfo_addr = rdr.uint(ptr_size)?;
+        (
+            pointed_string(data, space, version_addr, ptr_size, endian)?,
+            pointed_string(data, space, modinfo_addr, ptr_size, endian)?,
+        )
+    };
+
+    let mut info = parse_modinfo(strip_sentinels(modinfo)?)?;
+    info.offset = offset;
+    info.ptr_size = ptr_size;
+    info.endian = Some(endian);
+    info.go_version = go_version.into();
+
+    Ok(info)
+}
+
+fn varint_string<'r>(rdr: &mut Reader<'r>) -> Result<&'r str> {
+    let len = rdr.uleb128()?;
+    Ok(std::str::from_utf8(rdr.bytes(usize::try_from(len)?)?)?)
+}
+
+/// Go string with its header, a pointer and a length, at `addr`
+fn pointed_string<'d>(data: &'d [u8], space: &AddressSpace, addr: u64, ptr_size: usize, endian: Endian) -> Result<&'d str> {
+    let offset_of = |addr: u64| {
+        space.to_offset(addr).ok_or_else(|| anyhow!("Go string at {:#x} is not mapped from the file", addr))
+    };
+    let mut rdr = Reader::at(data, offset_of(addr)?, endian);
+    let str_addr = rdr.uint(ptr_size)?;
+    let len = usize::try_from(rdr.uint(ptr_size)?)?;
+    if len == 0 {
+        return Ok("");
+    }
+    rdr.seek(offset_of(str_addr)?);
+    Ok(std::str::from_utf8(rdr.bytes(len)?)?)
+}
+
+/// The module information without its sentinels, empty when the binary
+/// was built without module support
+fn strip_sentinels(modinfo: &str) -> Result<&str> {
+    let len = modinfo.len();
+    if len == 0 {
+        return Ok("");
+    }
+    if len > 2 * MODINFO_SENTINEL
+        && modinfo.as_bytes()[len - MODINFO_SENTINEL - 1] == b'\n'
+    {
+        if let Some(text) = modinfo.get(MODINFO_SENTINEL..len - MODINFO_SENTINEL) {
+            return Ok(text);
+        }
+    }
+    bail!("Module information of {} bytes is not wrapped in sentinels", len)
+}
+
+// ------------------------------------------------------------------------
+
+/// Parse the text form of `runtime/debug.BuildInfo`
+pub fn parse_modinfo(text: &str) -> Result<BuildInfo> {
+    let mut info = BuildInfo::default();
+    // Which module a `=>` line replaces: None, main or the last dependency
+    let mut last_is_main = None;
+
+    for line in text.lines().filter(|l| !l.is_empty()) {
+        let (key, rest) = line.split_once('\t').unwrap_or((line, ""));
+        match key {
+            "go" => info.go_version = rest.into(),
+            "path" => info.path = rest.into(),
+            "mod" => {
+                info.main = Some(parse_module(rest));
+                last_is_main = Some(true);
+            }
+            "dep" => {
+                info.deps.push(parse_module(rest));
+                last_is_main = Some(false);
+            }
+            "=>" => {
+                let target = match last_is_main {
+                    Some(true) => info.main.as_mut(),
+                    Some(false) => info.deps.last_mut(),
+                    None => None,
+                };
+                match target {
+                    Some(m) if m.replace.is_none() => m.replace = Some(Box::new(parse_module(rest))),
+                    _ => bail!("Unexpected module replacement: {}", line),
+                }
+                last_is_main = None;
+            }
+            "build" => {
+                let (k, v) = rest.split_once('=').unwrap_or((rest, ""));
+                info.settings.push((unquote(k), unquote(v)));
+            }
+            _ => bail!("Unexpected module information line: {}", line),
+        }
+    }
+
+    Ok(info)
+}
+
+fn parse_module(text: &str) -> Module {
+    let mut parts = text.split('\t');
+    let mut part = || parts.next().unwrap_or("").to_string();
+    Module {
+        path: part(),
+        version: part(),
+        sum: part(),
+        replace: None,
+    }
+}
+
+fn unquote(s: &str) -> String {
+    match s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(inner) => {
+            let mut rc = String::with_capacity(inner.len());
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    rc.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('n') => rc.push('\n'),
+                    Some('t') => rc.push('\t'),
+                    Some(c) => rc.push(c),
+                    None => {}
+                }
+            }
+            rc
+        }
+        None => s.into(),
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exe_types::Mapping;
+
+    const MODINFO: &str = "path\texample.com/tool\n\
+        mod\texample.com/tool\tv1.2.3\th1:abc=\n\
+        dep\tgolang.org/x/sys\tv0.1.0\th1:def=\n\
+        dep\texample.com/fork\tv0.0.1\t\n\
+        =>\t../fork\t(devel)\t\n\
+        build\t-ldflags=\"-s -w\"\n\
+        build\tGOARCH=amd64\n";
+
+    fn image() -> Vec<u8> {
+        let mut modinfo = vec![b'x'; MODINFO_SENTINEL];
+        modinfo.extend_from_slice(MODINFO.as_bytes());
+        modinfo.extend_from_slice(&[b'y'; MODINFO_SENTINEL]);
+
+        let mut data = vec![0u8; 48];
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&[8, FLAGS_VERSION_INL]);
+        data.extend_from_slice(&[0; 16]);
+        data.push(8);
+        data.extend_from_slice(b"go1.22.1");
+        data.extend_from_slice(&[modinfo.len() as u8 | 0x80, (modinfo.len() >> 7) as u8]);
+        data.extend_from_slice(&modinfo);
+        data
+    }
+
+    #[test]
+    fn decode_inline() {
+        let info = decode(&image(), &AddressSpace::default()).unwrap().unwrap();
+        assert_eq!(info.offset, 48);
+        assert_eq!(info.go_version, "go1.22.1");
+        assert_eq!(info.path, "example.com/tool");
+        assert_eq!(info.main.unwrap().version, "v1.2.3");
+        assert_eq!(info.deps.len(), 2);
+        assert_eq!(info.deps[1].replace.as_ref().unwrap().path, "../fork");
+        assert_eq!(info.settings[0], ("-ldflags".into(), "-s -w".into()));
+    }
+
+    #[test]
+    fn truncated() {
+        let data = image();
+        let space = AddressSpace::default();
+        assert!(decode(&data[..100], &space).is_err());
+        assert!(decode(&data[..60], &space).unwrap().is_none());
+    }
+
+    #[test]
+    fn unaligned_and_unwrapped() {
+        let space = AddressSpace::default();
+        let mut data = image();
+        data.insert(0, 0);
+        assert!(decode(&data, &space).unwrap().is_none());
+
+        assert_eq!(strip_sentinels("").unwrap(), "");
+        assert!(strip_sentinels(MODINFO).is_err());
+    }
+
+    #[test]
+    fn decode_pointers() {
+        const BASE: u64 = 0x10000;
+        let mut modinfo = vec![b'x'; MODINFO_SENTINEL];
+        modinfo.extend_from_slice(MODINFO.as_bytes());
+        modinfo.extend_from_slice(&[b'y'; MODINFO_SENTINEL]);
+
+        // Header, then the string headers, each followed by its string
+        let mut data = Vec::from(MAGIC);
+        data.extend_from_slice(&[8, 0]);
+        data.extend_from_slice(&(BASE + 32).to_le_bytes());
+        data.extend_from_slice(&(BASE + 56).to_le_bytes());
+        data.extend_from_slice(&(BASE + 48).to_le_bytes());
+        data.extend_from_slice(&8u64.to_le_bytes());
+        data.extend_from_slice(b"go1.16.5");
+        data.extend_from_slice(&(BASE + 72).to_le_bytes());
+        data.extend_from_slice(&(modinfo.len() as u64).to_le_bytes());
+        data.extend_from_slice(&modinfo);
+
+        let len = data.len() as u64;
+        let space = AddressSpace::new(Vec::from([Mapping { name: String::new(), addr: BASE, size: len, offset: 0, file_size: len }]));
+        let info = decode(&data, &space).unwrap().unwrap();
+        assert_eq!(info.go_version, "go1.16.5");
+        assert_eq!(info.path, "example.com/tool");
+        assert_eq!(info.deps.len(), 2);
+
+        assert!(decode(&data, &AddressSpace::default()).is_err());
+    }
+
+}
@@ -0,0 +1,22 @@
+//!
+//! Decoders for the structures embedded in executable images
+//!
+//! Decoders work on the raw file image and return plain Rust values,
+//! the screens module is responsible for presenting them.
+//!
+
+pub mod addr2line;
+pub mod compare;
+#[cfg(feature = "disasm")]
+pub mod disassembly;
+pub mod dwarf;
+pub mod eh_frame;
+pub mod go_buildinfo;
+pub mod reader;
+pub mod rust_info;
+pub mod security;
+pub mod strings;
+pub mod structures;
+pub mod symbol_hash;
+pub mod symbol_versions;
+pub mod unwind_info;
@@ -0,0 +1,225 @@
+//!
+//! Bounds checked cursor over a block of memory
+//!
+
+use anyhow::{anyhow, Result};
+
+// ------------------------------------------------------------------------
+
+/// Byte order of the values being read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+// ------------------------------------------------------------------------
+
+/// Cursor over a slice, every read is checked against the slice bounds
+#[derive(Debug, Clone)]
+pub struct Reader<'r> {
+    data: &'r [u8],
+    pos: usize,
+    pub endian: Endian,
+}
+
+impl<'r> Reader<'r> {
+
+    pub fn new(data: &'r [u8], endian: Endian) -> Self {
+        Self { data, pos: 0, endian }
+    }
+
+    pub fn at(data: &'r [u8], pos: usize, endian: Endian) -> Self {
+        Self { data, pos, endian }
+    }
+
+    // --------------------------------------------------------------------
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    // --------------------------------------------------------------------
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'r [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!(
+                "Read of {} bytes at offset {:#x} out of bounds ({:#x})",
+                len, self.pos, self.data.len()
+            ))?;
+        let rc = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(rc)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.bytes(N)?.try_into()?)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub fn i8(&mut self) -> Result<i8> {
+        Ok(self.u8()? as i8)
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        let a = self.array()?;
+        Ok(match self.endian {
+            Endian::Little => u16::from_le_bytes(a),
+            Endian::Big => u16::from_be_bytes(a),
+        })
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        let a = self.array()?;
+        Ok(match self.endian {
+            Endian::Little => u32::from_le_bytes(a),
+            Endian::Big => u32::from_be_bytes(a),
+        })
+    }
+
+    pub fn i32(&mut self) -> Result<i32> {
+        Ok(self.u32()? as i32)
+    }
+
+    pub fn u64(&mut self) -> Result<u64> {
+        let a = self.array()?;
+        Ok(match self.endian {
+            Endian::Little => u64::from_le_bytes(a),
+            Endian::Big => u64::from_be_bytes(a),
+        })
+    }
+
+    pub fn i64(&mut self) -> Result<i64> {
+        Ok(self.u64()? as i64)
+    }
+
+    /// Unsigned value of `size` bytes (1, 2, 4 or 8)
+    pub fn uint(&mut self, size: usize) -> Result<u64> {
+        match size {
+            1 => self.u8().map(u64::from),
+            2 => self.u16().map(u64::from),
+            4 => self.u32().map(u64::from),
+            8 => self.u64(),
+            v => Err(anyhow!("Unsupported integer size {}", v)),
+        }
+    }
+
+    /// Unsigned LEB128 value, also used for Go varints
+    pub fn uleb128(&mut self) -> Result<u64> {
+        let mut rc: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                rc |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(rc);
+            }
+        }
+    }
+
+    /// Signed LEB128 value
+    pub fn sleb128(&mut self) -> Result<i64> {
+        let mut rc: i64 = 0;
+        let mut shift = 0;
+        let mut byte;
+        loop {
+            byte = self.u8()?;
+            if shift < 64 {
+                rc |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        if shift < 64 && byte & 0x40 != 0 {
+            rc |= -1 << shift;
+        }
+        Ok(rc)
+    }
+
+    /// Nul terminated string, the terminator is consumed
+    pub fn cstr(&mut self) -> Result<&'r str> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let len = rest.iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| anyhow!("Unterminated string at offset {:#x}", self.pos))?;
+        let s = std::str::from_utf8(&rest[..len])?;
+        self.pos += len + 1;
+        Ok(s)
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+/// Nul terminated string at `offset` within `data`
+pub fn cstr_at(data: &[u8], offset: usize) -> Result<&str> {
+    Reader::at(data, offset, Endian::Little).cstr()
+}
+
+/// Offset of the first occurrence of `pattern` at or after `start`
+pub fn find(data: &[u8], pattern: &[u8], start: usize) -> Option<usize> {
+    let first = *pattern.first()?;
+    let mut pos = start;
+    while pos + pattern.len() <= data.len() {
+        pos += data[pos..].iter().position(|b| *b == first)?;
+        if data[pos..].starts_with(pattern) {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+    None
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leb128() {
+        let data = [0xe5, 0x8e, 0x26, 0x7f, 0x80, 0x7f];
+        let mut r = Reader::new(&data, Endian::Little);
+        assert_eq!(r.uleb128().unwrap(), 624485);
+        assert_eq!(r.sleb128().unwrap(), -1);
+        assert_eq!(r.sleb128().unwrap(), -128);
+        assert!(r.u8().is_err());
+    }
+
+    #[test]
+    fn bounds() {
+        let data = [1, 2, 3];
+        let mut r = Reader::new(&data, Endian::Big);
+        assert_eq!(r.u16().unwrap(), 0x0102);
+        assert!(r.u16().is_err());
+        assert_eq!(r.u8().unwrap(), 3);
+        assert!(r.cstr().is_err());
+        assert!(r.bytes(usize::MAX).is_err());
+    }
+
+}
@@ -0,0 +1,327 @@
+//!
+//! Decoder for the metadata left in executables built by rustc
+//!
+//! - The compiler version from `.comment` (ELF) or `__DATA,__const` (Mach-O)
+//! - Crate names and versions from the source paths embedded for panic
+//!   locations, e.g. `.cargo/registry/src/<index>/serde-1.0.193/src/de.rs`
+//! - The dependency list written by `cargo auditable` to `.dep-v0`
+//!
+//! - <https://github.com/rust-secure-code/cargo-auditable/blob/master/PARSING.md>
+
+use anyhow::{anyhow, Result};
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+use serde::Deserialize;
+
+use crate::exe_types::ExeRef;
+
+use super::reader::find;
+
+// ------------------------------------------------------------------------
+
+const RUSTC_VERSION: &[u8] = b"rustc version ";
+const RUSTC_PATH: &[u8] = b"/rustc/";
+const REGISTRY_PATH: &[u8] = b".cargo/registry/src/";
+const GIT_PATH: &[u8] = b".cargo/git/checkouts/";
+/// Dependencies vendored into the standard library
+const STD_DEPS_PATH: &[u8] = b"/rust/deps/";
+
+/// Largest decompressed auditable dependency list accepted
+const AUDITABLE_LIMIT: usize = 8 * 1024 * 1024;
+
+// ------------------------------------------------------------------------
+
+/// Crate found in an embedded source path
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Crate {
+    pub name: String,
+    pub version: String,
+    /// `registry`, `git` or `std`
+    pub source: &'static str,
+}
+
+/// Package from the `cargo auditable` dependency list
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub source: String,
+    #[serde(default = "runtime")]
+    pub kind: String,
+    #[serde(default)]
+    pub dependencies: Vec<usize>,
+    #[serde(default)]
+    pub root: bool,
+}
+
+fn runtime() -> String {
+    "runtime".into()
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionInfo {
+    packages: Vec<Package>,
+}
+
+/// Decoded Rust metadata
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RustInfo {
+    /// e.g. `rustc version 1.75.0 (82e1608df 2023-12-21)`
+    pub compiler: Option<String>,
+    /// Commit hash of the standard library from `/rustc/<hash>/` paths
+    pub commit: Option<String>,
+    pub crates: Vec<Crate>,
+    /// Source files of the crate itself named in panic locations
+    pub panic_locations: Vec<String>,
+    pub auditable: Option<Vec<Package>>,
+}
+
+impl RustInfo {
+    pub fn is_empty(&self) -> bool {
+        self.compiler.is_none()
+            && self.commit.is_none()
+            && self.crates.is_empty()
+            && self.auditable.is_none()
+    }
+}
+
+// ------------------------------------------------------------------------
+
+/// Sections holding the panic location strings
+const STRING_SECTIONS: &[&str] = &[".rodata", ".rdata", "__TEXT,__const", "__TEXT,__cstring"];
+
+/// Does the image look like it was produced by rustc? Only the whole
+/// file is searched for standard library paths when it has none of the
+/// sections they would be in
+pub fn is_present(exe: ExeRef) -> bool {
+    if compiler_version(exe).is_some() {
+        return true;
+    }
+    let sections: Vec<&[u8]> = STRING_SECTIONS.iter().filter_map(|name| exe.section_data(name)).collect();
+    if sections.is_empty() {
+        find(exe.mmap(), RUSTC_PATH, 0).is_some()
+    } else {
+        sections.iter().any(|data| find(data, RUSTC_PATH, 0).is_some())
+    }
+}
+
+pub fn decode(exe: ExeRef) -> Result<RustInfo> {
+    let mut crates = Vec::new();
+    let mut panic_locations = Vec::new();
+    let mut commit = None;
+
+    for run in path_runs(exe.mmap()) {
+        for_each_path(run, REGISTRY_PATH, |path| {
+            // Skip the registry index directory to get `<name>-<version>`
+            if let Some((name, version)) = path
+                .split('/')
+                .nth(1)
+                .and_then(split_name_version)
+            {
+                crates.push(Crate { name: name.into(), version: version.into(), source: "registry" });
+            }
+        });
+
+        for_each_path(run, STD_DEPS_PATH, |path| {
+            if let Some((name, version)) = path.split('/').next().and_then(split_name_version) {
+                crates.push(Crate { name: name.into(), version: version.into(), source: "std" });
+            }
+        });
+
+        for_each_path(run, GIT_PATH, |path| {
+            let mut parts = path.split('/');
+            if let (Some(repo), Some(rev)) = (parts.next(), parts.next()) {
+                let name = repo.rsplit_once('-').map(|r| r.0).unwrap_or(repo);
+                crates.push(Crate { name: name.into(), version: rev.into(), source: "git" });
+            }
+        });
+
+        for_each_path(run, RUSTC_PATH, |path| {
+            if commit.is_none() {
+                commit = path.split('/').next()
+                    .filter(|h| h.len() == 40 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .map(String::from);
+            }
+        });
+
+        for_each_source(run, |path| {
+            if !path.contains(".cargo/") && !path.starts_with("/rust") && !path.starts_with("library/") {
+                panic_locations.push(path.to_string());
+            }
+        });
+    }
+
+    crates.sort();
+    crates.dedup();
+    panic_locations.sort();
+    panic_locations.dedup();
+
+    Ok(RustInfo {
+        compiler: compiler_version(exe),
+        commit,
+        crates,
+        panic_locations,
+        auditable: auditable(exe)?,
+    })
+}
+
+// ------------------------------------------------------------------------
+
+/// Compiler version string from the comment or constant data section
+pub fn compiler_version(exe: ExeRef) -> Option<String> {
+    [".comment", "__DATA,__const", "__TEXT,__const"]
+        .iter()
+        .filter_map(|name| exe.section_data(name))
+        .find_map(|data| {
+            let start = find(data, RUSTC_VERSION, 0)?;
+            let len = data[start..]
+                .iter()
+                .position(|b| *b == 0 || !(b.is_ascii_graphic() || *b == b' '))
+                .unwrap_or(data.len() - start);
+            let version = std::str::from_utf8(&data[start..start + len]).ok()?;
+            // Constant data is not terminated, stop after the date
+            Some(match version.find(')') {
+                Some(end) => version[..=end].into(),
+                None => version.into(),
+            })
+        })
+}
+
+/// Decompress and parse the `cargo auditable` dependency list
+pub fn auditable(exe: ExeRef) -> Result<Option<Vec<Package>>> {
+    let data = match [".dep-v0", "__DATA,.dep-v0"]
+        .iter()
+        .find_map(|name| exe.section_data(name))
+    {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let json = decompress_to_vec_zlib_with_limit(data, AUDITABLE_LIMIT)
+        .map_err(|e| anyhow!("Unable to decompress .dep-v0 section: {:?}", e.status))?;
+    let info: VersionInfo = serde_json::from_slice(&json)?;
+
+    Ok(Some(info.packages))
+}
+
+// ------------------------------------------------------------------------
+
+/// Split `serde-1.0.193` at the hyphen that starts the version
+pub fn split_name_version(s: &str) -> Option<(&str, &str)> {
+    s.match_indices('-')
+        .map(|(idx, _)| (&s[..idx], &s[idx + 1..]))
+        .find(|(name, version)| {
+            !name.is_empty()
+                && version.split('.').take(3).filter(|p| {
+                    p.bytes().next().is_some_and(|b| b.is_ascii_digit())
+                }).count() == 3
+        })
+}
+
+fn is_path_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'/' | b'.' | b'_' | b'-' | b'+' | b'\\')
+}
+
+/// Runs of path characters with a `/` in them, found in one pass from
+/// each `/` out to the ends of its run
+fn path_runs(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let slash = pos + data[pos..].iter().position(|b| *b == b'/')?;
+        let start = data[pos..slash].iter().rposition(|b| !is_path_char(*b)).map_or(pos, |p| pos + p + 1);
+        let end = data[slash..].iter().position(|b| !is_path_char(*b)).map_or(data.len(), |p| slash + p);
+        pos = end;
+        Some(&data[start..end])
+    })
+}
+
+/// Call `f` with the remainder of each path following `pattern`, up to
+/// the next `pattern` when several are packed together
+fn for_each_path(data: &[u8], pattern: &[u8], mut f: impl FnMut(&str)) {
+    let mut next = find(data, pattern, 0);
+    // End of the run of path characters, shared by the matches inside it
+    let mut run_end = 0;
+    while let Some(start) = next {
+        let rest = start + pattern.len();
+        if rest >= run_end {
+            run_end = data[rest..].iter().position(|b| !is_path_char(*b)).map_or(data.len(), |p| rest + p);
+        }
+        next = find(data, pattern, rest);
+        let end = next.map_or(run_end, |n| n.min(run_end));
+        if let Ok(path) = std::str::from_utf8(&data[rest..end]) {
+            f(path);
+        }
+    }
+}
+
+/// Call `f` with every path ending in `.rs`, panic location strings are
+/// packed without separators so the start is the first path character
+fn for_each_source(data: &[u8], mut f: impl FnMut(&str)) {
+    let mut pos = 0;
+    while let Some(end) = find(data, b".rs", pos) {
+        // The preceding `.rs`, if there is one, ends the previous packed
+        // string, so only the text since then needs looking at
+        let start = data[pos..end].iter().rposition(|b| !is_path_char(*b)).map_or(pos, |p| pos + p + 1);
+        pos = end + 3;
+        let path = match std::str::from_utf8(&data[start..pos]) {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        // Start at the `src/` directory when it is preceded by other text
+        let path = match path.rfind("src/") {
+            Some(idx) if idx > 0 && !path[..idx].ends_with('/') => &path[idx..],
+            _ => path,
+        };
+        if path.contains('/') {
+            f(path);
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_version() {
+        assert_eq!(split_name_version("serde-1.0.193"), Some(("serde", "1.0.193")));
+        assert_eq!(split_name_version("x25519-dalek-2.0.0-rc.3"), Some(("x25519-dalek", "2.0.0-rc.3")));
+        assert_eq!(split_name_version("base64-0.21.7"), Some(("base64", "0.21.7")));
+        assert_eq!(split_name_version("no-version"), None);
+    }
+
+    #[test]
+    fn paths() {
+        let data = b"\0/home/u/.cargo/registry/src/index.crates.io-6f17d22bba15001f/\
+            anyhow-1.0.75/src/lib.rsoverflowsrc/main.rs\0";
+        let mut crates = Vec::new();
+        for_each_path(data, REGISTRY_PATH, |p| crates.push(p.to_string()));
+        assert_eq!(crates, ["index.crates.io-6f17d22bba15001f/anyhow-1.0.75/src/lib.rsoverflowsrc/main.rs"]);
+
+        let mut sources = Vec::new();
+        for_each_source(data, |p| sources.push(p.to_string()));
+        assert_eq!(sources[1], "src/main.rs");
+
+        let runs: Vec<&[u8]> = path_runs(b"no path a/b.rs\0/x y/").collect();
+        assert_eq!(runs, [&b"a/b.rs"[..], b"/x", b"y/"]);
+    }
+
+    #[test]
+    fn long_path_runs() {
+        // Packed runs like these took quadratic time to split
+        let data = b"a/b.rs".repeat(200_000);
+        let mut count = 0;
+        for_each_source(&data, |p| {
+            assert_eq!(p, "a/b.rs");
+            count += 1;
+        });
+        assert_eq!(count, 200_000);
+
+        let data = [REGISTRY_PATH, b"a/"].concat().repeat(100_000);
+        let mut count = 0;
+        for_each_path(&data, REGISTRY_PATH, |_| count += 1);
+        assert_eq!(count, 100_000);
+    }
+
+}
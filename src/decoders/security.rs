@@ -0,0 +1,439 @@
+//!
+//! Binary hardening report in the manner of `checksec`, for ELF, Mach-O
+//! and PE images
+//!
+//! - <https://github.com/slimm609/checksec>
+//! - <https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#dll-characteristics>
+//!
+
+use anyhow::Result;
+
+use crate::{
+    decoders::reader::{Endian, Reader},
+    exe_types::{elf, macho64::{self, fixed_str}, pe::nt_header_offset, ExeRef, Segment, Symbol},
+};
+
+// ------------------------------------------------------------------------
+
+/// How a check reflects on the hardening of the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Enabled,
+    Partial,
+    Disabled,
+    /// Information only, such as the symbol table being present
+    Info,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    /// Abbreviation used in the file list
+    pub short: &'static str,
+    pub level: Level,
+    pub value: String,
+}
+
+impl Check {
+    fn new(name: &'static str, short: &'static str, level: Level, value: impl Into<String>) -> Self {
+        Self { name, short, level, value: value.into() }
+    }
+
+    fn flag(name: &'static str, short: &'static str, enabled: bool) -> Self {
+        if enabled {
+            Self::new(name, short, Level::Enabled, "Yes")
+        } else {
+            Self::new(name, short, Level::Disabled, "No")
+        }
+    }
+}
+
+pub fn is_present(exe: ExeRef) -> bool {
+    Format::of(exe.mmap()).is_some()
+}
+
+/// Run the checks that apply to the format of the image, none for
+/// anything that is not an executable
+pub fn checks(exe: ExeRef) -> Result<Vec<Check>> {
+    match Format::of(exe.mmap()) {
+        Some(Format::Elf) => elf_checks(exe),
+        Some(Format::MachO(is64)) => macho_checks(exe, is64),
+        Some(Format::Pe) => pe_checks(exe),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Abbreviations of the enabled checks, partially enabled ones in lower
+/// case, for a one line summary
+pub fn summary(checks: &[Check]) -> String {
+    checks
+        .iter()
+        .filter_map(|c| match c.level {
+            Level::Enabled => Some(c.short.to_string()),
+            Level::Partial => Some(c.short.to_lowercase()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Elf,
+    MachO(bool),
+    Pe,
+}
+
+impl Format {
+    fn of(mmap: &[u8]) -> Option<Self> {
+        match mmap.get(..4)? {
+            [0x7f, b'E', b'L', b'F'] => Some(Self::Elf),
+            [0xce, 0xfa, 0xed, 0xfe] => Some(Self::MachO(false)),
+            [0xcf, 0xfa, 0xed, 0xfe] => Some(Self::MachO(true)),
+            [b'M', b'Z', ..] => Some(Self::Pe),
+            _ => None,
+        }
+    }
+}
+
+fn has_symbol(symbols: &[Symbol], names: &[&str]) -> bool {
+    symbols.iter().any(|s| names.contains(&s.name.as_str()))
+}
+
+// ------------------------------------------------------------------------
+// ELF
+
+const ET_EXEC: u16 = 2;
+const ET_DYN: u16 = 3;
+
+const PT_INTERP: u32 = 3;
+const PT_NOTE: u32 = 4;
+const PT_GNU_STACK: u32 = 0x6474e551;
+const PT_GNU_RELRO: u32 = 0x6474e552;
+const PT_GNU_PROPERTY: u32 = 0x6474e553;
+const PF_X: u32 = 0x1;
+
+const DT_RPATH: u64 = 15;
+const DT_BIND_NOW: u64 = 24;
+const DT_RUNPATH: u64 = 29;
+const DT_FLAGS: u64 = 30;
+const DT_FLAGS_1: u64 = 0x6ffffffb;
+const DF_BIND_NOW: u64 = 0x8;
+const DF_1_NOW: u64 = 0x1;
+const DF_1_PIE: u64 = 0x0800_0000;
+
+const SHT_SYMTAB: u32 = 2;
+
+const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+
+struct ElfImage<'e> {
+    data: &'e [u8],
+    endian: Endian,
+    is64: bool,
+    phdrs: Vec<Segment>,
+}
+
+impl<'e> ElfImage<'e> {
+    fn new(exe: ExeRef<'e>) -> Self {
+        Self {
+            data: exe.mmap(),
+            endian: exe.endianness().unwrap_or(Endian::Little),
+            is64: exe.bitness() == Some(64),
+            phdrs: exe.segments(),
+        }
+    }
+
+    fn segment(&self, kind: u32) -> Option<&Segment> {
+        self.phdrs.iter().find(|p| p.kind == kind)
+    }
+
+    /// Feature bits of the GNU property notes, by property type
+    fn gnu_properties(&self) -> Vec<(u32, u32)> {
+        let align = if self.is64 { 8 } else { 4 };
+        let mut properties = Vec::new();
+        let notes = self.phdrs.iter().filter(|p| p.kind == PT_GNU_PROPERTY || p.kind == PT_NOTE);
+        for phdr in notes {
+            let data = phdr.data(self.data).unwrap_or(&[]);
+            let mut rdr = Reader::new(data, self.endian);
+            while let (Ok(namesz), Ok(descsz), Ok(kind)) = (rdr.u32(), rdr.u32(), rdr.u32()) {
+                let name = rdr.bytes(align_up(namesz as usize, 4)).unwrap_or(&[]);
+                let desc_start = rdr.pos();
+                let desc_end = desc_start.saturating_add(descsz as usize);
+                if kind == NT_GNU_PROPERTY_TYPE_0 && name.starts_with(b"GNU\0") {
+                    let mut desc = Reader::new(data.get(desc_start..desc_end).unwrap_or(&[]), self.endian);
+                    while let (Ok(pr_type), Ok(pr_datasz)) = (desc.u32(), desc.u32()) {
+                        let start = desc.pos();
+                        if pr_datasz >= 4 {
+                            properties.push((pr_type, desc.u32().unwrap_or(0)));
+                        }
+                        desc.seek(start + align_up(pr_datasz as usize, align));
+                    }
+                }
+                rdr.seek(align_up(desc_end, 4));
+            }
+        }
+        properties
+    }
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+fn elf_checks(exe: ExeRef) -> Result<Vec<Check>> {
+    let image = ElfImage::new(exe);
+    let dynamic = elf::dynamic_entries(exe);
+    let tag = |t| dynamic.iter().find(|d| d.tag == t).map(|d| d.value);
+    let symbols = exe.symbols();
+    let e_type = Reader::at(image.data, 16, image.endian).u16()?;
+
+    let pie = match e_type {
+        ET_EXEC => Check::new("PIE", "PIE", Level::Disabled, "No"),
+        ET_DYN if tag(DT_FLAGS_1).unwrap_or(0) & DF_1_PIE != 0 || image.segment(PT_INTERP).is_some() =>
+            Check::new("PIE", "PIE", Level::Enabled, "Yes"),
+        ET_DYN => Check::new("PIE", "PIE", Level::Enabled, "Shared object"),
+        _ => Check::new("PIE", "PIE", Level::Info, "Relocatable object"),
+    };
+
+    let nx = match image.segment(PT_GNU_STACK) {
+        Some(stack) if stack.flags & PF_X == 0 => Check::new("NX Stack", "NX", Level::Enabled, "Yes"),
+        Some(_) => Check::new("NX Stack", "NX", Level::Disabled, "No, the stack is executable"),
+        None => Check::new("NX Stack", "NX", Level::Disabled, "No PT_GNU_STACK"),
+    };
+
+    let bind_now = tag(DT_BIND_NOW).is_some()
+        || tag(DT_FLAGS).unwrap_or(0) & DF_BIND_NOW != 0
+        || tag(DT_FLAGS_1).unwrap_or(0) & DF_1_NOW != 0;
+    let relro = match (image.segment(PT_GNU_RELRO).is_some(), bind_now) {
+        (true, true) => Check::new("RELRO", "RELRO", Level::Enabled, "Full"),
+        (true, false) => Check::new("RELRO", "RELRO", Level::Partial, "Partial"),
+        (false, _) => Check::new("RELRO", "RELRO", Level::Disabled, "No"),
+    };
+
+    let canary = Check::flag("Stack Canary", "SSP", has_symbol(&symbols, &["__stack_chk_fail", "__stack_chk_guard"]));
+
+    let mut fortified: Vec<&str> = symbols
+        .iter()
+        .map(|s| s.name.as_str())
+        .filter(|n| n.starts_with("__") && n.ends_with("_chk") && *n != "__stack_chk_fail")
+        .collect();
+    fortified.sort_unstable();
+    fortified.dedup();
+    let fortify = match fortified.len() {
+        0 => Check::new("FORTIFY_SOURCE", "FORT", Level::Disabled, "No"),
+        n => Check::new("FORTIFY_SOURCE", "FORT", Level::Enabled, format!("Yes, {} fortified functions", n)),
+    };
+
+    let properties = image.gnu_properties();
+    let feature = |kind| properties.iter().filter(|p| p.0 == kind).fold(0, |f, p| f | p.1);
+    let (x86, arm) = (feature(GNU_PROPERTY_X86_FEATURE_1_AND), feature(GNU_PROPERTY_AARCH64_FEATURE_1_AND));
+    let cet_names = [(x86 & 1, "IBT"), (x86 & 2, "SHSTK"), (arm & 1, "BTI"), (arm & 2, "PAC")];
+    let cet_enabled: Vec<&str> = cet_names.iter().filter(|c| c.0 != 0).map(|c| c.1).collect();
+    let cet = match cet_enabled.len() {
+        0 => Check::new("CET / BTI", "CET", Level::Disabled, "No"),
+        2 => Check::new("CET / BTI", "CET", Level::Enabled, cet_enabled.join(", ")),
+        _ => Check::new("CET / BTI", "CET", Level::Partial, cet_enabled.join(", ")),
+    };
+
+    let string = |t| dynamic.iter().find(|d| d.tag == t).and_then(|d| d.string.clone());
+    let path_check = |name, t| match string(t) {
+        Some(path) => Check::new(name, "", Level::Disabled, path),
+        None => Check::new(name, "", Level::Info, "None"),
+    };
+
+    let stripped = !exe.sections().iter().any(|s| s.kind == SHT_SYMTAB);
+
+    Ok(Vec::from([
+        pie, nx, relro, canary, fortify, cet,
+        path_check("RPATH", DT_RPATH),
+        path_check("RUNPATH", DT_RUNPATH),
+        Check::new("Stripped", "", Level::Info, if stripped { "Yes" } else { "No, has a symbol table" }),
+    ]))
+}
+
+// ------------------------------------------------------------------------
+// Mach-O
+
+const MH_ALLOW_STACK_EXECUTION: u32 = 0x0002_0000;
+const MH_PIE: u32 = 0x0020_0000;
+const MH_NO_HEAP_EXECUTION: u32 = 0x0100_0000;
+
+const LC_CODE_SIGNATURE: u32 = 0x1d;
+
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+const CS_RUNTIME: u32 = 0x0001_0000;
+
+fn macho_checks(exe: ExeRef, is64: bool) -> Result<Vec<Check>> {
+    let data = exe.mmap();
+    let layout = if is64 { &macho64::LAYOUT64 } else { &macho64::LAYOUT32 };
+    let flags = Reader::at(data, 24, Endian::Little).u32()?;
+
+    let mut segments = Vec::new();
+    let mut signature = None;
+    let mut symbol_count = 0;
+    for (cmd, cmd_offset, _) in macho64::load_commands(data, layout) {
+        let mut rdr = Reader::at(data, cmd_offset + 8, Endian::Little);
+        match cmd {
+            macho64::LC_SEGMENT | macho64::LC_SEGMENT_64 => segments.push(fixed_str(rdr.bytes(16)?)),
+            LC_CODE_SIGNATURE => signature = Some((rdr.u32()? as usize, rdr.u32()? as usize)),
+            macho64::LC_SYMTAB => {
+                let _symoff = rdr.u32()?;
+                symbol_count = rdr.u32()?;
+            }
+            _ => {}
+        }
+    }
+
+    let symbols = exe.symbols();
+    let restrict = segments.iter().any(|s| s == "__RESTRICT") || exe.section("__RESTRICT,__restrict").is_some();
+
+    let nx = match (flags & MH_ALLOW_STACK_EXECUTION == 0, flags & MH_NO_HEAP_EXECUTION != 0) {
+        (true, true) => Check::new("NX Stack / Heap", "NX", Level::Enabled, "Yes"),
+        (true, false) => Check::new("NX Stack / Heap", "NX", Level::Enabled, "Stack"),
+        (false, _) => Check::new("NX Stack / Heap", "NX", Level::Disabled, "No, the stack is executable"),
+    };
+
+    let hardened = match signature {
+        None => Check::new("Hardened Runtime", "HR", Level::Disabled, "Not signed"),
+        Some((offset, size)) => match code_directory_flags(data.get(offset..offset.saturating_add(size)).unwrap_or(&[])) {
+            Some(cs_flags) if cs_flags & CS_RUNTIME != 0 => Check::new("Hardened Runtime", "HR", Level::Enabled, "Yes"),
+            Some(_) => Check::new("Hardened Runtime", "HR", Level::Disabled, "No"),
+            None => Check::new("Hardened Runtime", "HR", Level::Disabled, "No code directory"),
+        },
+    };
+
+    Ok(Vec::from([
+        Check::flag("PIE", "PIE", flags & MH_PIE != 0),
+        nx,
+        hardened,
+        Check::flag("Stack Canary", "SSP", has_symbol(&symbols, &["___stack_chk_fail", "___stack_chk_guard"])),
+        Check::flag("__RESTRICT Segment", "RESTRICT", restrict),
+        Check::flag("ARC", "ARC", has_symbol(&symbols, &["_objc_release", "_objc_retain", "_objc_autorelease"])),
+        Check::new("Stripped", "", Level::Info, if symbol_count > 0 { "No, has a symbol table" } else { "Yes" }),
+    ]))
+}
+
+/// Flags of the code directory in an embedded code signature, which is
+/// big endian unlike the rest of the image
+fn code_directory_flags(blob: &[u8]) -> Option<u32> {
+    let mut rdr = Reader::new(blob, Endian::Big);
+    if rdr.u32().ok()? != CSMAGIC_EMBEDDED_SIGNATURE {
+        return None;
+    }
+    let _length = rdr.u32().ok()?;
+    let count = rdr.u32().ok()?;
+    for idx in 0..count as usize {
+        rdr.seek(12 + idx * 8);
+        let _slot = rdr.u32().ok()?;
+        let offset = rdr.u32().ok()? as usize;
+        let mut dir = Reader::at(blob, offset, Endian::Big);
+        if dir.u32().ok()? == CSMAGIC_CODEDIRECTORY {
+            dir.seek(offset + 12);
+            return dir.u32().ok();
+        }
+    }
+    None
+}
+
+// ------------------------------------------------------------------------
+// PE
+
+const IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA: u16 = 0x0020;
+const IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE: u16 = 0x0040;
+const IMAGE_DLLCHARACTERISTICS_NX_COMPAT: u16 = 0x0100;
+const IMAGE_DLLCHARACTERISTICS_NO_SEH: u16 = 0x0400;
+const IMAGE_DLLCHARACTERISTICS_GUARD_CF: u16 = 0x4000;
+
+const IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG: usize = 10;
+
+fn pe_checks(exe: ExeRef) -> Result<Vec<Check>> {
+    let data = exe.mmap();
+    let nt_offset = nt_header_offset(data)?;
+    let opt_offset = nt_offset + 24;
+    let mut rdr = Reader::at(data, opt_offset, Endian::Little);
+    let is64 = rdr.u16()? == 0x20b;
+    rdr.seek(opt_offset + 28);
+    let image_base = rdr.u32()?.into();
+    rdr.seek(opt_offset + 70);
+    let dll_flags = rdr.u16()?;
+
+    let aslr = match (dll_flags & IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE != 0, dll_flags & IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA != 0) {
+        (true, true) => Check::new("ASLR", "ASLR", Level::Enabled, "Yes, high entropy"),
+        (true, false) => Check::new("ASLR", "ASLR", Level::Enabled, "Yes"),
+        (false, _) => Check::new("ASLR", "ASLR", Level::Disabled, "No"),
+    };
+
+    // SafeSEH only applies to 32 bit images, 64 bit exception handling
+    // is table based
+    let safe_seh = if is64 {
+        Check::new("SafeSEH", "SEH", Level::Info, "Not applicable to 64 bit images")
+    } else if dll_flags & IMAGE_DLLCHARACTERISTICS_NO_SEH != 0 {
+        Check::new("SafeSEH", "SEH", Level::Enabled, "No SEH handlers")
+    } else {
+        match load_config(exe, image_base, opt_offset + 96) {
+            Some(config) => {
+                let mut rdr = Reader::at(config, 64, Endian::Little);
+                match (rdr.u32(), rdr.u32()) {
+                    (Ok(table), Ok(count)) if table != 0 => Check::new("SafeSEH", "SEH", Level::Enabled, format!("Yes, {} handlers", count)),
+                    _ => Check::new("SafeSEH", "SEH", Level::Disabled, "No"),
+                }
+            }
+            None => Check::new("SafeSEH", "SEH", Level::Disabled, "No load configuration"),
+        }
+    };
+
+    Ok(Vec::from([
+        aslr,
+        Check::flag("DEP", "DEP", dll_flags & IMAGE_DLLCHARACTERISTICS_NX_COMPAT != 0),
+        Check::flag("Control Flow Guard", "CFG", dll_flags & IMAGE_DLLCHARACTERISTICS_GUARD_CF != 0),
+        safe_seh,
+    ]))
+}
+
+/// Contents of the load configuration directory of a 32 bit image, the
+/// data directories start at `dirs_offset`
+fn load_config(exe: ExeRef<'_>, image_base: u64, dirs_offset: usize) -> Option<&[u8]> {
+    let mut rdr = Reader::at(exe.mmap(), dirs_offset + IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG * 8, Endian::Little);
+    let rva = u64::from(rdr.u32().ok()?);
+    if rva == 0 {
+        return None;
+    }
+    let addr = image_base + rva;
+    let section = exe.sections().into_iter().find(|s| addr >= s.addr && addr - s.addr < s.file_size)?;
+    // The directory size is unreliable, linkers record the size of an
+    // older layout, so take the rest of the section
+    let offset = (section.offset + addr - section.addr) as usize;
+    exe.mmap().get(offset..(section.offset + section.file_size) as usize)
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_levels() {
+        let checks = [
+            Check::new("PIE", "PIE", Level::Enabled, "Yes"),
+            Check::new("RELRO", "RELRO", Level::Partial, "Partial"),
+            Check::new("Stack Canary", "SSP", Level::Disabled, "No"),
+            Check::new("Stripped", "", Level::Info, "Yes"),
+        ];
+        assert_eq!(summary(&checks), "PIE relro");
+    }
+
+    #[test]
+    fn code_signature() {
+        let words: &[u32] = &[
+            CSMAGIC_EMBEDDED_SIGNATURE, 40, 1, 0, 20,
+            CSMAGIC_CODEDIRECTORY, 20, 0x20400, CS_RUNTIME | 0x2,
+        ];
+        let blob: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+        assert_eq!(code_directory_flags(&blob), Some(CS_RUNTIME | 0x2));
+        assert_eq!(code_directory_flags(&blob[4..]), None);
+    }
+}
@@ -0,0 +1,201 @@
+//!
+//! Runs of printable characters in the file, in the manner of `strings`
+//!
+//! Single byte runs are ASCII, or UTF-8 when they hold a valid multi-byte
+//! sequence. UTF-16 runs are limited to ASCII and Latin-1 characters, as
+//! nearly every pair of bytes would otherwise count as a character.
+//!
+
+use std::fmt;
+
+use crate::exe_types::Section;
+
+// ------------------------------------------------------------------------
+
+pub const DEFAULT_MIN_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Ascii,
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 4] = [Self::Ascii, Self::Utf8, Self::Utf16Le, Self::Utf16Be];
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ascii => "ASCII",
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+        })
+    }
+}
+
+/// String found in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    /// File offset of the first byte
+    pub offset: usize,
+    /// Length in bytes
+    pub len: usize,
+    pub encoding: Encoding,
+    pub text: String,
+}
+
+/// Find the runs of at least `min_len` characters, in file order
+pub fn scan(data: &[u8], min_len: usize) -> Vec<Hit> {
+    let min_len = min_len.max(1);
+    let mut hits = scan_bytes(data, min_len);
+    for alignment in 0..2 {
+        hits.extend(scan_utf16(data, min_len, alignment, Encoding::Utf16Le));
+        hits.extend(scan_utf16(data, min_len, alignment, Encoding::Utf16Be));
+    }
+    hits.sort_by_key(|h| (h.offset, h.encoding != Encoding::Ascii && h.encoding != Encoding::Utf8));
+
+    // The same UTF-16 text is seen in both byte orders a byte apart, so keep
+    // the first of any hits that overlap by more than a byte
+    let mut kept: Vec<Hit> = Vec::with_capacity(hits.len());
+    for hit in hits {
+        match kept.last() {
+            Some(last) if hit.offset + 1 < last.offset + last.len => {}
+            _ => kept.push(hit),
+        }
+    }
+    kept
+}
+
+fn printable(ch: char) -> bool {
+    ch == '\t' || !ch.is_control()
+}
+
+fn scan_bytes(data: &[u8], min_len: usize) -> Vec<Hit> {
+    let mut hits = Vec::new();
+    let mut start = 0;
+    let mut chars = 0;
+    let mut ascii = true;
+    let mut pos = 0;
+
+    let mut finish = |start: usize, end: usize, chars: usize, ascii: bool| {
+        if chars >= min_len {
+            hits.push(Hit {
+                offset: start,
+                len: end - start,
+                encoding: if ascii { Encoding::Ascii } else { Encoding::Utf8 },
+                text: String::from_utf8_lossy(&data[start..end]).into_owned(),
+            });
+        }
+    };
+
+    while pos < data.len() {
+        let len = match data[pos] {
+            0x00..=0x7f => 1,
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 0,
+        };
+        let ch = data
+            .get(pos..pos + len.max(1))
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|s| s.chars().next())
+            .filter(|&ch| len > 0 && printable(ch));
+
+        match ch {
+            Some(ch) => {
+                if chars == 0 {
+                    start = pos;
+                    ascii = true;
+                }
+                chars += 1;
+                ascii &= ch.is_ascii();
+                pos += len;
+            }
+            None => {
+                finish(start, pos, chars, ascii);
+                chars = 0;
+                pos += 1;
+            }
+        }
+    }
+    finish(start, pos, chars, ascii);
+
+    hits
+}
+
+fn scan_utf16(data: &[u8], min_len: usize, alignment: usize, encoding: Encoding) -> Vec<Hit> {
+    let mut hits = Vec::new();
+    let mut text = String::new();
+    let mut start = alignment;
+
+    let units = data.get(alignment..).unwrap_or_default().chunks_exact(2);
+    let mut pos = alignment;
+    for unit in units {
+        let unit = match encoding {
+            Encoding::Utf16Be => u16::from_be_bytes([unit[0], unit[1]]),
+            _ => u16::from_le_bytes([unit[0], unit[1]]),
+        };
+        match char::from_u32(unit.into()).filter(|&ch| u32::from(ch) < 0x100 && printable(ch)) {
+            Some(ch) => {
+                if text.is_empty() {
+                    start = pos;
+                }
+                text.push(ch);
+            }
+            None if !text.is_empty() => {
+                if text.chars().count() >= min_len {
+                    hits.push(Hit { offset: start, len: pos - start, encoding, text: std::mem::take(&mut text) });
+                }
+                text.clear();
+            }
+            None => {}
+        }
+        pos += 2;
+    }
+    if text.chars().count() >= min_len {
+        hits.push(Hit { offset: start, len: pos - start, encoding, text });
+    }
+
+    hits
+}
+
+/// Section holding the byte at `offset`, and the virtual address of it
+/// unless the section is not loaded
+pub fn locate(sections: &[Section], offset: usize) -> Option<(&Section, Option<u64>)> {
+    let offset = offset as u64;
+    sections
+        .iter()
+        .find(|s| offset >= s.offset && offset - s.offset < s.file_size)
+        .map(|s| (s, (s.addr != 0).then(|| s.addr.wrapping_add(offset - s.offset))))
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodings() {
+        let mut data = Vec::from(*b"\x01abc\x00/usr/lib\x00caf\xc3\xa9 au lait\xff");
+        data.extend("C:\\Windows".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        data.extend([0, 0, 0]);
+        data.extend("Résumé".encode_utf16().flat_map(|u| u.to_be_bytes()));
+
+        let hits = scan(&data, 4);
+        let found: Vec<_> = hits.iter().map(|h| (h.offset, h.encoding, h.text.as_str())).collect();
+        assert_eq!(found, [
+            (5, Encoding::Ascii, "/usr/lib"),
+            (14, Encoding::Utf8, "café au lait"),
+            (28, Encoding::Utf16Le, "C:\\Windows"),
+            (51, Encoding::Utf16Be, "Résumé"),
+        ]);
+        assert_eq!(hits[2].len, 20);
+        assert!(scan(&data, 13).is_empty());
+    }
+}
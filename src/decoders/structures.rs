@@ -0,0 +1,197 @@
+//!
+//! The structures of an image decoded field by field, for export
+//!
+//! The fields come from the same `FieldMap` tables as the header screens,
+//! so the export holds what the screens show. Each structure holds the
+//! structures its fields expand to, as a load command holds the fields of
+//! its command, and the entries of the table it describes.
+//!
+
+use serde::Serialize;
+
+use crate::{
+    exe_types::{ExeRef, StructureMap},
+    formatter::FieldMap,
+};
+
+// ------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct File {
+    pub file: String,
+    #[serde(rename = "type")]
+    pub file_type: String,
+    pub size: usize,
+    pub structures: Vec<Structure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Structure {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+    pub fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Structure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Field {
+    pub name: String,
+    /// File offset
+    pub offset: usize,
+    pub size: usize,
+    /// Raw bytes in hex
+    pub bytes: String,
+    pub value: String,
+    /// Meaning of the value, from the table of values of the field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Decode every structure the image maps
+pub fn decode(exe: ExeRef) -> File {
+    File {
+        file: exe.filename().display().to_string(),
+        file_type: exe.to_string(),
+        size: exe.len(),
+        structures: exe.structures().iter().map(|s| structure(s, exe.mmap())).collect(),
+    }
+}
+
+/// Decode a structure and the structures it expands to
+fn structure(structure_map: &StructureMap, file: &[u8]) -> Structure {
+    let (start, end) = structure_map.data;
+    Structure {
+        name: structure_map.name.to_string(),
+        offset: start,
+        size: end.saturating_sub(start),
+        fields: fields(structure_map.map, file, structure_map.data),
+        children: structure_map.expand(file).iter().map(|s| structure(s, file)).collect(),
+    }
+}
+
+/// Fields of the block `data` of the file shown by `map`, leaving out those
+/// the file is too short to hold
+pub fn fields(map: &FieldMap, file: &[u8], data: (usize, usize)) -> Vec<Field> {
+    let block = file.get(data.0..data.1.min(file.len())).unwrap_or_default();
+
+    map.fields
+        .iter()
+        .filter(|f| f.string_fn.is_some() || f.string_fn2.is_some())
+        .filter_map(|field| {
+            let (start, end) = field.block_range(data.1.saturating_sub(data.0));
+            let bytes = block.get(start..end)?;
+            Some(Field {
+                name: field.name.to_string(),
+                offset: data.0 + start,
+                size: end.saturating_sub(start),
+                bytes: hex::encode(bytes),
+                value: field.to_string(block),
+                description: field.lookup(block).map(|entry| entry.1.to_string()),
+            })
+        })
+        .collect()
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exe_types;
+    use crate::formatter::{
+        FieldDef, ValTable, BE_HEX, LE_32_STRING, LE_32_USIZE, LE_8_STRING, LE_8_USIZE, MALFORMED,
+        OUT_OF_BOUNDS,
+    };
+
+    const KIND: &ValTable = &[(1, "One", None), (2, "Two", None)];
+
+    const FIELDS: &[FieldDef] = &[
+        FieldDef::new(0, 2, "Magic", Some(BE_HEX)),
+        FieldDef::ignore(2, 2),
+        FieldDef::new(4, 1, "Kind", Some(LE_8_STRING)).val_tbl(LE_8_USIZE, KIND),
+        FieldDef::new(5, 4, "Past End", Some(LE_32_STRING)),
+    ];
+    const MAP: FieldMap = FieldMap::new(FIELDS);
+
+    #[test]
+    fn fields_of_block() {
+        let file = [0xff, 0xca, 0xfe, 0, 0, 2, 0x7f];
+
+        let found = fields(&MAP, &file, (1, 1 + MAP.data_len));
+        assert_eq!(found, [
+            Field {
+                name: "Magic".into(),
+                offset: 1,
+                size: 2,
+                bytes: "cafe".into(),
+                value: "ca fe".into(),
+                description: None,
+            },
+            Field {
+                name: "Kind".into(),
+                offset: 5,
+                size: 1,
+                bytes: "02".into(),
+                value: "2".into(),
+                description: Some("Two".into()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn bad_fields() {
+        // The converter does not match the field length
+        let short = FieldDef::new(0, 2, "Short", Some(LE_32_STRING)).fn_usize(LE_32_USIZE);
+        assert_eq!(short.to_string(&[1, 2, 3, 4]), MALFORMED);
+        assert!(short.to_usize(&[1, 2, 3, 4]).is_err());
+
+        assert_eq!(FIELDS[3].to_string(&[0; 6]), OUT_OF_BOUNDS);
+        assert!(FIELDS[2].to_usize(&[0; 4]).is_err());
+        assert!(FIELDS[2].lookup(&[0; 4]).is_none());
+    }
+
+    fn sample(name: &str) -> File {
+        let path = format!("{}/tests/corpus/samples/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let exe = exe_types::new(&path);
+        decode(exe.as_ref())
+    }
+
+    fn named<'s>(structures: &'s [Structure], name: &str) -> Vec<&'s Structure> {
+        structures.iter().filter(|s| s.name == name).collect()
+    }
+
+    #[test]
+    fn elf_tables() {
+        let file = sample("hello-x86_64.elf");
+        assert_eq!(file.structures[0].name, "ELF Header");
+        assert!(!named(&file.structures, "Program Header").is_empty());
+
+        // The symbols are under the headers of the symbol table sections
+        let sections = named(&file.structures, "Section Header");
+        let tables: Vec<_> = sections.iter().filter(|s| !s.children.is_empty()).collect();
+        assert_eq!(tables.len(), 2);
+        for table in tables {
+            assert!(table.children.iter().all(|s| s.name == "Symbol" && s.children.is_empty()));
+            let first = &table.children[0];
+            assert_eq!(first.fields[0].name, "Name Offset");
+            assert_eq!(first.fields[0].value, "0");
+        }
+    }
+
+    #[test]
+    fn macho_commands() {
+        let file = sample("hello-x86_64.macho");
+        assert_eq!(file.structures[0].name, "Mach-O Header");
+
+        // A load command holds the fields of its command
+        let segments: Vec<_> = named(&file.structures, "Load Command")
+            .into_iter()
+            .flat_map(|cmd| named(&cmd.children, "Segment Load"))
+            .map(|segment| segment.fields[0].value.as_str())
+            .collect();
+        assert_eq!(segments, ["__PAGEZERO", "__TEXT", "__LINKEDIT"]);
+        assert!(named(&file.structures, "Segment Load").is_empty());
+    }
+}
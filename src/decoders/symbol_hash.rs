@@ -0,0 +1,396 @@
+//!
+//! SysV `.hash` and GNU `.gnu.hash` symbol hash tables of an ELF image and
+//! symbol lookup through them in the way the dynamic loader does it
+//!
+//! - <https://refspecs.linuxfoundation.org/elf/gabi4+/ch5.dynamic.html#hash>
+//! - <https://sourceware.org/git/?p=glibc.git;a=blob;f=elf/dl-lookup.c>
+//!
+
+use anyhow::Result;
+
+use crate::{
+    decoders::{
+        reader::{Endian, Reader},
+        symbol_versions::SymbolVersions,
+    },
+    exe_types::{ExeRef, Symbol},
+};
+
+// ------------------------------------------------------------------------
+
+const SHT_HASH: u32 = 5;
+const SHT_GNU_HASH: u32 = 0x6ffffff6;
+
+const VERSYM_HIDDEN: u16 = 0x8000;
+
+/// SysV hash table, every symbol is on the chain of its bucket
+#[derive(Debug, Clone, Default)]
+pub struct SysvHash {
+    pub buckets: Vec<u32>,
+    pub chains: Vec<u32>,
+}
+
+/// GNU hash table, symbols from `symoffset` on are sorted by bucket and
+/// the low bit of a chain entry marks the end of the bucket
+#[derive(Debug, Clone, Default)]
+pub struct GnuHash {
+    pub symoffset: u32,
+    pub bloom_shift: u32,
+    /// Bits in a bloom filter word, the ELF class word size
+    pub bloom_bits: u32,
+    pub bloom: Vec<u64>,
+    pub buckets: Vec<u32>,
+    pub chains: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HashTables {
+    pub sysv: Option<SysvHash>,
+    pub gnu: Option<GnuHash>,
+}
+
+/// Why a symbol on the chain did or did not match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Probe {
+    HashDiffers,
+    NameDiffers,
+    Undefined,
+    Local,
+    VersionDiffers,
+    Found,
+}
+
+/// Steps taken looking a name up in one of the tables
+#[derive(Debug, Clone, Default)]
+pub struct Lookup {
+    pub hash: u32,
+    /// Bloom filter word index, the two bits tested and if both are set
+    pub bloom: Option<(usize, u32, u32, bool)>,
+    pub bucket: usize,
+    /// Symbol index and the outcome of each comparison
+    pub probes: Vec<(usize, Probe)>,
+    pub found: Option<usize>,
+}
+
+pub fn is_present(exe: ExeRef) -> bool {
+    exe.sections().iter().any(|s| matches!(s.kind, SHT_HASH | SHT_GNU_HASH))
+}
+
+pub fn decode(exe: ExeRef) -> Result<HashTables> {
+    let sections = exe.sections();
+    let endian = exe.endianness().unwrap_or(Endian::Little);
+    let addr_size = exe.bitness().unwrap_or(64) as usize / 8;
+    let data = |kind| sections
+        .iter()
+        .find(|s| s.kind == kind)
+        .map(|s| s.data(exe.mmap()).unwrap_or(&[]));
+
+    Ok(HashTables {
+        sysv: data(SHT_HASH).map(|data| parse_sysv(data, endian)).transpose()?,
+        gnu: data(SHT_GNU_HASH).map(|data| parse_gnu(data, endian, addr_size)).transpose()?,
+    })
+}
+
+pub fn parse_sysv(data: &[u8], endian: Endian) -> Result<SysvHash> {
+    let mut rdr = Reader::new(data, endian);
+    let nbucket = rdr.u32()? as usize;
+    let nchain = rdr.u32()? as usize;
+    Ok(SysvHash {
+        buckets: u32_array(&mut rdr, nbucket)?,
+        chains: u32_array(&mut rdr, nchain)?,
+    })
+}
+
+pub fn parse_gnu(data: &[u8], endian: Endian, addr_size: usize) -> Result<GnuHash> {
+    let mut rdr = Reader::new(data, endian);
+    let nbucket = rdr.u32()? as usize;
+    let symoffset = rdr.u32()?;
+    let bloom_size = rdr.u32()? as usize;
+    let bloom_shift = rdr.u32()?;
+    let bloom = (0..bloom_size).map(|_| rdr.uint(addr_size)).collect::<Result<_>>()?;
+    let buckets = u32_array(&mut rdr, nbucket)?;
+    // The chain array runs to the end of the section
+    let nchain = rdr.remaining() / 4;
+    let chains = u32_array(&mut rdr, nchain)?;
+    Ok(GnuHash { symoffset, bloom_shift, bloom_bits: addr_size as u32 * 8, bloom, buckets, chains })
+}
+
+fn u32_array(rdr: &mut Reader, count: usize) -> Result<Vec<u32>> {
+    (0..count).map(|_| rdr.u32()).collect()
+}
+
+// ------------------------------------------------------------------------
+
+/// Hash function of the SysV `.hash` table
+pub fn elf_hash(name: &[u8]) -> u32 {
+    name.iter().fold(0u32, |h, &c| {
+        let h = (h << 4).wrapping_add(c.into());
+        let g = h & 0xf000_0000;
+        (h ^ (g >> 24)) & !g
+    })
+}
+
+/// Hash function of the `.gnu.hash` table, Bernstein's with a seed of 5381
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |h, &c| h.wrapping_mul(33).wrapping_add(c.into()))
+}
+
+/// Count of buckets having each chain length, the index is the length
+pub fn histogram(lengths: &[usize]) -> Vec<usize> {
+    let mut counts = vec![0; lengths.iter().max().map_or(1, |m| m + 1)];
+    for &len in lengths {
+        counts[len] += 1;
+    }
+    counts
+}
+
+impl SysvHash {
+
+    /// Each chain entry is walked once, the length from an entry already
+    /// counted is added to the entries leading to it
+    pub fn chain_lengths(&self) -> Vec<usize> {
+        let mut lengths: Vec<Option<usize>> = vec![None; self.chains.len()];
+        let mut on_path = vec![false; self.chains.len()];
+        self.buckets
+            .iter()
+            .map(|&first| {
+                let mut path = Vec::new();
+                let mut index = first as usize;
+                while index != 0 && index < self.chains.len() && lengths[index].is_none() && !on_path[index] {
+                    on_path[index] = true;
+                    path.push(index);
+                    index = self.chains[index] as usize;
+                }
+                // A loop back into the path ends the chain
+                let mut len = lengths.get(index).copied().flatten().unwrap_or(0);
+                for &entry in path.iter().rev() {
+                    len += 1;
+                    lengths[entry] = Some(len);
+                    on_path[entry] = false;
+                }
+                len
+            })
+            .collect()
+    }
+
+    /// Symbol indexes of a chain, stopping at the end of the table or a loop
+    fn chain(&self, first: u32) -> impl Iterator<Item = usize> + '_ {
+        let mut index = first as usize;
+        let mut steps = 0;
+        std::iter::from_fn(move || {
+            if index == 0 || index >= self.chains.len() || steps > self.chains.len() {
+                return None;
+            }
+            let current = index;
+            index = self.chains[index] as usize;
+            steps += 1;
+            Some(current)
+        })
+    }
+
+    pub fn lookup(&self, name: &str, symbols: &[Symbol], versions: Option<&SymbolVersions>) -> Lookup {
+        let (name, version) = split_name(name);
+        let hash = elf_hash(name.as_bytes());
+        let mut lookup = Lookup { hash, ..Default::default() };
+        if self.buckets.is_empty() {
+            return lookup;
+        }
+        lookup.bucket = hash as usize % self.buckets.len();
+
+        for index in self.chain(self.buckets[lookup.bucket]) {
+            let probe = check_match(index, name, version, symbols, versions);
+            lookup.probes.push((index, probe));
+            if probe == Probe::Found {
+                lookup.found = Some(index);
+                break;
+            }
+        }
+        lookup
+    }
+
+}
+
+impl GnuHash {
+
+    /// Each distinct chain is walked once, so buckets sharing a chain cost
+    /// no more than the chain itself
+    pub fn chain_lengths(&self) -> Vec<usize> {
+        let mut starts = self.buckets.clone();
+        starts.sort_unstable();
+        starts.dedup();
+        let lengths: Vec<usize> = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, &first)| self.chain(first, starts.get(idx + 1).copied()).count())
+            .collect();
+        self.buckets
+            .iter()
+            .map(|first| starts.binary_search(first).map_or(0, |idx| lengths[idx]))
+            .collect()
+    }
+
+    /// Symbol indexes and hashes of a bucket up to the entry with the end
+    /// bit, or the start of the next chain when the bit is missing
+    fn chain(&self, first: u32, next: Option<u32>) -> impl Iterator<Item = (usize, u32)> + '_ {
+        let end = next.map_or(usize::MAX, |n| n as usize);
+        let mut index = if first < self.symoffset { None } else { Some(first as usize) };
+        std::iter::from_fn(move || {
+            let current = index.filter(|i| *i < end)?;
+            let hash = *self.chains.get(current - self.symoffset as usize)?;
+            index = if hash & 1 == 0 { Some(current + 1) } else { None };
+            Some((current, hash))
+        })
+    }
+
+    /// Number of bloom filter bits set
+    pub fn bloom_bits_set(&self) -> u32 {
+        self.bloom.iter().map(|w| w.count_ones()).sum()
+    }
+
+    pub fn lookup(&self, name: &str, symbols: &[Symbol], versions: Option<&SymbolVersions>) -> Lookup {
+        let (name, version) = split_name(name);
+        let hash = gnu_hash(name.as_bytes());
+        let mut lookup = Lookup { hash, ..Default::default() };
+        if self.buckets.is_empty() || self.bloom.is_empty() || self.bloom_bits == 0 {
+            return lookup;
+        }
+
+        // Both bits must be set, otherwise the name is certainly not present
+        let word = (hash / self.bloom_bits) as usize % self.bloom.len();
+        let bit1 = hash % self.bloom_bits;
+        let bit2 = hash.checked_shr(self.bloom_shift).unwrap_or(0) % self.bloom_bits;
+        let pass = (self.bloom[word] >> bit1) & (self.bloom[word] >> bit2) & 1 != 0;
+        lookup.bloom = Some((word, bit1, bit2, pass));
+        lookup.bucket = hash as usize % self.buckets.len();
+        if !pass {
+            return lookup;
+        }
+
+        let first = self.buckets[lookup.bucket];
+        let next = self.buckets.iter().copied().filter(|b| *b > first).min();
+        for (index, chain_hash) in self.chain(first, next) {
+            let probe = if (chain_hash ^ hash) >> 1 != 0 {
+                Probe::HashDiffers
+            } else {
+                check_match(index, name, version, symbols, versions)
+            };
+            lookup.probes.push((index, probe));
+            if probe == Probe::Found {
+                lookup.found = Some(index);
+                break;
+            }
+        }
+        lookup
+    }
+
+}
+
+/// Split `memcpy@GLIBC_2.14` or `memcpy@@GLIBC_2.14` into name and version
+fn split_name(name: &str) -> (&str, Option<&str>) {
+    match name.split_once('@') {
+        Some((name, version)) => (name, Some(version.trim_start_matches('@'))),
+        None => (name, None),
+    }
+}
+
+/// The checks of `check_match` in the loader, which skips undefined and
+/// local symbols and, without a requested version, hidden versions
+fn check_match(
+    index: usize,
+    name: &str,
+    version: Option<&str>,
+    symbols: &[Symbol],
+    versions: Option<&SymbolVersions>,
+) -> Probe {
+    let Some(symbol) = symbols.get(index) else {
+        return Probe::NameDiffers;
+    };
+    if !symbol.defined {
+        return Probe::Undefined;
+    }
+    if symbol.name != name {
+        return Probe::NameDiffers;
+    }
+    if !symbol.global {
+        return Probe::Local;
+    }
+
+    let versym = versions.and_then(|v| v.versym.get(index).map(|&i| (v, i)));
+    match (version, versym) {
+        (Some(wanted), Some((v, versym))) if v.name(versym) != Some(wanted) => Probe::VersionDiffers,
+        (Some(_), None) => Probe::VersionDiffers,
+        (None, Some((_, versym))) if versym & VERSYM_HIDDEN != 0 => Probe::VersionDiffers,
+        _ => Probe::Found,
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_functions() {
+        assert_eq!(elf_hash(b""), 0);
+        assert_eq!(elf_hash(b"printf"), 0x077905a6);
+        assert_eq!(elf_hash(b"exit"), 0x0006cf04);
+        assert_eq!(gnu_hash(b""), 0x00001505);
+        assert_eq!(gnu_hash(b"printf"), 0x156b2bb8);
+        assert_eq!(gnu_hash(b"exit"), 0x7c967e3f);
+    }
+
+    #[test]
+    fn gnu_lookup() {
+        let symbol = |name: &str, defined| Symbol { name: name.into(), defined, global: true, ..Default::default() };
+        let symbols = Vec::from([symbol("", false), symbol("puts", false), symbol("exit", true), symbol("printf", true)]);
+
+        // One bucket holding both defined symbols from index 2 on
+        let hashes = [gnu_hash(b"exit"), gnu_hash(b"printf")];
+        let bloom = hashes.iter().fold(0u64, |w, h| w | 1 << (h % 64) | 1 << ((h >> 6) % 64));
+        let table = GnuHash {
+            symoffset: 2,
+            bloom_shift: 6,
+            bloom_bits: 64,
+            bloom: Vec::from([bloom]),
+            buckets: Vec::from([2]),
+            chains: Vec::from([hashes[0] & !1, hashes[1] | 1]),
+        };
+        assert_eq!(table.chain_lengths(), [2]);
+        assert_eq!(histogram(&table.chain_lengths()), [0, 0, 1]);
+
+        let lookup = table.lookup("printf", &symbols, None);
+        assert_eq!(lookup.probes, [(2, Probe::HashDiffers), (3, Probe::Found)]);
+        assert_eq!(lookup.found, Some(3));
+        assert_eq!(table.lookup("puts", &symbols, None).found, None);
+    }
+
+    #[test]
+    fn gnu_chains_without_end_bits() {
+        let table = GnuHash {
+            symoffset: 1,
+            bloom_shift: 6,
+            bloom_bits: 64,
+            bloom: Vec::from([u64::MAX]),
+            buckets: Vec::from([1, 3, 1, 0]),
+            chains: Vec::from([0; 5]),
+        };
+        assert_eq!(table.chain_lengths(), [2, 3, 2, 0]);
+
+        // Many buckets sharing one long chain walk it once
+        let table = GnuHash { buckets: vec![1; 100_000], chains: vec![0; 100_000], ..table };
+        assert!(table.chain_lengths().iter().all(|len| *len == 100_000));
+    }
+
+    #[test]
+    fn sysv_chain_loops() {
+        // 1 -> 2 -> 3 -> 1, and 4 -> 2
+        let table = SysvHash { buckets: Vec::from([1, 4, 0, 3]), chains: Vec::from([0, 2, 3, 1, 2]) };
+        assert_eq!(table.chain_lengths(), [3, 3, 0, 1]);
+
+        let mut chains: Vec<u32> = (1..=100_000).collect();
+        chains[99_999] = 1;
+        let table = SysvHash { buckets: (1..100_000).collect(), chains };
+        assert_eq!(table.chain_lengths()[0], 99_999);
+    }
+}
@@ -0,0 +1,289 @@
+//!
+//! GNU symbol versioning from the `.gnu.version`, `.gnu.version_d` and
+//! `.gnu.version_r` sections of an ELF image
+//!
+//! - <https://refspecs.linuxfoundation.org/LSB_5.0.0/LSB-Core-generic/LSB-Core-generic/symversion.html>
+//!
+
+use anyhow::Result;
+use std::cmp::Ordering;
+
+use crate::{
+    decoders::reader::{self, Endian, Reader},
+    exe_types::{ExeRef, Section, Symbol},
+};
+
+// ------------------------------------------------------------------------
+
+const SHT_GNU_VERDEF: u32 = 0x6ffffffd;
+const SHT_GNU_VERNEED: u32 = 0x6ffffffe;
+const SHT_GNU_VERSYM: u32 = 0x6fffffff;
+
+const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+/// Version defined by this image, the first name is the version and any
+/// others its predecessors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDef {
+    pub index: u16,
+    pub flags: u16,
+    pub names: Vec<String>,
+}
+
+/// Version required from a library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionNeed {
+    pub index: u16,
+    pub flags: u16,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Library {
+    pub file: String,
+    pub versions: Vec<VersionNeed>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolVersions {
+    pub defs: Vec<VersionDef>,
+    pub needs: Vec<Library>,
+    /// Version index of each `.dynsym` entry
+    pub versym: Vec<u16>,
+}
+
+pub fn is_present(exe: ExeRef) -> bool {
+    exe.sections().iter().any(|s| s.kind == SHT_GNU_VERSYM)
+}
+
+pub fn decode(exe: ExeRef) -> Result<SymbolVersions> {
+    let sections = exe.sections();
+    let endian = exe.endianness().unwrap_or(Endian::Little);
+    let mmap = exe.mmap();
+    let find = |kind| sections.iter().find(|s| s.kind == kind);
+    let strings = |s: &Section| sections
+        .get(s.link as usize)
+        .and_then(|s| s.data(mmap))
+        .unwrap_or(&[]);
+    let string = |strtab, offset: u32| reader::cstr_at(strtab, offset as usize).unwrap_or("???").to_string();
+
+    let mut versions = SymbolVersions::default();
+
+    if let Some(data) = find(SHT_GNU_VERSYM).and_then(|s| s.data(mmap)) {
+        let mut rdr = Reader::new(data, endian);
+        versions.versym = (0..data.len() / 2).map(|_| rdr.u16()).collect::<Result<_>>()?;
+    }
+
+    if let Some(section) = find(SHT_GNU_VERDEF) {
+        let data = section.data(mmap).unwrap_or(&[]);
+        let strtab = strings(section);
+        let mut offset = 0;
+        for _ in 0..section.info {
+            let mut rdr = Reader::at(data, offset, endian);
+            let _version = rdr.u16()?;
+            let flags = rdr.u16()?;
+            let index = rdr.u16()?;
+            let count = rdr.u16()?;
+            let _hash = rdr.u32()?;
+            let aux = rdr.u32()? as usize;
+            let next = rdr.u32()? as usize;
+
+            let mut names = Vec::new();
+            let mut aux_offset = offset + aux;
+            for _ in 0..count {
+                let mut rdr = Reader::at(data, aux_offset, endian);
+                names.push(string(strtab, rdr.u32()?));
+                let aux_next = rdr.u32()? as usize;
+                if aux_next == 0 {
+                    break;
+                }
+                aux_offset += aux_next;
+            }
+            versions.defs.push(VersionDef { index, flags, names });
+
+            if next == 0 {
+                break;
+            }
+            offset += next;
+        }
+    }
+
+    if let Some(section) = find(SHT_GNU_VERNEED) {
+        let data = section.data(mmap).unwrap_or(&[]);
+        let strtab = strings(section);
+        let mut offset = 0;
+        for _ in 0..section.info {
+            let mut rdr = Reader::at(data, offset, endian);
+            let _version = rdr.u16()?;
+            let count = rdr.u16()?;
+            let file = string(strtab, rdr.u32()?);
+            let aux = rdr.u32()? as usize;
+            let next = rdr.u32()? as usize;
+
+            let mut library = Library { file, versions: Vec::new() };
+            let mut aux_offset = offset + aux;
+            for _ in 0..count {
+                let mut rdr = Reader::at(data, aux_offset, endian);
+                let _hash = rdr.u32()?;
+                let flags = rdr.u16()?;
+                let index = rdr.u16()?;
+                let name = string(strtab, rdr.u32()?);
+                let aux_next = rdr.u32()? as usize;
+                library.versions.push(VersionNeed { index, flags, name });
+                if aux_next == 0 {
+                    break;
+                }
+                aux_offset += aux_next;
+            }
+            versions.needs.push(library);
+
+            if next == 0 {
+                break;
+            }
+            offset += next;
+        }
+    }
+
+    Ok(versions)
+}
+
+// ------------------------------------------------------------------------
+
+impl SymbolVersions {
+
+    /// Version name of an index from `.gnu.version`
+    pub fn name(&self, index: u16) -> Option<&str> {
+        let index = index & !VERSYM_HIDDEN;
+        self.needs
+            .iter()
+            .flat_map(|l| &l.versions)
+            .find(|v| v.index == index)
+            .map(|v| v.name.as_str())
+            .or_else(|| self.defs
+                .iter()
+                .find(|d| d.index == index && d.flags & VER_FLG_BASE == 0)
+                .and_then(|d| d.names.first())
+                .map(|n| n.as_str()))
+    }
+
+    /// Symbol name with its version, `@@` marks the default version of a
+    /// defined symbol
+    pub fn versioned_name(&self, symbol: &Symbol) -> String {
+        let Some(&index) = self.versym.get(symbol.index).filter(|_| symbol.dynamic) else {
+            return symbol.name.clone();
+        };
+        match self.name(index) {
+            Some(version) if symbol.defined && index & VERSYM_HIDDEN == 0 => format!("{}@@{}", symbol.name, version),
+            Some(version) => format!("{}@{}", symbol.name, version),
+            None => symbol.name.clone(),
+        }
+    }
+
+    /// Highest version required of each versioning scheme, such as
+    /// `GLIBC` or `GLIBCXX`, for each library
+    pub fn highest_required(&self) -> Vec<(&str, &str)> {
+        let mut highest: Vec<(&str, &str)> = Vec::new();
+        for library in &self.needs {
+            let start = highest.len();
+            for need in &library.versions {
+                let (scheme, _) = split_version(&need.name);
+                match highest[start..].iter_mut().find(|h| split_version(h.1).0 == scheme) {
+                    Some(h) if compare_versions(&need.name, h.1) == Ordering::Greater => h.1 = &need.name,
+                    Some(_) => {}
+                    None => highest.push((&library.file, &need.name)),
+                }
+            }
+        }
+        highest
+    }
+
+}
+
+/// Split `GLIBC_2.14` into `GLIBC` and `2.14`
+fn split_version(name: &str) -> (&str, &str) {
+    match name.rfind('_') {
+        Some(idx) if name[idx + 1..].starts_with(|c: char| c.is_ascii_digit()) => (&name[..idx], &name[idx + 1..]),
+        _ => (name, ""),
+    }
+}
+
+/// Compare version names numerically, `GLIBC_2.9` is before `GLIBC_2.14`
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let numbers = |v: &str| split_version(v).1
+        .split('.')
+        .map(|n| n.parse::<u64>().unwrap_or(0))
+        .collect::<Vec<_>>();
+    numbers(a).cmp(&numbers(b))
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exe_types;
+
+    #[test]
+    fn highest() {
+        let need = |index, name: &str| VersionNeed { index, flags: 0, name: name.into() };
+        let versions = SymbolVersions {
+            needs: Vec::from([
+                Library { file: "libc.so.6".into(), versions: Vec::from([
+                    need(2, "GLIBC_2.9"), need(3, "GLIBC_2.14"), need(4, "GLIBC_2.2.5"),
+                ]) },
+                Library { file: "libstdc++.so.6".into(), versions: Vec::from([
+                    need(5, "GLIBCXX_3.4.21"), need(6, "CXXABI_1.3"), need(7, "GLIBCXX_3.4.9"),
+                ]) },
+            ]),
+            versym: Vec::from([0, 3, 0x8002]),
+            ..Default::default()
+        };
+
+        assert_eq!(versions.highest_required(), [
+            ("libc.so.6", "GLIBC_2.14"),
+            ("libstdc++.so.6", "GLIBCXX_3.4.21"),
+            ("libstdc++.so.6", "CXXABI_1.3"),
+        ]);
+
+        let symbol = |index, name: &str| Symbol { name: name.into(), index, dynamic: true, ..Default::default() };
+        assert_eq!(versions.versioned_name(&symbol(1, "memcpy")), "memcpy@GLIBC_2.14");
+        assert_eq!(versions.versioned_name(&symbol(2, "old")), "old@GLIBC_2.9");
+        assert_eq!(versions.versioned_name(&symbol(0, "local")), "local");
+    }
+
+    #[test]
+    fn shared_library() {
+        let exe = exe_types::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/samples/versions-x86_64.so"));
+        assert!(is_present(exe.as_ref()));
+        let versions = decode(exe.as_ref()).unwrap();
+
+        // .gnu.version_d, the base entry naming the library, and VERS_2.0
+        // following on from VERS_1.0
+        let def = |index, flags, names: &[&str]| VersionDef {
+            index, flags, names: names.iter().map(|n| n.to_string()).collect(),
+        };
+        assert_eq!(versions.defs, [
+            def(1, VER_FLG_BASE, &["libversions.so.1"]),
+            def(2, 0, &["VERS_1.0"]),
+            def(3, 0, &["VERS_2.0", "VERS_1.0"]),
+        ]);
+
+        // .gnu.version_r
+        let need = |index, name: &str| VersionNeed { index, flags: 0, name: name.into() };
+        assert_eq!(versions.needs, [Library {
+            file: "libc.so.6".into(),
+            versions: Vec::from([need(5, "GLIBC_2.2.5"), need(4, "GLIBC_2.14")]),
+        }]);
+        assert_eq!(versions.highest_required(), [("libc.so.6", "GLIBC_2.14")]);
+
+        // .gnu.version, one entry per .dynsym entry, the null one included
+        let symbols: Vec<Symbol> = exe.symbols().into_iter().filter(|s| s.dynamic).collect();
+        assert_eq!(versions.versym.len(), 11);
+        let names: Vec<String> = symbols.iter().map(|s| versions.versioned_name(s)).collect();
+        for name in ["memcpy@GLIBC_2.14", "__cxa_finalize@GLIBC_2.2.5", "copy@@VERS_1.0", "count@VERS_1.0", "count@@VERS_2.0"] {
+            assert!(names.iter().any(|n| n == name), "{} not in {:?}", name, names);
+        }
+    }
+}
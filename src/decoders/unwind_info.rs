@@ -0,0 +1,245 @@
+//!
+//! Mach-O compact unwind information from `__TEXT,__unwind_info`
+//!
+//! - <https://github.com/apple-oss-distributions/ld64/blob/main/src/abstraction/MachOFileAbstraction.hpp>
+//! - `mach-o/compact_unwind_encoding.h`
+//!
+
+use anyhow::{bail, Result};
+use std::collections::HashSet;
+
+use crate::{
+    decoders::{
+        eh_frame::RegisterSet,
+        reader::{Endian, Reader},
+    },
+    exe_types::ExeRef,
+};
+
+// ------------------------------------------------------------------------
+
+const REGULAR_PAGE: u32 = 2;
+const COMPRESSED_PAGE: u32 = 3;
+
+const MODE_MASK: u32 = 0x0f00_0000;
+const HAS_LSDA: u32 = 0x4000_0000;
+const PERSONALITY_MASK: u32 = 0x3000_0000;
+
+/// Function start and its encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnwindEntry {
+    pub addr: u64,
+    pub encoding: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UnwindInfo {
+    pub version: u32,
+    pub common_encodings: Vec<u32>,
+    pub personalities: Vec<u32>,
+    pub entries: Vec<UnwindEntry>,
+}
+
+/// Decode the `__TEXT,__unwind_info` section, `None` if there isn't one
+pub fn decode(exe: ExeRef) -> Option<Result<UnwindInfo>> {
+    let section = exe.section("__TEXT,__unwind_info")?;
+    let data = section.data(exe.mmap())?;
+
+    // Function offsets are from the start of the image, which is the
+    // __TEXT segment, mapped from file offset 0
+    let base = exe.section("__TEXT,__text").map_or(0, |s| s.addr.wrapping_sub(s.offset));
+    Some(parse(data, base))
+}
+
+pub fn parse(data: &[u8], base: u64) -> Result<UnwindInfo> {
+    let mut rdr = Reader::new(data, Endian::Little);
+    let version = rdr.u32()?;
+    if version != 1 {
+        bail!("Unsupported __unwind_info version {}", version);
+    }
+    let common_offset = rdr.u32()? as usize;
+    let common_count = rdr.u32()? as usize;
+    let personality_offset = rdr.u32()? as usize;
+    let personality_count = rdr.u32()? as usize;
+    let index_offset = rdr.u32()? as usize;
+    let index_count = rdr.u32()? as usize;
+
+    let common_encodings = u32_array(data, common_offset, common_count)?;
+    let personalities = u32_array(data, personality_offset, personality_count)?;
+
+    // Entries take at least four bytes each, and a page shared by several
+    // index entries is only decoded once
+    let max_entries = data.len() / 4;
+    let mut pages = HashSet::new();
+    let mut entries = Vec::new();
+    for idx in 0..index_count {
+        rdr.seek(index_offset + idx * 12);
+        let function_offset = rdr.u32()?;
+        let page_offset = rdr.u32()? as usize;
+        // The last index entry only marks the end of the range
+        if page_offset == 0 || !pages.insert(page_offset) {
+            continue;
+        }
+
+        let mut page = Reader::at(data, page_offset, Endian::Little);
+        match page.u32()? {
+            REGULAR_PAGE => {
+                let entry_offset = usize::from(page.u16()?);
+                let count = usize::from(page.u16()?);
+                check_count(entries.len(), count, max_entries)?;
+                page.seek(page_offset + entry_offset);
+                for _ in 0..count {
+                    let addr = base.wrapping_add(page.u32()?.into());
+                    entries.push(UnwindEntry { addr, encoding: page.u32()? });
+                }
+            }
+            COMPRESSED_PAGE => {
+                let entry_offset = usize::from(page.u16()?);
+                let count = usize::from(page.u16()?);
+                let encodings_offset = usize::from(page.u16()?);
+                let encodings_count = usize::from(page.u16()?);
+                check_count(entries.len(), count, max_entries)?;
+                let page_encodings = u32_array(data, page_offset + encodings_offset, encodings_count)?;
+
+                page.seek(page_offset + entry_offset);
+                for _ in 0..count {
+                    let entry = page.u32()?;
+                    let index = (entry >> 24) as usize;
+                    let encoding = common_encodings
+                        .get(index)
+                        .or_else(|| page_encodings.get(index.wrapping_sub(common_count)))
+                        .copied()
+                        .unwrap_or(0);
+                    let addr = base.wrapping_add(u64::from(function_offset) + u64::from(entry & 0x00ff_ffff));
+                    entries.push(UnwindEntry { addr, encoding });
+                }
+            }
+            v => bail!("Unknown second level page kind {} at {:#x}", v, page_offset),
+        }
+    }
+
+    Ok(UnwindInfo { version, common_encodings, personalities, entries })
+}
+
+fn check_count(decoded: usize, count: usize, max_entries: usize) -> Result<()> {
+    if decoded + count > max_entries {
+        bail!("Malformed __unwind_info, more than the {} entries the section can hold", max_entries);
+    }
+    Ok(())
+}
+
+fn u32_array(data: &[u8], offset: usize, count: usize) -> Result<Vec<u32>> {
+    let mut rdr = Reader::at(data, offset, Endian::Little);
+    (0..count).map(|_| rdr.u32()).collect()
+}
+
+// ------------------------------------------------------------------------
+
+/// Readable form of a compact unwind encoding
+pub fn describe(encoding: u32, regs: RegisterSet) -> String {
+    let mut text = match regs {
+        RegisterSet::X86_64 | RegisterSet::X86 => describe_x86(encoding, regs == RegisterSet::X86_64),
+        RegisterSet::AArch64 => describe_arm64(encoding),
+        RegisterSet::Other => format!("{:#010x}", encoding),
+    };
+    if encoding & HAS_LSDA != 0 {
+        text += ", has LSDA";
+    }
+    if encoding & PERSONALITY_MASK != 0 {
+        text += &format!(", personality {}", (encoding & PERSONALITY_MASK) >> 28);
+    }
+    text
+}
+
+fn describe_x86(encoding: u32, is64: bool) -> String {
+    let names: &[&str] = if is64 {
+        &["", "rbx", "r12", "r13", "r14", "r15", "rbp"]
+    } else {
+        &["", "ebx", "ecx", "edx", "edi", "esi", "ebp"]
+    };
+    let (frame, word) = if is64 { ("rbp", 8) } else { ("ebp", 4) };
+    let stack_size = (encoding >> 16) & 0xff;
+
+    match encoding & MODE_MASK {
+        0 if encoding == 0 => "No unwind information".into(),
+        0x0100_0000 => {
+            let saved = (0..5)
+                .map(|i| (encoding >> (i * 3)) & 0x7)
+                .filter(|r| *r != 0)
+                .map(|r| names.get(r as usize).copied().unwrap_or("?"))
+                .collect::<Vec<_>>();
+            format!("{} frame, saved [{}] at {}-{}", frame, saved.join(", "), frame, stack_size * word)
+        }
+        0x0200_0000 => format!("Frameless, stack size {}, {} registers saved",
+            stack_size * word, (encoding >> 10) & 0x7),
+        0x0300_0000 => format!("Frameless, stack size from the sub at function+{}, {} registers saved",
+            stack_size, (encoding >> 10) & 0x7),
+        0x0400_0000 => format!("DWARF, FDE at __eh_frame+{:#x}", encoding & 0x00ff_ffff),
+        v => format!("Unknown mode {:#x}", v >> 24),
+    }
+}
+
+fn describe_arm64(encoding: u32) -> String {
+    const PAIRS: &[(u32, &str)] = &[
+        (0x001, "x19/x20"), (0x002, "x21/x22"), (0x004, "x23/x24"), (0x008, "x25/x26"),
+        (0x010, "x27/x28"), (0x100, "d8/d9"), (0x200, "d10/d11"), (0x400, "d12/d13"),
+        (0x800, "d14/d15"),
+    ];
+    let saved = || PAIRS
+        .iter()
+        .filter(|p| encoding & p.0 != 0)
+        .map(|p| p.1)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match encoding & MODE_MASK {
+        0 if encoding == 0 => "No unwind information".into(),
+        0x0200_0000 => format!("Frameless, stack size {}", ((encoding >> 12) & 0xfff) * 16),
+        0x0300_0000 => format!("DWARF, FDE at __eh_frame+{:#x}", encoding & 0x00ff_ffff),
+        0x0400_0000 => format!("fp frame, saved [{}]", saved()),
+        v => format!("Unknown mode {:#x}", v >> 24),
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_page() {
+        let words: &[u32] = &[
+            1, 28, 1, 32, 0, 32, 2,         // header
+            0x0100_0000,                    // common encodings
+            0x1000, 56, 0, 0x2000, 0, 0,    // index
+            3, 12 | 2 << 16, 20 | 1 << 16,  // compressed page header
+            0x0000_0000, 0x0100_0040,       // entries
+            0x0201_0000,                    // page encodings
+        ];
+        let data: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+
+        let info = parse(&data, 0x1_0000_0000).unwrap();
+        assert_eq!(info.entries, [
+            UnwindEntry { addr: 0x1_0000_1000, encoding: 0x0100_0000 },
+            UnwindEntry { addr: 0x1_0000_1040, encoding: 0x0201_0000 },
+        ]);
+        assert_eq!(describe(0x0201_0000, RegisterSet::X86_64), "Frameless, stack size 8, 0 registers saved");
+        assert_eq!(describe(0x0100_0000, RegisterSet::X86_64), "rbp frame, saved [] at rbp-0");
+    }
+
+    #[test]
+    fn shared_and_oversized_pages() {
+        // Index entries all pointing at the same regular page
+        let mut words = Vec::from([1, 28, 0, 28, 0, 28, 4, 0, 76, 0, 0, 76, 0, 0, 76, 0, 0, 76, 0]);
+        words.extend_from_slice(&[2, 8 | 2 << 16, 0x1000, 0x0100_0000, 0x1040, 0x0201_0000]);
+        let data: Vec<u8> = words.iter().flat_map(|w: &u32| w.to_le_bytes()).collect();
+        assert_eq!(parse(&data, 0).unwrap().entries.len(), 2);
+
+        // A page claiming more entries than the section holds
+        let mut words = Vec::from([1, 28, 0, 28, 0, 28, 1, 0, 40, 0]);
+        words.extend_from_slice(&[2, 8 | 0xffff << 16, 0x1000, 0x0100_0000]);
+        let data: Vec<u8> = words.iter().flat_map(|w: &u32| w.to_le_bytes()).collect();
+        assert!(parse(&data, 0).unwrap_err().to_string().starts_with("Malformed"));
+    }
+}
@@ -0,0 +1,111 @@
+//!
+//! Mapping between the virtual addresses of an image and its file offsets
+//!
+//! Built from what the loader maps: the ELF `PT_LOAD` segments, the Mach-O
+//! segments and the PE headers and sections. The part of a mapping past
+//! the bytes it takes from the file, such as `.bss`, has addresses but no
+//! file offsets.
+//!
+
+// ------------------------------------------------------------------------
+
+/// Range of the file mapped at a virtual address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mapping {
+    /// Segment or section name, empty for ELF segments
+    pub name: String,
+    pub addr: u64,
+    /// Bytes of address space
+    pub size: u64,
+    pub offset: u64,
+    /// Bytes taken from the file, the rest is zero filled
+    pub file_size: u64,
+}
+
+impl Mapping {
+    /// Does the virtual address fall in the mapping?
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.addr && addr - self.addr < self.size
+    }
+}
+
+/// Virtual address space of an image, empty if the format has none
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressSpace {
+    mappings: Vec<Mapping>,
+}
+
+impl AddressSpace {
+    /// From the mappings in any order, leaving out the empty ones
+    pub fn new(mut mappings: Vec<Mapping>) -> Self {
+        mappings.retain(|m| m.size > 0);
+        mappings.sort_by_key(|m| m.addr);
+        Self { mappings }
+    }
+
+    /// Mappings by address
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    /// Mapping holding the virtual address
+    pub fn mapping(&self, addr: u64) -> Option<&Mapping> {
+        self.mappings.iter().find(|m| m.contains(addr))
+    }
+
+    /// File offset of a virtual address, `None` if it is not mapped or
+    /// zero filled
+    pub fn to_offset(&self, addr: u64) -> Option<usize> {
+        let mapping = self.mapping(addr)?;
+        let delta = addr - mapping.addr;
+        if delta >= mapping.file_size {
+            return None;
+        }
+        usize::try_from(mapping.offset.checked_add(delta)?).ok()
+    }
+
+    /// Virtual address a file offset is mapped at, the lowest one if the
+    /// mappings overlap
+    pub fn to_addr(&self, offset: usize) -> Option<u64> {
+        let offset = u64::try_from(offset).ok()?;
+        self.mappings
+            .iter()
+            .find(|m| offset >= m.offset && offset - m.offset < m.file_size.min(m.size))
+            .and_then(|m| m.addr.checked_add(offset - m.offset))
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(addr: u64, size: u64, offset: u64, file_size: u64) -> Mapping {
+        Mapping { name: String::new(), addr, size, offset, file_size }
+    }
+
+    #[test]
+    fn translation() {
+        let space = AddressSpace::new(Vec::from([
+            mapping(0x403000, 0x2000, 0x2000, 0x800),
+            mapping(0x400000, 0x1000, 0, 0x1000),
+            mapping(0x500000, 0, 0x4000, 0),
+        ]));
+
+        assert_eq!(space.mappings().len(), 2);
+        assert_eq!(space.to_offset(0x400010), Some(0x10));
+        assert_eq!(space.to_offset(0x403100), Some(0x2100));
+        // Zero filled and unmapped
+        assert_eq!(space.to_offset(0x403900), None);
+        assert_eq!(space.to_offset(0x401000), None);
+
+        assert_eq!(space.to_addr(0x2100), Some(0x403100));
+        assert_eq!(space.to_addr(0x1800), None);
+        assert!(space.mapping(0x403900).is_some());
+    }
+}
@@ -0,0 +1,126 @@
+//!
+//! Format independent description of the machine an image is built for
+//!
+
+use std::fmt;
+
+// ------------------------------------------------------------------------
+
+/// Instruction set of the code in an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Architecture {
+    X86,
+    X86_64,
+    Arm,
+    AArch64,
+    PowerPC,
+    PowerPC64,
+    Mips,
+    RiscV,
+    /// Machine number of the format, for the machines not named here
+    Unknown(u32),
+}
+
+impl Architecture {
+
+    /// From the ELF `e_machine`
+    pub fn from_elf(machine: u16) -> Self {
+        match machine {
+            EM_386 => Self::X86,
+            EM_MIPS => Self::Mips,
+            EM_PPC => Self::PowerPC,
+            EM_PPC64 => Self::PowerPC64,
+            EM_ARM => Self::Arm,
+            EM_X86_64 => Self::X86_64,
+            EM_AARCH64 => Self::AArch64,
+            EM_RISCV => Self::RiscV,
+            v => Self::Unknown(v.into()),
+        }
+    }
+
+    /// From the Mach-O `cputype`
+    pub fn from_macho(cputype: u32) -> Self {
+        match cputype {
+            CPU_TYPE_X86 => Self::X86,
+            CPU_TYPE_X86_64 => Self::X86_64,
+            CPU_TYPE_ARM => Self::Arm,
+            CPU_TYPE_ARM64 => Self::AArch64,
+            CPU_TYPE_POWERPC => Self::PowerPC,
+            CPU_TYPE_POWERPC64 => Self::PowerPC64,
+            v => Self::Unknown(v),
+        }
+    }
+
+    /// From the `Machine` of the PE file header
+    pub fn from_pe(machine: u16) -> Self {
+        match machine {
+            IMAGE_FILE_MACHINE_I386 => Self::X86,
+            IMAGE_FILE_MACHINE_AMD64 => Self::X86_64,
+            IMAGE_FILE_MACHINE_ARM | IMAGE_FILE_MACHINE_ARMNT => Self::Arm,
+            IMAGE_FILE_MACHINE_ARM64 => Self::AArch64,
+            IMAGE_FILE_MACHINE_POWERPC => Self::PowerPC,
+            IMAGE_FILE_MACHINE_R4000 => Self::Mips,
+            IMAGE_FILE_MACHINE_RISCV32 | IMAGE_FILE_MACHINE_RISCV64 => Self::RiscV,
+            v => Self::Unknown(v.into()),
+        }
+    }
+
+}
+
+impl fmt::Display for Architecture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::X86 => f.write_str("x86"),
+            Self::X86_64 => f.write_str("x86-64"),
+            Self::Arm => f.write_str("ARM"),
+            Self::AArch64 => f.write_str("AArch64"),
+            Self::PowerPC => f.write_str("PowerPC"),
+            Self::PowerPC64 => f.write_str("PowerPC64"),
+            Self::Mips => f.write_str("MIPS"),
+            Self::RiscV => f.write_str("RISC-V"),
+            Self::Unknown(v) => write!(f, "{:#x}", v),
+        }
+    }
+}
+
+const EM_386: u16 = 3;
+const EM_MIPS: u16 = 8;
+const EM_PPC: u16 = 20;
+const EM_PPC64: u16 = 21;
+const EM_ARM: u16 = 40;
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+const EM_RISCV: u16 = 243;
+
+const CPU_TYPE_X86: u32 = 7;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM: u32 = 12;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+const CPU_TYPE_POWERPC: u32 = 18;
+const CPU_TYPE_POWERPC64: u32 = 0x0100_0012;
+
+const IMAGE_FILE_MACHINE_I386: u16 = 0x14c;
+const IMAGE_FILE_MACHINE_R4000: u16 = 0x166;
+const IMAGE_FILE_MACHINE_ARM: u16 = 0x1c0;
+const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x1c4;
+const IMAGE_FILE_MACHINE_POWERPC: u16 = 0x1f0;
+const IMAGE_FILE_MACHINE_RISCV32: u16 = 0x5032;
+const IMAGE_FILE_MACHINE_RISCV64: u16 = 0x5064;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xaa64;
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn machine_numbers() {
+        assert_eq!(Architecture::from_elf(62), Architecture::X86_64);
+        assert_eq!(Architecture::from_macho(0x0100_000c), Architecture::AArch64);
+        assert_eq!(Architecture::from_pe(0x14c), Architecture::X86);
+        assert_eq!(Architecture::from_elf(0x9026), Architecture::Unknown(0x9026));
+        assert_eq!(Architecture::Unknown(0x9026).to_string(), "0x9026");
+    }
+}
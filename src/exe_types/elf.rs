@@ -7,21 +7,27 @@ use anyhow::{
     bail,
 };
 use memmap2::Mmap;
-use std::{fmt, ops::Deref};
+use std::{fmt, ops::Deref, path::{Path, PathBuf}};
 
-use crate::formatter::{
-    self,
-    FieldDef, 
-    FieldMap,
+use crate::{
+    decoders::reader::{self, Endian, Reader},
+    formatter::{
+        self,
+        Enter,
+        FieldDef, 
+        FieldMap,
+    },
 };
-use super::Executable;
+use super::{AddressSpace, Architecture, ExeRef, Executable, Mapping, Section, Segment, StructureMap, Symbol, SymbolKind};
 
 // ------------------------------------------------------------------------
 
 pub struct ELF<'elf> {
-    filename: String,
+    filename: PathBuf,
     mmap: Mmap,
     hdr_map: &'elf FieldMap<'elf>,
+    endian: Endian,
+    is64: bool,
 }
 
 // ------------------------------------------------------------------------
@@ -29,19 +35,21 @@ pub struct ELF<'elf> {
 impl<'elf> ELF<'elf> {
 
     pub fn new( 
-        filename : &str,
+        filename : &Path,
         mmap : Mmap,
     ) -> Result<Self> {
 
-        let mmap_slice = mmap.deref();
+        let Some(&[class, data]) = mmap.get(4..6) else {
+            bail!("Too small for an ELF header: {}", mmap.len())
+        };
 
-        let hdr_map = match mmap_slice[4] {
-            1 => match mmap_slice[5] {
+        let hdr_map = match class {
+            1 => match data {
                 1 => &HEADER_MAP_32_LE,
                 2 => &HEADER_MAP_32_BE,
                 v => bail!("Invalid ELF endianness {:02x}", v)
             }
-            2 => match mmap_slice[5] {
+            2 => match data {
                 1 => &HEADER_MAP_64_LE,
                 2 => &HEADER_MAP_64_BE,
                 v => bail!("Invalid ELF endianness {:02x}", v)
@@ -49,22 +57,318 @@ impl<'elf> ELF<'elf> {
             v => bail!("Invalid ELF bit length {:02x}", v)
         };
 
+        let endian = if data == 2 { Endian::Big } else { Endian::Little };
+        let is64 = class == 2;
+
         Ok(Self{
-            filename: String::from(filename), 
+            filename: filename.to_path_buf(), 
             mmap, 
             // fname_fn: None,
-            hdr_map
+            hdr_map,
+            endian,
+            is64,
         })
 
     }
 
+    pub fn reader(&self) -> Reader<'_> {
+        Reader::new(self.mmap.deref(), self.endian)
+    }
+
+    /// Read an address sized (32 or 64 bit) value
+    fn addr(&self, rdr: &mut Reader) -> Result<u64> {
+        if self.is64 { rdr.u64() } else { rdr.u32().map(u64::from) }
+    }
+
+    // --------------------------------------------------------------------
+
+    /// Parse the section header table, naming the sections from the
+    /// section header string table
+    pub fn section_headers(&self) -> Result<Vec<Section>> {
+        let mut rdr = self.reader();
+
+        rdr.seek(if self.is64 { 40 } else { 32 });
+        let shoff = usize::try_from(self.addr(&mut rdr)?)?;
+        rdr.seek(if self.is64 { 58 } else { 46 });
+        let shentsize = usize::from(rdr.u16()?);
+        let mut shnum = usize::from(rdr.u16()?);
+        let mut shstrndx = usize::from(rdr.u16()?);
+
+        if shoff == 0 {
+            return Ok(Vec::new());
+        }
+        // Smaller entries would overlap, and the count can be anything
+        if shentsize < if self.is64 { 64 } else { 40 } {
+            bail!("Section header size {} is too small", shentsize);
+        }
+
+        let mut sections = Vec::new();
+        let mut name_offs = Vec::new();
+        let mut idx = 0;
+        while idx < shnum.max(1) {
+            rdr.seek(shoff.saturating_add(idx * shentsize));
+            let name_off = rdr.u32()?;
+            let kind = rdr.u32()?;
+            let flags = self.addr(&mut rdr)?;
+            let addr = self.addr(&mut rdr)?;
+            let offset = self.addr(&mut rdr)?;
+            let size = self.addr(&mut rdr)?;
+            let link = rdr.u32()?;
+            let info = rdr.u32()?;
+            let _align = self.addr(&mut rdr)?;
+            let entsize = self.addr(&mut rdr)?;
+
+            // Extended section numbering, the real counts are in section 0
+            if idx == 0 {
+                if shnum == 0 {
+                    shnum = usize::try_from(size)?;
+                }
+                if shstrndx == SHN_XINDEX {
+                    shstrndx = usize::try_from(link)?;
+                }
+            }
+
+            name_offs.push(name_off);
+            sections.push(Section {
+                segment: String::new(),
+                name: String::new(),
+                addr, size, offset,
+                file_size: if kind == SHT_NOBITS { 0 } else { size },
+                kind, flags, link, info, entsize,
+            });
+            idx += 1;
+        }
+
+        let strtab = sections.get(shstrndx).and_then(|s| s.data(self.mmap.deref()));
+        if let Some(strtab) = strtab {
+            for (section, name_off) in sections.iter_mut().zip(name_offs) {
+                section.name = reader::cstr_at(strtab, name_off as usize)
+                    .unwrap_or("???")
+                    .into();
+            }
+        }
+
+        Ok(sections)
+    }
+
+    /// Parse the program header table
+    pub fn program_headers(&self) -> Result<Vec<Segment>> {
+        let mut rdr = self.reader();
+
+        rdr.seek(if self.is64 { 32 } else { 28 });
+        let phoff = usize::try_from(self.addr(&mut rdr)?)?;
+        rdr.seek(if self.is64 { 54 } else { 42 });
+        let phentsize = usize::from(rdr.u16()?);
+        let phnum = usize::from(rdr.u16()?);
+        if phnum > 0 && phentsize < if self.is64 { 56 } else { 32 } {
+            bail!("Program header size {} is too small", phentsize);
+        }
+
+        let mut segments = Vec::new();
+        for idx in 0..phnum {
+            rdr.seek(phoff.saturating_add(idx * phentsize));
+            let kind = rdr.u32()?;
+            let mut flags = if self.is64 { rdr.u32()? } else { 0 };
+            let offset = self.addr(&mut rdr)?;
+            let addr = self.addr(&mut rdr)?;
+            let _paddr = self.addr(&mut rdr)?;
+            let file_size = self.addr(&mut rdr)?;
+            let size = self.addr(&mut rdr)?;
+            if !self.is64 {
+                flags = rdr.u32()?;
+            }
+
+            let name = SEGMENT_TYPES
+                .iter()
+                .find(|t| t.0 == kind)
+                .map_or_else(|| format!("{:#x}", kind), |t| t.1.to_string());
+            segments.push(Segment { name, kind, flags, addr, size, offset, file_size });
+        }
+
+        Ok(segments)
+    }
+
+    /// File offset and entry size of the program header table, or of the
+    /// section header table with `sections`
+    fn header_table(&self, sections: bool) -> Result<(usize, usize)> {
+        let mut rdr = self.reader();
+        rdr.seek(match (self.is64, sections) {
+            (true, false) => 32,
+            (true, true) => 40,
+            (false, false) => 28,
+            (false, true) => 32,
+        });
+        let offset = usize::try_from(self.addr(&mut rdr)?)?;
+        rdr.seek(if self.is64 { 54 } else { 42 } + if sections { 4 } else { 0 });
+        Ok((offset, usize::from(rdr.u16()?)))
+    }
+
+    /// The header with the program headers, the section headers and the
+    /// entries of the symbol table sections after it
+    fn header_tables(&self) -> Vec<StructureMap<'_>> {
+        let (phdr_map, shdr_map, sym_map) = match (self.is64, self.endian) {
+            (false, Endian::Little) => (&PHDR_MAP_32_LE, &SHDR_MAP_32_LE, &SYM_MAP_32_LE),
+            (false, Endian::Big) => (&PHDR_MAP_32_BE, &SHDR_MAP_32_BE, &SYM_MAP_32_BE),
+            (true, Endian::Little) => (&PHDR_MAP_64_LE, &SHDR_MAP_64_LE, &SYM_MAP_64_LE),
+            (true, Endian::Big) => (&PHDR_MAP_64_BE, &SHDR_MAP_64_BE, &SYM_MAP_64_BE),
+        };
+        let entries = |offset: usize, entsize: usize, count: usize, name, map: &'static FieldMap| {
+            (0..count)
+                .map(|idx| offset.saturating_add(idx * entsize))
+                .map(|start| StructureMap::new(name, (start, start.saturating_add(map.data_len)), map))
+                .collect::<Vec<_>>()
+        };
+
+        let mut structures = Vec::from([StructureMap::new("ELF Header", (0, self.hdr_map.data_len), self.hdr_map)]);
+        if let (Ok(segments), Ok((offset, entsize))) = (self.program_headers(), self.header_table(false)) {
+            structures.extend(entries(offset, entsize, segments.len(), "Program Header", phdr_map));
+        }
+        if let (Ok(sections), Ok((offset, entsize))) = (self.section_headers(), self.header_table(true)) {
+            let mut headers = entries(offset, entsize, sections.len(), "Section Header", shdr_map);
+            for (header, section) in headers.iter_mut().zip(&sections) {
+                if matches!(section.kind, SHT_SYMTAB | SHT_DYNSYM) {
+                    let count = section.data(self.mmap.deref()).map_or(0, |data| data.len() / sym_map.data_len);
+                    header.children = entries(section.offset as usize, sym_map.data_len, count, "Symbol", sym_map);
+                }
+            }
+            structures.extend(headers);
+        }
+        structures
+    }
+
+    /// Parse the entries of the `.symtab` and `.dynsym` sections
+    pub fn symbol_table(&self) -> Result<Vec<Symbol>> {
+        let sections = self.section_headers()?;
+        let mut symbols = Vec::new();
+
+        for section in sections.iter().filter(|s| matches!(s.kind, SHT_SYMTAB | SHT_DYNSYM)) {
+            let Some(data) = section.data(self.mmap.deref()) else { continue };
+            let strtab = sections
+                .get(section.link as usize)
+                .and_then(|s| s.data(self.mmap.deref()))
+                .unwrap_or(&[]);
+            let entsize = if self.is64 { 24 } else { 16 };
+
+            let mut rdr = Reader::new(data, self.endian);
+            for index in 0..data.len() / entsize {
+                rdr.seek(index * entsize);
+                let name_off = rdr.u32()?;
+                let (addr, size, info, shndx) = if self.is64 {
+                    let info = rdr.u8()?;
+                    let _other = rdr.u8()?;
+                    let shndx = rdr.u16()?;
+                    (rdr.u64()?, rdr.u64()?, info, shndx)
+                } else {
+                    let addr = rdr.u32()?.into();
+                    let size = rdr.u32()?.into();
+                    let info = rdr.u8()?;
+                    let _other = rdr.u8()?;
+                    (addr, size, info, rdr.u16()?)
+                };
+
+                symbols.push(Symbol {
+                    name: reader::cstr_at(strtab, name_off as usize).unwrap_or("???").into(),
+                    addr, size,
+                    kind: match info & 0xf {
+                        STT_OBJECT | STT_TLS => SymbolKind::Object,
+                        STT_FUNC | STT_GNU_IFUNC => SymbolKind::Function,
+                        _ => SymbolKind::Other,
+                    },
+                    global: info >> 4 != STB_LOCAL,
+                    defined: shndx != SHN_UNDEF,
+                    dynamic: section.kind == SHT_DYNSYM,
+                    index,
+                });
+            }
+        }
+
+        Ok(symbols)
+    }
+
+}
+
+/// Entry of the `PT_DYNAMIC` segment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicEntry {
+    pub tag: u64,
+    pub value: u64,
+    /// The string the value refers to, for the tags naming libraries and
+    /// paths
+    pub string: Option<String>,
 }
 
+/// Entries of the dynamic segment of an ELF image, up to `DT_NULL`
+pub fn dynamic_entries(exe: ExeRef) -> Vec<DynamicEntry> {
+    let segments = exe.segments();
+    let Some(data) = segments.iter().find(|s| s.kind == PT_DYNAMIC).and_then(|s| s.data(exe.mmap())) else {
+        return Vec::new();
+    };
+    let mut rdr = Reader::new(data, exe.endianness().unwrap_or(Endian::Little));
+    let addr_size = exe.bitness().unwrap_or(64) as usize / 8;
+
+    let mut entries = Vec::new();
+    while let (Ok(tag), Ok(value)) = (rdr.uint(addr_size), rdr.uint(addr_size)) {
+        if tag == DT_NULL {
+            break;
+        }
+        entries.push(DynamicEntry { tag, value, string: None });
+    }
+
+    let space = exe.address_space();
+    let strtab = entries.iter().find(|d| d.tag == DT_STRTAB).and_then(|d| space.to_offset(d.value));
+    for entry in entries.iter_mut().filter(|d| [DT_NEEDED, DT_SONAME, DT_RPATH, DT_RUNPATH].contains(&d.tag)) {
+        let string = strtab
+            .and_then(|s| usize::try_from(entry.value).ok().and_then(|v| s.checked_add(v)))
+            .and_then(|offset| reader::cstr_at(exe.mmap(), offset).ok())
+            .unwrap_or("???");
+        entry.string = Some(string.to_string());
+    }
+
+    entries
+}
+
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+
+pub const DT_NULL: u64 = 0;
+pub const DT_NEEDED: u64 = 1;
+pub const DT_STRTAB: u64 = 5;
+pub const DT_SONAME: u64 = 14;
+pub const DT_RPATH: u64 = 15;
+pub const DT_RUNPATH: u64 = 29;
+
+const SEGMENT_TYPES: &[(u32, &str)] = &[
+    (0, "NULL"),
+    (1, "LOAD"),
+    (2, "DYNAMIC"),
+    (3, "INTERP"),
+    (4, "NOTE"),
+    (5, "SHLIB"),
+    (6, "PHDR"),
+    (7, "TLS"),
+    (0x6474e550, "GNU_EH_FRAME"),
+    (0x6474e551, "GNU_STACK"),
+    (0x6474e552, "GNU_RELRO"),
+    (0x6474e553, "GNU_PROPERTY"),
+];
+
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_DYNSYM: u32 = 11;
+const SHN_UNDEF: u16 = 0;
+const SHN_XINDEX: usize = 0xffff;
+
+const STB_LOCAL: u8 = 0;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STT_TLS: u8 = 6;
+const STT_GNU_IFUNC: u8 = 10;
+
 // ------------------------------------------------------------------------
 
 impl Executable for ELF<'_> {
 
-    fn filename(&self) -> &str {
+    fn filename(&self) -> &Path {
         &self.filename
     }
     fn len(&self) -> usize {
@@ -73,9 +377,52 @@ impl Executable for ELF<'_> {
     fn mmap(&self) -> &[u8] {
         self.mmap.deref()
     }
-    fn header_map(&self) -> &FieldMap {
+    fn header_map(&self) -> &FieldMap<'_> {
         self.hdr_map
     }
+    fn architecture(&self) -> Option<Architecture> {
+        Reader::at(self.mmap.deref(), 18, self.endian).u16().ok().map(Architecture::from_elf)
+    }
+    fn endianness(&self) -> Option<Endian> {
+        Some(self.endian)
+    }
+    fn bitness(&self) -> Option<u32> {
+        Some(if self.is64 { 64 } else { 32 })
+    }
+    fn entry_point(&self) -> Option<u64> {
+        let mut rdr = self.reader();
+        rdr.seek(24);
+        self.addr(&mut rdr).ok().filter(|entry| *entry != 0)
+    }
+    fn segments(&self) -> Vec<Segment> {
+        self.program_headers().unwrap_or_default()
+    }
+    fn sections(&self) -> Vec<Section> {
+        self.section_headers().unwrap_or_default()
+    }
+    fn symbols(&self) -> Vec<Symbol> {
+        self.symbol_table().unwrap_or_default()
+    }
+    fn dependencies(&self) -> Vec<String> {
+        dynamic_entries(self)
+            .into_iter()
+            .filter(|d| d.tag == DT_NEEDED)
+            .filter_map(|d| d.string)
+            .collect()
+    }
+    fn structures(&self) -> Vec<StructureMap<'_>> {
+        self.header_tables()
+    }
+    fn address_space(&self) -> AddressSpace {
+        // The loader only maps `PT_LOAD`, leave the names empty as the
+        // sections name the parts of an ELF image
+        AddressSpace::new(self
+            .segments()
+            .iter()
+            .filter(|s| s.kind == PT_LOAD)
+            .map(|s| Mapping { name: String::new(), ..s.mapping() })
+            .collect())
+    }
 
 }
 
@@ -90,7 +437,7 @@ impl fmt::Debug for ELF<'_> {
         write!(
             f,
             "ELF: {}: {:p}/{}",
-            self.filename,
+            self.filename.display(),
             self.mmap.as_ptr(),
             self.len(),
         )
@@ -115,11 +462,12 @@ const HDR_32_LE: & [FieldDef] = &[
 	FieldDef::new(16, 2, "Object File Type", Some(formatter::LE_16_HEX)),
 	FieldDef::new(18, 2, "Instruction Set Architecture", Some(formatter::LE_16_HEX)),
 	FieldDef::new(20, 4, "ELF Version", Some(formatter::LE_32_STRING)),
-	FieldDef::new(24, 4, "Entry Point Address", Some(formatter::LE_32_PTR)),
-	FieldDef::new(38, 4, "Program Header Offset", Some(formatter::LE_32_PTR)),
+	FieldDef::new(24, 4, "Entry Point Address", Some(formatter::LE_32_PTR))
+		.on_enter(Enter::EntryPoint),
+	FieldDef::new(28, 4, "Program Header Offset", Some(formatter::LE_32_PTR)),
 	FieldDef::new(32, 4, "Segment Header Offset", Some(formatter::LE_32_PTR)),
 	FieldDef::new(36, 4, "Flags", Some(formatter::BIN_STRING)),
-	FieldDef::new(40, 2, "Header Size", Some(formatter::LE_32_STRING)),
+	FieldDef::new(40, 2, "Header Size", Some(formatter::LE_16_STRING)),
 	FieldDef::new(42, 2, "Program Header Size", Some(formatter::LE_16_STRING)),
 	FieldDef::new(44, 2, "# of Program Headers", Some(formatter::LE_16_STRING)),
 	FieldDef::new(46, 2, "Segment Header Size", Some(formatter::LE_16_STRING)),
@@ -138,7 +486,8 @@ const HDR_32_BE: &[FieldDef] = &[
 	FieldDef::new(16, 2, "Object File Type", Some(formatter::BE_HEX)),
 	FieldDef::new(18, 2, "Instruction Set Architecture", Some(formatter::BE_HEX)),
 	FieldDef::new(20, 4, "ELF Version", Some(formatter::BE_32_STRING)),
-	FieldDef::new(24, 4, "Entry Point Address", Some(formatter::BE_32_PTR)),
+	FieldDef::new(24, 4, "Entry Point Address", Some(formatter::BE_32_PTR))
+		.on_enter(Enter::EntryPoint),
 	FieldDef::new(28, 4, "Program Header Offset", Some(formatter::BE_32_PTR)),
 	FieldDef::new(32, 4, "Segment Header Offset", Some(formatter::BE_32_PTR)),
 	FieldDef::new(36, 4, "Flags", Some(formatter::BIN_STRING)),
@@ -161,16 +510,17 @@ const HDR_64_LE: &[FieldDef] = &[
 	FieldDef::new(16, 2, "Object File Type", Some(formatter::LE_16_HEX)),
 	FieldDef::new(18, 2, "Instruction Set Architecture", Some(formatter::LE_16_HEX)),
 	FieldDef::new(20, 4, "ELF Version", Some(formatter::LE_32_STRING)),
-	FieldDef::new(24, 8, "Entry Point Address", Some(formatter::LE_64_PTR)),
+	FieldDef::new(24, 8, "Entry Point Address", Some(formatter::LE_64_PTR))
+		.on_enter(Enter::EntryPoint),
 	FieldDef::new(32, 8, "Program Header Offset", Some(formatter::LE_64_PTR)),
 	FieldDef::new(40, 8, "Segment Header Offset", Some(formatter::LE_64_PTR)),
 	FieldDef::new(48, 4, "Flags", Some(formatter::BIN_STRING)),
-	FieldDef::new(50, 2, "Header Size", Some(formatter::LE_16_STRING)),
-	FieldDef::new(52, 2, "Program Header Size", Some(formatter::LE_16_STRING)),
-	FieldDef::new(54, 2, "# of Program Headers", Some(formatter::LE_16_STRING)),
-	FieldDef::new(56, 2, "Segment Header Size", Some(formatter::LE_16_STRING)),
-	FieldDef::new(58, 2, "# of Segment Headers", Some(formatter::LE_16_STRING)),
-	FieldDef::new(60, 2, "Section Name Index", Some(formatter::LE_16_STRING)),
+	FieldDef::new(52, 2, "Header Size", Some(formatter::LE_16_STRING)),
+	FieldDef::new(54, 2, "Program Header Size", Some(formatter::LE_16_STRING)),
+	FieldDef::new(56, 2, "# of Program Headers", Some(formatter::LE_16_STRING)),
+	FieldDef::new(58, 2, "Segment Header Size", Some(formatter::LE_16_STRING)),
+	FieldDef::new(60, 2, "# of Segment Headers", Some(formatter::LE_16_STRING)),
+	FieldDef::new(62, 2, "Section Name Index", Some(formatter::LE_16_STRING)),
 ];
 
 const HDR_64_BE: &[FieldDef] = &[
@@ -184,14 +534,164 @@ const HDR_64_BE: &[FieldDef] = &[
 	FieldDef::new(16, 2, "Object File Type", Some(formatter::BE_HEX)),
 	FieldDef::new(18, 2, "Instruction Set Architecture", Some(formatter::BE_HEX)),
 	FieldDef::new(20, 4, "ELF Version", Some(formatter::BE_32_STRING)),
-	FieldDef::new(24, 8, "Entry Point Address", Some(formatter::BE_64_PTR)),
+	FieldDef::new(24, 8, "Entry Point Address", Some(formatter::BE_64_PTR))
+		.on_enter(Enter::EntryPoint),
 	FieldDef::new(32, 8, "Program Header Offset", Some(formatter::BE_64_PTR)),
 	FieldDef::new(40, 8, "Segment Header Offset", Some(formatter::BE_64_PTR)),
 	FieldDef::new(48, 4, "Flags", Some(formatter::BIN_STRING)),
-	FieldDef::new(50, 2, "Header Size", Some(formatter::BE_16_STRING)),
-	FieldDef::new(52, 2, "Program Header Size", Some(formatter::BE_16_STRING)),
-	FieldDef::new(54, 2, "# of Program Headers", Some(formatter::BE_16_STRING)),
-	FieldDef::new(56, 2, "Segment Header Size", Some(formatter::BE_16_STRING)),
-	FieldDef::new(58, 2, "# of Segment Headers", Some(formatter::BE_16_STRING)),
-	FieldDef::new(60, 2, "Section Name Index", Some(formatter::BE_16_STRING)),
+	FieldDef::new(52, 2, "Header Size", Some(formatter::BE_16_STRING)),
+	FieldDef::new(54, 2, "Program Header Size", Some(formatter::BE_16_STRING)),
+	FieldDef::new(56, 2, "# of Program Headers", Some(formatter::BE_16_STRING)),
+	FieldDef::new(58, 2, "Segment Header Size", Some(formatter::BE_16_STRING)),
+	FieldDef::new(60, 2, "# of Segment Headers", Some(formatter::BE_16_STRING)),
+	FieldDef::new(62, 2, "Section Name Index", Some(formatter::BE_16_STRING)),
+];
+
+// ------------------------------------------------------------------------
+
+// Entries of the program header, section header and symbol tables
+
+const PHDR_MAP_32_LE: FieldMap = FieldMap::new(PHDR_32_LE);
+const PHDR_MAP_32_BE: FieldMap = FieldMap::new(PHDR_32_BE);
+const PHDR_MAP_64_LE: FieldMap = FieldMap::new(PHDR_64_LE);
+const PHDR_MAP_64_BE: FieldMap = FieldMap::new(PHDR_64_BE);
+const SHDR_MAP_32_LE: FieldMap = FieldMap::new(SHDR_32_LE);
+const SHDR_MAP_32_BE: FieldMap = FieldMap::new(SHDR_32_BE);
+const SHDR_MAP_64_LE: FieldMap = FieldMap::new(SHDR_64_LE);
+const SHDR_MAP_64_BE: FieldMap = FieldMap::new(SHDR_64_BE);
+const SYM_MAP_32_LE: FieldMap = FieldMap::new(SYM_32_LE);
+const SYM_MAP_32_BE: FieldMap = FieldMap::new(SYM_32_BE);
+const SYM_MAP_64_LE: FieldMap = FieldMap::new(SYM_64_LE);
+const SYM_MAP_64_BE: FieldMap = FieldMap::new(SYM_64_BE);
+
+const PHDR_32_LE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Type", Some(formatter::LE_32_HEX)),
+	FieldDef::new(4, 4, "Offset", Some(formatter::LE_32_PTR)),
+	FieldDef::new(8, 4, "Virtual Address", Some(formatter::LE_32_PTR)),
+	FieldDef::new(12, 4, "Physical Address", Some(formatter::LE_32_PTR)),
+	FieldDef::new(16, 4, "File Size", Some(formatter::LE_32_STRING)),
+	FieldDef::new(20, 4, "Memory Size", Some(formatter::LE_32_STRING)),
+	FieldDef::new(24, 4, "Flags", Some(formatter::BIN_STRING)),
+	FieldDef::new(28, 4, "Alignment", Some(formatter::LE_32_STRING)),
+];
+
+const PHDR_32_BE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Type", Some(formatter::BE_HEX)),
+	FieldDef::new(4, 4, "Offset", Some(formatter::BE_32_PTR)),
+	FieldDef::new(8, 4, "Virtual Address", Some(formatter::BE_32_PTR)),
+	FieldDef::new(12, 4, "Physical Address", Some(formatter::BE_32_PTR)),
+	FieldDef::new(16, 4, "File Size", Some(formatter::BE_32_STRING)),
+	FieldDef::new(20, 4, "Memory Size", Some(formatter::BE_32_STRING)),
+	FieldDef::new(24, 4, "Flags", Some(formatter::BIN_STRING)),
+	FieldDef::new(28, 4, "Alignment", Some(formatter::BE_32_STRING)),
+];
+
+const PHDR_64_LE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Type", Some(formatter::LE_32_HEX)),
+	FieldDef::new(4, 4, "Flags", Some(formatter::BIN_STRING)),
+	FieldDef::new(8, 8, "Offset", Some(formatter::LE_64_PTR)),
+	FieldDef::new(16, 8, "Virtual Address", Some(formatter::LE_64_PTR)),
+	FieldDef::new(24, 8, "Physical Address", Some(formatter::LE_64_PTR)),
+	FieldDef::new(32, 8, "File Size", Some(formatter::LE_64_STRING)),
+	FieldDef::new(40, 8, "Memory Size", Some(formatter::LE_64_STRING)),
+	FieldDef::new(48, 8, "Alignment", Some(formatter::LE_64_STRING)),
+];
+
+const PHDR_64_BE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Type", Some(formatter::BE_HEX)),
+	FieldDef::new(4, 4, "Flags", Some(formatter::BIN_STRING)),
+	FieldDef::new(8, 8, "Offset", Some(formatter::BE_64_PTR)),
+	FieldDef::new(16, 8, "Virtual Address", Some(formatter::BE_64_PTR)),
+	FieldDef::new(24, 8, "Physical Address", Some(formatter::BE_64_PTR)),
+	FieldDef::new(32, 8, "File Size", Some(formatter::BE_64_STRING)),
+	FieldDef::new(40, 8, "Memory Size", Some(formatter::BE_64_STRING)),
+	FieldDef::new(48, 8, "Alignment", Some(formatter::BE_64_STRING)),
+];
+
+const SHDR_32_LE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::LE_32_STRING)),
+	FieldDef::new(4, 4, "Type", Some(formatter::LE_32_HEX)),
+	FieldDef::new(8, 4, "Flags", Some(formatter::LE_32_PTR)),
+	FieldDef::new(12, 4, "Address", Some(formatter::LE_32_PTR)),
+	FieldDef::new(16, 4, "Offset", Some(formatter::LE_32_PTR)),
+	FieldDef::new(20, 4, "Size", Some(formatter::LE_32_STRING)),
+	FieldDef::new(24, 4, "Link", Some(formatter::LE_32_STRING)),
+	FieldDef::new(28, 4, "Info", Some(formatter::LE_32_STRING)),
+	FieldDef::new(32, 4, "Alignment", Some(formatter::LE_32_STRING)),
+	FieldDef::new(36, 4, "Entry Size", Some(formatter::LE_32_STRING)),
+];
+
+const SHDR_32_BE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::BE_32_STRING)),
+	FieldDef::new(4, 4, "Type", Some(formatter::BE_HEX)),
+	FieldDef::new(8, 4, "Flags", Some(formatter::BE_32_PTR)),
+	FieldDef::new(12, 4, "Address", Some(formatter::BE_32_PTR)),
+	FieldDef::new(16, 4, "Offset", Some(formatter::BE_32_PTR)),
+	FieldDef::new(20, 4, "Size", Some(formatter::BE_32_STRING)),
+	FieldDef::new(24, 4, "Link", Some(formatter::BE_32_STRING)),
+	FieldDef::new(28, 4, "Info", Some(formatter::BE_32_STRING)),
+	FieldDef::new(32, 4, "Alignment", Some(formatter::BE_32_STRING)),
+	FieldDef::new(36, 4, "Entry Size", Some(formatter::BE_32_STRING)),
+];
+
+const SHDR_64_LE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::LE_32_STRING)),
+	FieldDef::new(4, 4, "Type", Some(formatter::LE_32_HEX)),
+	FieldDef::new(8, 8, "Flags", Some(formatter::LE_64_PTR)),
+	FieldDef::new(16, 8, "Address", Some(formatter::LE_64_PTR)),
+	FieldDef::new(24, 8, "Offset", Some(formatter::LE_64_PTR)),
+	FieldDef::new(32, 8, "Size", Some(formatter::LE_64_STRING)),
+	FieldDef::new(40, 4, "Link", Some(formatter::LE_32_STRING)),
+	FieldDef::new(44, 4, "Info", Some(formatter::LE_32_STRING)),
+	FieldDef::new(48, 8, "Alignment", Some(formatter::LE_64_STRING)),
+	FieldDef::new(56, 8, "Entry Size", Some(formatter::LE_64_STRING)),
+];
+
+const SHDR_64_BE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::BE_32_STRING)),
+	FieldDef::new(4, 4, "Type", Some(formatter::BE_HEX)),
+	FieldDef::new(8, 8, "Flags", Some(formatter::BE_64_PTR)),
+	FieldDef::new(16, 8, "Address", Some(formatter::BE_64_PTR)),
+	FieldDef::new(24, 8, "Offset", Some(formatter::BE_64_PTR)),
+	FieldDef::new(32, 8, "Size", Some(formatter::BE_64_STRING)),
+	FieldDef::new(40, 4, "Link", Some(formatter::BE_32_STRING)),
+	FieldDef::new(44, 4, "Info", Some(formatter::BE_32_STRING)),
+	FieldDef::new(48, 8, "Alignment", Some(formatter::BE_64_STRING)),
+	FieldDef::new(56, 8, "Entry Size", Some(formatter::BE_64_STRING)),
+];
+
+const SYM_32_LE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::LE_32_STRING)),
+	FieldDef::new(4, 4, "Value", Some(formatter::LE_32_PTR)),
+	FieldDef::new(8, 4, "Size", Some(formatter::LE_32_STRING)),
+	FieldDef::new(12, 1, "Info", Some(formatter::LE_8_HEX)),
+	FieldDef::new(13, 1, "Other", Some(formatter::LE_8_HEX)),
+	FieldDef::new(14, 2, "Section Index", Some(formatter::LE_16_STRING)),
+];
+
+const SYM_32_BE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::BE_32_STRING)),
+	FieldDef::new(4, 4, "Value", Some(formatter::BE_32_PTR)),
+	FieldDef::new(8, 4, "Size", Some(formatter::BE_32_STRING)),
+	FieldDef::new(12, 1, "Info", Some(formatter::BE_HEX)),
+	FieldDef::new(13, 1, "Other", Some(formatter::BE_HEX)),
+	FieldDef::new(14, 2, "Section Index", Some(formatter::BE_16_STRING)),
+];
+
+const SYM_64_LE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::LE_32_STRING)),
+	FieldDef::new(4, 1, "Info", Some(formatter::LE_8_HEX)),
+	FieldDef::new(5, 1, "Other", Some(formatter::LE_8_HEX)),
+	FieldDef::new(6, 2, "Section Index", Some(formatter::LE_16_STRING)),
+	FieldDef::new(8, 8, "Value", Some(formatter::LE_64_PTR)),
+	FieldDef::new(16, 8, "Size", Some(formatter::LE_64_STRING)),
+];
+
+const SYM_64_BE: &[FieldDef] = &[
+	FieldDef::new(0, 4, "Name Offset", Some(formatter::BE_32_STRING)),
+	FieldDef::new(4, 1, "Info", Some(formatter::BE_HEX)),
+	FieldDef::new(5, 1, "Other", Some(formatter::BE_HEX)),
+	FieldDef::new(6, 2, "Section Index", Some(formatter::BE_16_STRING)),
+	FieldDef::new(8, 8, "Value", Some(formatter::BE_64_PTR)),
+	FieldDef::new(16, 8, "Size", Some(formatter::BE_64_STRING)),
 ];
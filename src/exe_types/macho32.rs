@@ -4,26 +4,31 @@
 
 use anyhow::Result;
 use memmap2::Mmap;
-use std::{fmt, ops::Deref};
+use std::{fmt, ops::Deref, path::{Path, PathBuf}};
 
+#[cfg(feature = "tui")]
+use crate::windows::line::{Line, PairVec};
 use crate::{
-    formatter::FieldMap,
-    windows::line::{Line, PairVec},
+    decoders::reader::{Endian, Reader},
+    formatter::{self, FieldDef, FieldMap},
 };
 
-use super::Executable;
+use super::{
+    macho64::{self, LAYOUT32},
+    AddressSpace, Architecture, Executable, Section, Segment, StructureMap, Symbol,
+};
 
 // ------------------------------------------------------------------------
 
 pub struct MachO32 {
-    filename: String,
+    filename: PathBuf,
     mmap: Mmap,
 }
 
 impl MachO32 {
-    pub fn new(filename: &str, mmap: Mmap) -> Self {
+    pub fn new(filename: &Path, mmap: Mmap) -> Self {
         MachO32 {
-            filename: String::from(filename),
+            filename: filename.to_path_buf(),
             mmap,
         }
     }
@@ -31,20 +36,17 @@ impl MachO32 {
 
 // ------------------------------------------------------------------------
 
+#[cfg(feature = "tui")]
 impl<'l> Line<'l> for MachO32 {
     fn as_pairs(&self, _max_len: usize) -> Result<PairVec> {
-        Ok(Vec::from([(
-            None,
-            String::from("Mach-O 32 Not Supported Yet"),
-        )]))
+        Ok(Vec::from([(None, self.to_string())]))
     }
-
 }
 
 // ------------------------------------------------------------------------
 
 impl Executable for MachO32 {
-    fn filename(&self) -> &str {
+    fn filename(&self) -> &Path {
         &self.filename
     }
     fn len(&self) -> usize {
@@ -53,8 +55,39 @@ impl Executable for MachO32 {
     fn mmap(&self) -> &[u8] {
         self.mmap.deref()
     }
-    fn header_map(&self) -> &FieldMap {
-        todo!("Header map not implmeneted for MachO32")
+    fn header_map(&self) -> &FieldMap<'_> {
+        &HEADER_MAP
+    }
+    fn architecture(&self) -> Option<Architecture> {
+        Reader::at(self.mmap.deref(), 4, Endian::Little).u32().ok().map(Architecture::from_macho)
+    }
+    fn endianness(&self) -> Option<Endian> {
+        Some(Endian::Little)
+    }
+    fn bitness(&self) -> Option<u32> {
+        Some(32)
+    }
+    fn entry_point(&self) -> Option<u64> {
+        macho64::main_entry(self.mmap.deref(), &LAYOUT32).ok().flatten()
+    }
+    fn segments(&self) -> Vec<Segment> {
+        macho64::segment_commands(self.mmap.deref(), &LAYOUT32).unwrap_or_default()
+    }
+    fn sections(&self) -> Vec<Section> {
+        macho64::section_headers(self.mmap.deref(), &LAYOUT32).unwrap_or_default()
+    }
+    fn symbols(&self) -> Vec<Symbol> {
+        macho64::symbol_table(self.mmap.deref(), &LAYOUT32).unwrap_or_default()
+    }
+    fn dependencies(&self) -> Vec<String> {
+        macho64::dylibs(self.mmap.deref(), &LAYOUT32).unwrap_or_default()
+    }
+    fn address_space(&self) -> AddressSpace {
+        macho64::address_space(self.mmap.deref(), &LAYOUT32)
+    }
+
+    fn structures(&self) -> Vec<StructureMap<'_>> {
+        macho64::load_command_structures(self.mmap.deref(), &LAYOUT32, &HEADER_MAP)
     }
 }
 
@@ -69,9 +102,25 @@ impl fmt::Debug for MachO32 {
         write!(
             f,
             "Mach-O 32 Bit: {}: {:p}/{}",
-            self.filename,
+            self.filename.display(),
             self.mmap.as_ptr(),
             self.len(),
         )
     }
 }
+
+// ------------------------------------------------------------------------
+
+const HEADER_MAP: FieldMap = FieldMap::new(HEADER);
+
+/// The same as the 64 bit header without the reserved field at the end
+const HEADER: &[FieldDef] = &[
+    FieldDef::new(0, 4, "Magic Number", Some(formatter::LE_32_HEX)),
+    FieldDef::new(4, 4, "CPU Type", Some(formatter::LE_32_HEX))
+        .val_tbl(formatter::LE_32_USIZE, super::macho64::CPU_TYPE),
+    FieldDef::new(8, 4, "CPU Sub-Type", Some(formatter::LE_32_HEX)),
+    FieldDef::new(12, 4, "File Type", Some(formatter::LE_32_HEX)),
+    FieldDef::new(16, 4, "Load Commands", Some(formatter::LE_32_STRING)),
+    FieldDef::new(20, 4, "Load Command Length", Some(formatter::LE_32_PTR)),
+    FieldDef::new(24, 4, "Flags", Some(formatter::BIN_STRING)),
+];
@@ -4,222 +4,459 @@
 //! - <https://github.com/aidansteele/osx-abi-macho-file-format-reference>
 //! - <https://en.wikipedia.org/wiki/Mach-O>
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use memmap2::Mmap;
-use std::{fmt, ops::Deref};
+use std::{fmt, ops::Deref, path::{Path, PathBuf}};
 
 use crate::{
-    color::{Colors, WindowColors},
-    formatter::{self, FieldDef, FieldMap, ValEntry},
-    screens::details_list,
-    windows::{
-        details,
-        line::{self, ActionType, Line, PairVec},
-    },
+    decoders::reader::{self, Endian, Reader},
+    formatter::{self, Enter, FieldDef, FieldMap},
 };
 
-use super::{ExeRef, Executable};
+use super::{AddressSpace, Architecture, Executable, Section, Segment, StructureMap, Symbol, SymbolKind};
 
 // ------------------------------------------------------------------------
 
 pub struct MachO64 {
-    filename: String,
+    filename: PathBuf,
     mmap: Mmap,
 }
 
 // ------------------------------------------------------------------------
 
 impl MachO64 {
-    pub fn new(filename: &str, mmap: Mmap) -> Self {
+    pub fn new(filename: &Path, mmap: Mmap) -> Self {
         Self {
-            filename: String::from(filename),
+            filename: filename.to_path_buf(),
             mmap,
         }
     }
-}
-
-// ------------------------------------------------------------------------
 
-impl Executable for MachO64 {
-    fn filename(&self) -> &str {
-        &self.filename
+    pub fn reader(&self) -> Reader<'_> {
+        Reader::new(self.mmap.deref(), Endian::Little)
     }
-    fn len(&self) -> usize {
-        self.mmap.len()
+
+    /// Type, offset and length of each load command
+    pub fn load_commands(&self) -> LoadCommands {
+        load_commands(self.mmap.deref(), &LAYOUT64)
     }
-    fn mmap(&self) -> &[u8] {
-        self.mmap.deref()
+
+    /// Parse the sections of every `LC_SEGMENT_64` command
+    pub fn section_headers(&self) -> Result<Vec<Section>> {
+        section_headers(self.mmap.deref(), &LAYOUT64)
     }
-    fn header_map(&self) -> &FieldMap {
-        &HEADER_MAP
+
+    /// Parse the address ranges of every `LC_SEGMENT_64` command
+    pub fn segment_commands(&self) -> Result<Vec<Segment>> {
+        segment_commands(self.mmap.deref(), &LAYOUT64)
     }
-}
 
-impl fmt::Display for MachO64 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "Mach-O 64 Bit")
+    /// Install names of the libraries of the `LC_LOAD_DYLIB` style commands
+    pub fn dylibs(&self) -> Result<Vec<String>> {
+        dylibs(self.mmap.deref(), &LAYOUT64)
     }
-}
 
-impl fmt::Debug for MachO64 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(
-            f,
-            "Mach-O 64 Bit: {}: {:p}/{}",
-            self.filename,
-            self.mmap.as_ptr(),
-            self.len(),
-        )
+    /// Parse the `LC_SYMTAB` symbol table, skipping debugger entries
+    pub fn symbol_table(&self) -> Result<Vec<Symbol>> {
+        symbol_table(self.mmap.deref(), &LAYOUT64)
     }
 }
 
 // ------------------------------------------------------------------------
-/// Load commands line -> new window listing the load commands
-
-struct CmdLine<'e> {
-    exe: ExeRef<'e>,
-    data: (usize, usize),
-    val_entry: Option<&'e ValEntry<'e>>,
-    fields: &'e [FieldDef<'e>],
-    wc: WindowColors,
-    action: Option<ActionType<'e>>
 
+/// Sizes and commands which differ between the 32 and 64 bit formats, the
+/// load commands are otherwise walked the same way
+pub struct Layout {
+    bits: u32,
+    header_len: usize,
+    segment_cmd: u32,
+    segment_len: usize,
+    section_len: usize,
+    nlist_len: usize,
 }
 
-const DTL_INDENT: usize = 7;
-
-impl<'cl> CmdLine<'cl> {
-    fn new(
-        exe: ExeRef<'cl>,
-        cmd_offset: usize,
-        cmd_len: usize,
-        cmd_slice: &[u8],
-        wc: WindowColors,
-    ) -> CmdLine<'cl> {
-
-        let val_entry = CMD_HEADER[0].lookup(cmd_slice);
-        let data = (cmd_offset, cmd_offset + cmd_len);
-
-        let action = 
-            if let Some(val_entry) = val_entry {
-                if let Some(detail_map) = &val_entry.2 {
-                    Some(ActionType::Expandable(
-                        Box::new(move | | details::to_lines(exe, data, detail_map, wc)), 
-                        0, 
-                        DTL_INDENT
-                    ))
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-        CmdLine {
-            exe, wc, val_entry, action, data,
-            fields: CMD_HEADER,
+impl Layout {
+    /// Read an address or size, which are the width of the format
+    fn word(&self, rdr: &mut Reader) -> Result<u64> {
+        if self.bits == 64 {
+            rdr.u64()
+        } else {
+            rdr.u32().map(u64::from)
         }
-
     }
 }
 
-impl<'l> Line<'l> for CmdLine<'l> {
-    fn as_pairs(&self, _max_len: usize) -> Result<PairVec> {
-        let data = &self.exe.mmap()[self.data.0..self.data.1];
+pub const LAYOUT64: Layout = Layout {
+    bits: 64,
+    header_len: 32,
+    segment_cmd: LC_SEGMENT_64,
+    segment_len: 72,
+    section_len: 80,
+    nlist_len: 16,
+};
 
-        let mut pairs = Vec::from([
-            (
-                Some(self.wc.text),
-                format!("{:6}", self.fields[1].to_usize(data)),
-            ),
-            (Some(self.wc.text), String::from(" ")),
-            (Some(self.wc.text), self.fields[0].to_string(data)?),
-        ]);
+pub const LAYOUT32: Layout = Layout {
+    bits: 32,
+    header_len: 28,
+    segment_cmd: LC_SEGMENT,
+    segment_len: 56,
+    section_len: 68,
+    nlist_len: 12,
+};
 
-        if let Some(desc) = self.val_entry {
-            pairs.push((Some(self.wc.value), format!(" ({})", desc.1)));
-        };
+/// The load commands up to the first that cannot be read or stepped over
+#[derive(Debug, Default)]
+pub struct LoadCommands {
+    /// Type, offset and length of each command
+    pub commands: Vec<(u32, usize, usize)>,
+    /// Why the walk stopped before the last command
+    pub error: Option<anyhow::Error>,
+}
+
+impl IntoIterator for LoadCommands {
+    type Item = (u32, usize, usize);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commands.into_iter()
+    }
+}
 
-        Ok(pairs)
+/// Type, offset and length of each load command, those before a bad
+/// command are kept with the error
+pub fn load_commands(data: &[u8], layout: &Layout) -> LoadCommands {
+    let mut cmds = LoadCommands::default();
+    if let Err(err) = walk_load_commands(data, layout, &mut cmds.commands) {
+        cmds.error = Some(err);
     }
+    cmds
+}
 
-    fn expand(&self) -> Option<usize> {
-        if let Some(val_entry) = self.val_entry {
-            if val_entry.2.is_some() {
-                return Some(DTL_INDENT);
-            }
+fn walk_load_commands(data: &[u8], layout: &Layout, cmds: &mut Vec<(u32, usize, usize)>) -> Result<()> {
+    let mut rdr = Reader::at(data, 16, Endian::Little);
+    let num_cmds = rdr.u32()?;
+
+    let mut cmd_offset = layout.header_len;
+    for _ in 0..num_cmds {
+        rdr.seek(cmd_offset);
+        let cmd = rdr.u32()?;
+        let cmd_len = rdr.u32()? as usize;
+        if cmd_len < CMD_HEADER_MAP.data_len {
+            bail!("Load command at {:#x} has invalid length {}", cmd_offset, cmd_len);
         }
-        None
+        cmds.push((cmd, cmd_offset, cmd_len));
+        cmd_offset = cmd_offset
+            .checked_add(cmd_len)
+            .ok_or_else(|| anyhow!("Load command at {:#x} overflows", cmd_offset))?;
     }
 
-    fn expand_fn(&self) -> Result<Option<line::LineVec<'l>>> {
-        let mut rc = None;
+    Ok(())
+}
 
-        if let Some(val_entry) = self.val_entry {
-            if let Some(detail_map) = &val_entry.2 {
-                rc = Some(
-                    details::to_lines(self.exe, self.data, detail_map, self.wc)
-                );
-            }
+/// Parse the sections of every segment command
+pub fn section_headers(data: &[u8], layout: &Layout) -> Result<Vec<Section>> {
+    let mut sections = Vec::new();
+    let mut rdr = Reader::new(data, Endian::Little);
+
+    for (_, cmd_offset, _) in load_commands(data, layout)
+        .into_iter()
+        .filter(|c| c.0 == layout.segment_cmd)
+    {
+        // The section count is the second last field in both formats
+        rdr.seek(cmd_offset + layout.segment_len - 8);
+        let num_sects = rdr.u32()? as usize;
+
+        for index in 0..num_sects {
+            rdr.seek(cmd_offset + layout.segment_len + index * layout.section_len);
+            let name = fixed_str(rdr.bytes(16)?);
+            let segment = fixed_str(rdr.bytes(16)?);
+            let addr = layout.word(&mut rdr)?;
+            let size = layout.word(&mut rdr)?;
+            let offset = rdr.u32()?;
+            let _align = rdr.u32()?;
+            let _reloff = rdr.u32()?;
+            let _nreloc = rdr.u32()?;
+            let flags = rdr.u32()?;
+            let reserved1 = rdr.u32()?;
+            let reserved2 = rdr.u32()?;
+
+            let zerofill = matches!(flags & SECTION_TYPE, S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL);
+
+            sections.push(Section {
+                segment, name, addr, size,
+                offset: offset.into(),
+                file_size: if zerofill { 0 } else { size },
+                kind: flags & SECTION_TYPE,
+                flags: flags.into(),
+                link: reserved1,
+                info: reserved2,
+                entsize: 0,
+            });
         }
+    }
+
+    Ok(sections)
+}
 
-        Ok(rc)
+/// Parse the address ranges of every segment command
+pub fn segment_commands(data: &[u8], layout: &Layout) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut rdr = Reader::new(data, Endian::Little);
+
+    for (kind, cmd_offset, _) in load_commands(data, layout)
+        .into_iter()
+        .filter(|c| c.0 == layout.segment_cmd)
+    {
+        rdr.seek(cmd_offset + 8);
+        let name = fixed_str(rdr.bytes(16)?);
+        let addr = layout.word(&mut rdr)?;
+        let size = layout.word(&mut rdr)?;
+        let offset = layout.word(&mut rdr)?;
+        let file_size = layout.word(&mut rdr)?;
+        let _max_prot = rdr.u32()?;
+        let init_prot = rdr.u32()?;
+
+        // VM_PROT_READ, VM_PROT_WRITE and VM_PROT_EXECUTE are in the
+        // opposite order to the ELF flags
+        let flags = [
+            (VM_PROT_READ, Segment::READ),
+            (VM_PROT_WRITE, Segment::WRITE),
+            (VM_PROT_EXECUTE, Segment::EXECUTE),
+        ]
+        .iter()
+        .filter(|p| init_prot & p.0 != 0)
+        .fold(0, |flags, p| flags | p.1);
+        segments.push(Segment { name, kind, flags, addr, size, offset, file_size });
     }
 
-    fn action_type(&self) -> Option<&ActionType<'l>> {
-        if let Some(ref at) = self.action {
-            Some(at)
-        } else {
-            None
-        }
+    Ok(segments)
+}
+
+/// Virtual address of `main` from the `LC_MAIN` command, which holds its
+/// file offset
+pub fn main_entry(data: &[u8], layout: &Layout) -> Result<Option<u64>> {
+    let Some((_, cmd_offset, _)) = load_commands(data, layout).into_iter().find(|c| c.0 == LC_MAIN) else {
+        return Ok(None);
+    };
+    let entry_offset = Reader::at(data, cmd_offset + 8, Endian::Little).u64()?;
+    Ok(address_space(data, layout).to_addr(usize::try_from(entry_offset)?))
+}
+
+/// Map from file offsets to addresses built from the segment commands
+pub fn address_space(data: &[u8], layout: &Layout) -> AddressSpace {
+    let segments = segment_commands(data, layout).unwrap_or_default();
+    AddressSpace::new(segments.iter().map(Segment::mapping).collect())
+}
+
+/// Install names of the libraries of the `LC_LOAD_DYLIB` style commands
+pub fn dylibs(data: &[u8], layout: &Layout) -> Result<Vec<String>> {
+    let mut dylibs = Vec::new();
+    for (_, cmd_offset, cmd_len) in load_commands(data, layout)
+        .into_iter()
+        .filter(|c| DYLIB_COMMANDS.contains(&c.0))
+    {
+        let cmd = data
+            .get(cmd_offset..cmd_offset.saturating_add(cmd_len))
+            .ok_or_else(|| anyhow!("Load command at {:#x} is out of bounds", cmd_offset))?;
+        let name_offset = Reader::at(cmd, 8, Endian::Little).u32()?;
+        dylibs.push(reader::cstr_at(cmd, name_offset as usize)?.to_string());
     }
-    fn action_type_mut(&mut self) -> Option<&mut ActionType<'l>> {
-        if let Some(ref mut at) = self.action {
-            Some(at)
-        } else {
-            None
+    Ok(dylibs)
+}
+
+/// Parse the `LC_SYMTAB` symbol table, skipping debugger entries.
+/// Mach-O has no symbol sizes so they run to the next symbol
+pub fn symbol_table(data: &[u8], layout: &Layout) -> Result<Vec<Symbol>> {
+    let sections = section_headers(data, layout)?;
+    let mut symbols = Vec::new();
+    let mut rdr = Reader::new(data, Endian::Little);
+
+    let Some((_, cmd_offset, _)) = load_commands(data, layout)
+        .into_iter()
+        .find(|c| c.0 == LC_SYMTAB)
+    else {
+        return Ok(symbols);
+    };
+
+    rdr.seek(cmd_offset + 8);
+    let symoff = rdr.u32()? as usize;
+    let nsyms = rdr.u32()? as usize;
+    let stroff = rdr.u32()? as usize;
+    let strsize = rdr.u32()? as usize;
+    let strtab = data.get(stroff..stroff.saturating_add(strsize)).unwrap_or(&[]);
+
+    for index in 0..nsyms {
+        rdr.seek(symoff + index * layout.nlist_len);
+        let strx = rdr.u32()?;
+        let n_type = rdr.u8()?;
+        let n_sect = rdr.u8()?;
+        let _n_desc = rdr.u16()?;
+        let addr = layout.word(&mut rdr)?;
+        if n_type & N_STAB != 0 {
+            continue;
         }
+
+        let defined = n_type & N_TYPE == N_SECT;
+        let section = usize::from(n_sect).checked_sub(1).and_then(|i| sections.get(i));
+        symbols.push(Symbol {
+            name: reader::cstr_at(strtab, strx as usize).unwrap_or("???").into(),
+            addr,
+            size: 0,
+            kind: match section {
+                Some(s) if defined && s.flags & u64::from(S_ATTR_PURE_INSTRUCTIONS) != 0 => SymbolKind::Function,
+                Some(_) if defined => SymbolKind::Object,
+                _ => SymbolKind::Other,
+            },
+            global: n_type & N_EXT != 0,
+            defined,
+            dynamic: false,
+            index,
+        });
+    }
+
+    // Size each defined symbol up to the next one or the section end
+    let mut starts: Vec<u64> = symbols.iter().filter(|s| s.defined).map(|s| s.addr).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    for symbol in symbols.iter_mut().filter(|s| s.defined) {
+        let section_end = sections.iter()
+            .find(|s| s.contains(symbol.addr))
+            .map_or(symbol.addr, |s| s.addr.saturating_add(s.size));
+        let next = starts.partition_point(|a| *a <= symbol.addr);
+        let end = starts.get(next).map_or(section_end, |a| (*a).min(section_end));
+        symbol.size = end.saturating_sub(symbol.addr);
     }
 
+    Ok(symbols)
 }
 
-// ------------------------------------------------------------------------
+/// Header and load command structures, the command type expands a load
+/// command to its command specific map where there is one
+pub fn load_command_structures<'m>(
+    data: &[u8],
+    layout: &Layout,
+    header_map: &'m FieldMap<'m>,
+) -> Vec<StructureMap<'m>> {
+    let mut structures = Vec::from([StructureMap::new("Mach-O Header", (0, header_map.data_len), header_map)]);
+    for (_, cmd_offset, cmd_len) in load_commands(data, layout) {
+        structures.push(StructureMap::new("Load Command", (cmd_offset, cmd_offset + cmd_len), &CMD_HEADER_MAP));
+    }
+    structures
+}
+
+/// Name from a fixed length, possibly unterminated, field
+pub fn fixed_str(data: &[u8]) -> String {
+    let len = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..len]).into()
+}
+
+pub const LC_SEGMENT: u32 = 0x1;
+pub const LC_SYMTAB: u32 = 0x2;
+pub const LC_LOAD_DYLIB: u32 = 0xc;
+pub const LC_SEGMENT_64: u32 = 0x19;
+pub const LC_LAZY_LOAD_DYLIB: u32 = 0x20;
+pub const LC_LOAD_WEAK_DYLIB: u32 = 0x8000_0018;
+pub const LC_REEXPORT_DYLIB: u32 = 0x8000_001f;
+pub const LC_LOAD_UPWARD_DYLIB: u32 = 0x8000_0023;
+pub const LC_MAIN: u32 = 0x8000_0028;
+
+const DYLIB_COMMANDS: &[u32] = &[
+    LC_LOAD_DYLIB, LC_LAZY_LOAD_DYLIB, LC_LOAD_WEAK_DYLIB, LC_REEXPORT_DYLIB, LC_LOAD_UPWARD_DYLIB,
+];
 
-fn list_load_commands_on_enter<'lce>(exe: ExeRef<'lce>) -> Result<()> {
-    let wsc = Colors::global().get_window_set_colors("list")?;
+const VM_PROT_READ: u32 = 0x1;
+const VM_PROT_WRITE: u32 = 0x2;
+const VM_PROT_EXECUTE: u32 = 0x4;
 
-    let num_cmds = HEADER[4].to_usize(exe.mmap());
-    let cmds_len = HEADER[5].to_usize(exe.mmap());
-    let mut cmd_offset = HEADER_MAP.data_len;
+const N_STAB: u8 = 0xe0;
+const N_TYPE: u8 = 0x0e;
+const N_EXT: u8 = 0x01;
+const N_SECT: u8 = 0x0e;
+const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x8000_0000;
 
-    let mut lines: line::LineVec<'lce> = Vec::with_capacity(num_cmds);
-    for _ in 0..num_cmds {
-        let cmd_slice = &exe.mmap()[cmd_offset..cmd_offset + CMD_HEADER_MAP.data_len];
-        let cmd_len: usize = CMD_HEADER[1].to_usize(cmd_slice);
-        lines.push(Box::new(CmdLine::new(exe, cmd_offset, cmd_len, cmd_slice, wsc.scrollable_region)));
-        cmd_offset += cmd_len;
+const SECTION_TYPE: u32 = 0xff;
+const S_ZEROFILL: u32 = 0x1;
+const S_GB_ZEROFILL: u32 = 0xc;
+const S_THREAD_LOCAL_ZEROFILL: u32 = 0x12;
+
+// ------------------------------------------------------------------------
+
+impl Executable for MachO64 {
+    fn filename(&self) -> &Path {
+        &self.filename
+    }
+    fn len(&self) -> usize {
+        self.mmap.len()
+    }
+    fn mmap(&self) -> &[u8] {
+        self.mmap.deref()
+    }
+    fn header_map(&self) -> &FieldMap<'_> {
+        &HEADER_MAP
+    }
+    fn architecture(&self) -> Option<Architecture> {
+        Reader::at(self.mmap.deref(), 4, Endian::Little).u32().ok().map(Architecture::from_macho)
+    }
+    fn endianness(&self) -> Option<Endian> {
+        Some(Endian::Little)
+    }
+    fn bitness(&self) -> Option<u32> {
+        Some(64)
+    }
+    fn entry_point(&self) -> Option<u64> {
+        main_entry(self.mmap.deref(), &LAYOUT64).ok().flatten()
+    }
+    fn segments(&self) -> Vec<Segment> {
+        self.segment_commands().unwrap_or_default()
+    }
+    fn sections(&self) -> Vec<Section> {
+        self.section_headers().unwrap_or_default()
+    }
+    fn symbols(&self) -> Vec<Symbol> {
+        self.symbol_table().unwrap_or_default()
+    }
+    fn dependencies(&self) -> Vec<String> {
+        self.dylibs().unwrap_or_default()
+    }
+    fn address_space(&self) -> AddressSpace {
+        address_space(self.mmap.deref(), &LAYOUT64)
     }
 
-    let footer = format!(
-        "Mach-O Load Commands: {} commands, {} bytes",
-        num_cmds, cmds_len
-    );
+    fn structures(&self) -> Vec<StructureMap<'_>> {
+        load_command_structures(self.mmap.deref(), &LAYOUT64, &HEADER_MAP)
+    }
+}
 
-    details_list::show(lines, "Length Command", &footer, wsc)
+impl fmt::Display for MachO64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Mach-O 64 Bit")
+    }
+}
+
+impl fmt::Debug for MachO64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "Mach-O 64 Bit: {}: {:p}/{}",
+            self.filename.display(),
+            self.mmap.as_ptr(),
+            self.len(),
+        )
+    }
 }
 
 // ------------------------------------------------------------------------
 
-const HEADER_MAP: FieldMap = FieldMap::new(HEADER);
+pub const HEADER_MAP: FieldMap = FieldMap::new(HEADER);
 
-const HEADER: &[FieldDef] = &[
+pub const HEADER: &[FieldDef] = &[
     FieldDef::new(0, 4, "Magic Number", Some(formatter::LE_32_HEX)),
     FieldDef::new(4, 4, "CPU Type", Some(formatter::LE_32_HEX))
         .val_tbl(formatter::LE_32_USIZE, CPU_TYPE),
     FieldDef::new(8, 4, "CPU Sub-Type", Some(formatter::LE_32_HEX)),
     FieldDef::new(12, 4, "File Type", Some(formatter::LE_32_HEX)),
     FieldDef::new(16, 4, "Load Commands", Some(formatter::LE_32_STRING))
-        .enter_fn(list_load_commands_on_enter)
+        .on_enter(Enter::LoadCommands)
         .fn_usize(formatter::LE_32_USIZE),
     FieldDef::new(20, 4, "Load Command Length", Some(formatter::LE_32_PTR))
         .fn_usize(formatter::LE_32_USIZE),
@@ -227,7 +464,7 @@ const HEADER: &[FieldDef] = &[
     FieldDef::ignore(28, 4),
 ];
 
-const CPU_TYPE: &formatter::ValTable = &[
+pub const CPU_TYPE: &formatter::ValTable = &[
     (0x7, "x86", None),
     (0x01000007, "64 Bit x86", None),
     (0xC, "ARM", None),
@@ -236,9 +473,9 @@ const CPU_TYPE: &formatter::ValTable = &[
 
 // ------------------------------------------------------------------------
 
-const CMD_HEADER_MAP: FieldMap = FieldMap::new(CMD_HEADER);
+pub const CMD_HEADER_MAP: FieldMap = FieldMap::new(CMD_HEADER);
 
-const CMD_HEADER: &[FieldDef] = &[
+pub const CMD_HEADER: &[FieldDef] = &[
     FieldDef::new(0, 4, "Command Type", Some(formatter::LE_32_PTR))
         .fn_usize(formatter::LE_32_USIZE)
         .val_tbl(formatter::LE_32_USIZE, CMD_TYPE),
@@ -247,6 +484,7 @@ const CMD_HEADER: &[FieldDef] = &[
 ];
 
 const CMD_TYPE: &formatter::ValTable = &[
+    (0x1, "Segment Load", Some(SEGMENT_LOAD_MAP32)),
     (0x19, "Segment Load", Some(SEGMENT_LOAD_MAP64)),
     (
         0x0C,
@@ -285,6 +523,34 @@ const SEGMENT_LOAD64: &[FieldDef] = &[
 
 // ------------------------------------------------------------------------
 
+const SEGMENT_LOAD_MAP32: FieldMap = FieldMap::new(SEGMENT_LOAD32);
+
+const SEGMENT_LOAD32: &[FieldDef] = &[
+    FieldDef::ignore(0, 4),
+    FieldDef::ignore(4, 4),
+    FieldDef::new(8, 16, "Segment Name", Some(formatter::BE_CHAR)),
+    FieldDef::new(24, 4, "Address", Some(formatter::LE_32_PTR)),
+    FieldDef::new(28, 4, "Address Size", Some(formatter::LE_32_PTR)),
+    FieldDef::new(32, 4, "File Offset", Some(formatter::LE_32_PTR)),
+    FieldDef::new(36, 4, "Size", Some(formatter::LE_32_PTR)),
+    FieldDef::new(
+        40,
+        4,
+        "Maximum Memory Protections",
+        Some(formatter::LE_32_PTR),
+    ),
+    FieldDef::new(
+        44,
+        4,
+        "Initial Memory Protections",
+        Some(formatter::LE_32_PTR),
+    ),
+    FieldDef::new(48, 4, "Number of Sections", Some(formatter::LE_32_STRING)),
+    FieldDef::new(52, 4, "Flags", Some(formatter::BIN_STRING)),
+];
+
+// ------------------------------------------------------------------------
+
 const DLL_FULL_PATH_MAP: FieldMap = FieldMap::new(DLL_FULL_PATH);
 
 const DLL_FULL_PATH: &[FieldDef] = &[
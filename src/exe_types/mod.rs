@@ -2,30 +2,89 @@
 //! Executable file typing
 //!
 
+pub mod address_space;
+pub mod architecture;
 pub mod elf;
 pub mod macho32;
 pub mod macho64;
 pub mod notexe;
+pub mod pe;
+#[cfg(feature = "scan")]
+pub mod scan;
+pub mod section;
+pub mod segment;
+pub mod structure;
+pub mod symbol;
 
 use memmap2::Mmap;
-use std::{fmt, fs::File};
+use std::{fmt, fs::File, path::Path};
 
-use crate::formatter::FieldMap;
+use crate::{decoders::reader::Endian, formatter::FieldMap};
 
 use elf::ELF;
 use macho32::MachO32;
 use macho64::MachO64;
 use notexe::NotExecutable;
+use pe::PE;
+pub use address_space::{AddressSpace, Mapping};
+pub use architecture::Architecture;
+pub use section::Section;
+pub use segment::Segment;
+pub use structure::StructureMap;
+pub use symbol::{Symbol, SymbolKind, SymbolMap};
 
 // ------------------------------------------------------------------------
 /// Trait to be implemented by the various executable handlers
-
-pub trait Executable: fmt::Display + fmt::Debug {
-    fn filename(&self) -> &str;
+pub trait Executable: fmt::Display + fmt::Debug + Send + Sync {
+    fn filename(&self) -> &Path;
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool {self.len() == 0 }
     fn mmap(&self) -> &[u8];
-    fn header_map(&self) -> &FieldMap;
+    fn header_map(&self) -> &FieldMap<'_>;
+
+    /// Instruction set of the code, `None` if the format does not say
+    fn architecture(&self) -> Option<Architecture> { None }
+
+    /// Byte order of the values in the image
+    fn endianness(&self) -> Option<Endian> { None }
+
+    /// Size of an address in bits, 32 or 64
+    fn bitness(&self) -> Option<u32> { None }
+
+    /// Virtual address execution starts at, if the image has one
+    fn entry_point(&self) -> Option<u64> { None }
+
+    /// Segments in the image, empty if the format has none
+    fn segments(&self) -> Vec<Segment> { Vec::new() }
+
+    /// Sections in the image, empty if the format has none
+    fn sections(&self) -> Vec<Section> { Vec::new() }
+
+    /// Section by name, Mach-O sections are named `segment,section`
+    fn section(&self, name: &str) -> Option<Section> {
+        self.sections().into_iter().find(|s| s.full_name() == name)
+    }
+
+    /// Contents of the named section
+    fn section_data(&self, name: &str) -> Option<&[u8]> {
+        self.section(name).and_then(|s| s.data(self.mmap()))
+    }
+
+    /// Entries of the static and dynamic symbol tables
+    fn symbols(&self) -> Vec<Symbol> { Vec::new() }
+
+    /// Names of the shared libraries the image is linked against, as
+    /// recorded in the image
+    fn dependencies(&self) -> Vec<String> { Vec::new() }
+
+    /// Virtual addresses the file is loaded at, empty if unknown
+    fn address_space(&self) -> AddressSpace { AddressSpace::default() }
+
+    /// Structures decoded with a field map, with the tables they describe
+    /// as children
+    fn structures(&self) -> Vec<StructureMap<'_>> {
+        Vec::from([StructureMap::new("Header", (0, self.header_map().data_len), self.header_map())])
+    }
 }
 
 // Convenience types for the executable trait
@@ -37,7 +96,8 @@ pub type ExeVec<'e> = Vec<ExeItem<'e>>;
 // ------------------------------------------------------------------------
 // Constructor for an executable object
 
-pub fn new(filename: &str) -> ExeItem<'_> {
+pub fn new<'e>(filename: impl AsRef<Path>) -> ExeItem<'e> {
+    let filename = filename.as_ref();
     let fd = match File::open(filename) {
         Ok(f) => f,
         Err(msg) => return Box::new(NotExecutable::new(filename, msg.to_string())),
@@ -64,6 +124,10 @@ pub fn new(filename: &str) -> ExeItem<'_> {
             Ok(elf) => Box::new(elf),
             Err(msg) => Box::new(NotExecutable::new(filename, msg.to_string())),
         },
+        v if v & 0xffff == 0x5a4d || v >> 16 == 0x4d5a => match PE::new(filename, mmap) {
+            Ok(pe) => Box::new(pe),
+            Err(msg) => Box::new(NotExecutable::new(filename, msg.to_string())),
+        },
         // 0xcafebabe => ExeType::UNIVBIN,
         // 0xbebafeca => ExeType::UNIVBIN,
         v => Box::new(NotExecutable::new(
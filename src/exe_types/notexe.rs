@@ -2,23 +2,22 @@
 //! Not Executable file typing
 //!
 
-use std::fmt;
+use std::{fmt, path::{Path, PathBuf}};
 
-use crate::formatter::FieldMap;
+use crate::formatter::{FieldDef, FieldMap};
 
-use super::Executable;
+use super::{Executable, StructureMap};
 
 /// Simple not executable file
-
 pub struct NotExecutable {
-    pub filename: String,
+    pub filename: PathBuf,
     pub msg: String,
 }
 
 impl NotExecutable {
-    pub fn new(filename: &str, msg: String) -> Self {
+    pub fn new(filename: &Path, msg: String) -> Self {
         Self {
-            filename: filename.to_string(),
+            filename: filename.to_path_buf(),
             msg,
         }
     }
@@ -26,10 +25,8 @@ impl NotExecutable {
 
 // ------------------------------------------------------------------------
 /// Basic trait implementation for a non-executable file
-///
-
 impl Executable for NotExecutable {
-    fn filename(&self) -> &str {
+    fn filename(&self) -> &Path {
         &self.filename
     }
     fn len(&self) -> usize {
@@ -38,13 +35,18 @@ impl Executable for NotExecutable {
     fn is_empty(&self) -> bool {self.len() == 0 }
 
     fn mmap(&self) -> &[u8] {
-        panic!("Mmap called on non-executable")
+        &[]
     }
-    fn header_map(&self) -> &FieldMap {
-        panic!("Header map called on non-executable")
+    fn header_map(&self) -> &FieldMap<'_> {
+        &EMPTY_MAP
+    }
+    fn structures(&self) -> Vec<StructureMap<'_>> {
+        Vec::new()
     }
 }
 
+const EMPTY_MAP: FieldMap = FieldMap::new(&[] as &[FieldDef]);
+
 impl fmt::Display for NotExecutable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "Not Executable")
@@ -56,7 +58,7 @@ impl fmt::Debug for NotExecutable {
         write!(
             f,
             "Not Executable filename:{}, msg: {}",
-            self.filename, self.msg
+            self.filename.display(), self.msg
         )
     }
 }
@@ -0,0 +1,377 @@
+//!
+//! Formatter for the Windows Portable Executable format
+//!
+//! - <https://learn.microsoft.com/en-us/windows/win32/debug/pe-format>
+
+use anyhow::{anyhow, bail, Result};
+use memmap2::Mmap;
+use std::{fmt, ops::Deref, path::{Path, PathBuf}};
+
+use crate::{
+    decoders::reader::{self, Endian, Reader},
+    exe_types::macho64::fixed_str,
+    formatter::{self, Enter, FieldDef, FieldMap},
+};
+
+use super::{AddressSpace, Architecture, Executable, Mapping, Section, StructureMap};
+
+// ------------------------------------------------------------------------
+
+pub struct PE {
+    filename: PathBuf,
+    mmap: Mmap,
+}
+
+// ------------------------------------------------------------------------
+
+impl PE {
+    pub fn new(filename: &Path, mmap: Mmap) -> Result<Self> {
+        let nt_offset = nt_header_offset(mmap.deref())?;
+
+        if mmap.get(nt_offset..nt_offset + 4) != Some(b"PE\0\0") {
+            bail!("MS-DOS executable, no PE signature at {:#x}", nt_offset)
+        }
+
+        Ok(Self {
+            filename: filename.to_path_buf(),
+            mmap,
+        })
+    }
+
+    pub fn reader(&self) -> Reader<'_> {
+        Reader::new(self.mmap.deref(), Endian::Little)
+    }
+
+    /// Offset of the optional header and whether it is the PE32+ one
+    fn optional_header(&self) -> Result<(usize, bool)> {
+        let opt_offset = nt_header_offset(self.mmap.deref())? + NT_HEADER_MAP.data_len;
+        let magic = Reader::at(self.mmap.deref(), opt_offset, Endian::Little).u16()?;
+        Ok((opt_offset, magic == PE32_PLUS_MAGIC))
+    }
+
+    /// Preferred load address, `ImageBase` of the optional header
+    pub fn image_base(&self) -> Result<u64> {
+        let (opt_offset, is64) = self.optional_header()?;
+        match is64 {
+            true => Reader::at(self.mmap.deref(), opt_offset + 24, Endian::Little).u64(),
+            false => Reader::at(self.mmap.deref(), opt_offset + 28, Endian::Little).u32().map(u64::from),
+        }
+    }
+
+    /// Virtual address of `AddressOfEntryPoint`, `None` for DLLs without one
+    fn entry(&self) -> Result<Option<u64>> {
+        let (opt_offset, _) = self.optional_header()?;
+        let entry = Reader::at(self.mmap.deref(), opt_offset + 16, Endian::Little).u32()?;
+        if entry == 0 {
+            return Ok(None);
+        }
+        self.image_base()?
+            .checked_add(u64::from(entry))
+            .map(Some)
+            .ok_or_else(|| anyhow!("Entry point {:#x} is past the end of the address space", entry))
+    }
+
+    /// Names of the DLLs in the import directory
+    pub fn imports(&self) -> Result<Vec<String>> {
+        let (opt_offset, is64) = self.optional_header()?;
+        let mut rdr = self.reader();
+        // The import table is the second data directory
+        rdr.seek(opt_offset + if is64 { 112 } else { 96 } + 8);
+        let rva = u64::from(rdr.u32()?);
+        let size = rdr.u32()?;
+        if rva == 0 || size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let space = self.address_space();
+        let image_base = self.image_base()?;
+        let offset = image_base
+            .checked_add(rva)
+            .and_then(|addr| space.to_offset(addr))
+            .ok_or_else(|| anyhow!("Import directory at {:#x} is not in the file", rva))?;
+
+        // One descriptor per DLL, ended by an empty one
+        let mut dlls = Vec::new();
+        rdr.seek(offset);
+        loop {
+            rdr.skip(12)?;
+            let name_rva = u64::from(rdr.u32()?);
+            rdr.skip(4)?;
+            if name_rva == 0 {
+                break;
+            }
+            let name = image_base
+                .checked_add(name_rva)
+                .and_then(|addr| space.to_offset(addr))
+                .and_then(|offset| reader::cstr_at(self.mmap.deref(), offset).ok())
+                .unwrap_or("???");
+            dlls.push(name.to_string());
+        }
+        Ok(dlls)
+    }
+
+    /// Parse the section table, addresses include the image base
+    pub fn section_headers(&self) -> Result<Vec<Section>> {
+        let nt_offset = nt_header_offset(self.mmap.deref())?;
+        let mut rdr = self.reader();
+
+        rdr.seek(nt_offset + 6);
+        let num_sects = rdr.u16()?;
+        rdr.seek(nt_offset + 20);
+        let opt_len = usize::from(rdr.u16()?);
+
+        let opt_offset = nt_offset + NT_HEADER_MAP.data_len;
+        let image_base = self.image_base()?;
+
+        rdr.seek(opt_offset + opt_len);
+        let mut sections = Vec::with_capacity(num_sects.into());
+        for _ in 0..num_sects {
+            let name = fixed_str(rdr.bytes(8)?);
+            let virt_size = u64::from(rdr.u32()?);
+            let virt_addr = u64::from(rdr.u32()?);
+            let raw_size = u64::from(rdr.u32()?);
+            let raw_offset = u64::from(rdr.u32()?);
+            rdr.skip(12)?;
+            let flags = rdr.u32()?;
+
+            let size = if virt_size == 0 { raw_size } else { virt_size };
+            let addr = image_base
+                .checked_add(virt_addr)
+                .ok_or_else(|| anyhow!("Section {} is past the end of the address space", name))?;
+            sections.push(Section {
+                segment: String::new(),
+                name,
+                addr,
+                size,
+                offset: raw_offset,
+                file_size: raw_size.min(size),
+                kind: 0,
+                flags: flags.into(),
+                link: 0,
+                info: 0,
+                entsize: 0,
+            });
+        }
+
+        Ok(sections)
+    }
+
+    /// The headers and sections as the loader maps them
+    pub fn mappings(&self) -> Result<Vec<Mapping>> {
+        let (opt_offset, _) = self.optional_header()?;
+        let image_base = self.image_base()?;
+        let mut rdr = self.reader();
+        rdr.seek(opt_offset + 60);
+        let headers_size = u64::from(rdr.u32()?);
+
+        let headers = Mapping {
+            name: String::from("Headers"),
+            addr: image_base,
+            size: headers_size,
+            offset: 0,
+            file_size: headers_size,
+        };
+        Ok(std::iter::once(headers)
+            .chain(self.section_headers()?.into_iter().map(|s| Mapping {
+                name: s.name,
+                addr: s.addr,
+                size: s.size,
+                offset: s.offset,
+                file_size: s.file_size,
+            }))
+            .collect())
+    }
+}
+
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+/// File offset of the NT headers (`e_lfanew`)
+pub fn nt_header_offset(mmap: &[u8]) -> Result<usize> {
+    match mmap.get(0x3c..0x40).and_then(formatter::LE_32_USIZE) {
+        Some(v) => Ok(v),
+        None => bail!("Too small for a DOS header: {}", mmap.len()),
+    }
+}
+
+// ------------------------------------------------------------------------
+
+impl Executable for PE {
+    fn filename(&self) -> &Path {
+        &self.filename
+    }
+    fn len(&self) -> usize {
+        self.mmap.len()
+    }
+    fn mmap(&self) -> &[u8] {
+        self.mmap.deref()
+    }
+    fn header_map(&self) -> &FieldMap<'_> {
+        &DOS_HEADER_MAP
+    }
+    fn architecture(&self) -> Option<Architecture> {
+        let nt_offset = nt_header_offset(self.mmap.deref()).ok()?;
+        Reader::at(self.mmap.deref(), nt_offset + 4, Endian::Little).u16().ok().map(Architecture::from_pe)
+    }
+    fn endianness(&self) -> Option<Endian> {
+        Some(Endian::Little)
+    }
+    fn bitness(&self) -> Option<u32> {
+        self.optional_header().ok().map(|(_, is64)| if is64 { 64 } else { 32 })
+    }
+    fn entry_point(&self) -> Option<u64> {
+        self.entry().ok().flatten()
+    }
+    fn sections(&self) -> Vec<Section> {
+        self.section_headers().unwrap_or_default()
+    }
+    fn dependencies(&self) -> Vec<String> {
+        self.imports().unwrap_or_default()
+    }
+    fn address_space(&self) -> AddressSpace {
+        AddressSpace::new(self.mappings().unwrap_or_default())
+    }
+    fn structures(&self) -> Vec<StructureMap<'_>> {
+        let mut structures = Vec::from([StructureMap::new("DOS Header", (0, DOS_HEADER_MAP.data_len), &DOS_HEADER_MAP)]);
+        if let Ok(nt_offset) = nt_header_offset(self.mmap.deref()) {
+            let opt_offset = nt_offset + NT_HEADER_MAP.data_len;
+            structures.push(StructureMap::new("NT Headers", (nt_offset, opt_offset), &NT_HEADER_MAP));
+            let map = match self.mmap.get(opt_offset..opt_offset + 2) {
+                Some([0x0b, 0x01]) => Some(&OPTIONAL_HEADER_MAP_32),
+                Some([0x0b, 0x02]) => Some(&OPTIONAL_HEADER_MAP_64),
+                _ => None,
+            };
+            if let Some(map) = map {
+                structures.push(StructureMap::new("Optional Header", (opt_offset, opt_offset + map.data_len), map));
+            }
+        }
+        structures
+    }
+}
+
+impl fmt::Display for PE {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Portable Executable")
+    }
+}
+
+impl fmt::Debug for PE {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "Portable Executable: {}: {:p}/{}",
+            self.filename.display(),
+            self.mmap.as_ptr(),
+            self.len(),
+        )
+    }
+}
+
+// ------------------------------------------------------------------------
+
+const DOS_HEADER_MAP: FieldMap = FieldMap::new(DOS_HEADER);
+
+const DOS_HEADER: &[FieldDef] = &[
+    FieldDef::new(0, 2, "Magic Number", Some(formatter::BE_HEX)),
+    FieldDef::new(2, 2, "Bytes on Last Page", Some(formatter::LE_16_STRING)),
+    FieldDef::new(4, 2, "Pages in File", Some(formatter::LE_16_STRING)),
+    FieldDef::new(6, 2, "Relocations", Some(formatter::LE_16_STRING)),
+    FieldDef::new(8, 2, "Header Paragraphs", Some(formatter::LE_16_STRING)),
+    FieldDef::ignore(10, 14),
+    FieldDef::new(24, 2, "Relocation Table Offset", Some(formatter::LE_16_HEX)),
+    FieldDef::ignore(26, 34),
+    FieldDef::new(60, 4, "PE Header Offset", Some(formatter::LE_32_PTR))
+        .on_enter(Enter::NtHeaders),
+];
+
+// ------------------------------------------------------------------------
+
+const NT_HEADER_MAP: FieldMap = FieldMap::new(NT_HEADER);
+
+const NT_HEADER: &[FieldDef] = &[
+    FieldDef::new(0, 4, "Signature", Some(formatter::BE_HEX)),
+    FieldDef::new(4, 2, "Machine", Some(formatter::LE_16_HEX))
+        .val_tbl(formatter::LE_16_USIZE, MACHINE),
+    FieldDef::new(6, 2, "Number of Sections", Some(formatter::LE_16_STRING)),
+    FieldDef::new(8, 4, "Timestamp", Some(formatter::LE_32_HEX)),
+    FieldDef::new(12, 4, "Symbol Table Offset", Some(formatter::LE_32_PTR)),
+    FieldDef::new(16, 4, "Number of Symbols", Some(formatter::LE_32_STRING)),
+    FieldDef::new(20, 2, "Optional Header Size", Some(formatter::LE_16_STRING)),
+    FieldDef::new(22, 2, "Characteristics", Some(formatter::BIN_STRING)),
+];
+
+const MACHINE: &formatter::ValTable = &[
+    (0x14c, "x86", None),
+    (0x8664, "64 Bit x86", None),
+    (0x1c0, "ARM", None),
+    (0x1c4, "ARM Thumb-2", None),
+    (0xaa64, "64 Bit ARM", None),
+];
+
+// ------------------------------------------------------------------------
+
+const OPTIONAL_HEADER_MAP_32: FieldMap = FieldMap::new(OPTIONAL_HEADER_32);
+const OPTIONAL_HEADER_MAP_64: FieldMap = FieldMap::new(OPTIONAL_HEADER_64);
+
+const OPTIONAL_HEADER_32: &[FieldDef] = &[
+    FieldDef::new(0, 2, "Optional Header Magic", Some(formatter::LE_16_HEX)),
+    FieldDef::new(2, 1, "Linker Major Version", Some(formatter::LE_8_STRING)),
+    FieldDef::new(3, 1, "Linker Minor Version", Some(formatter::LE_8_STRING)),
+    FieldDef::new(4, 4, "Size of Code", Some(formatter::LE_32_STRING)),
+    FieldDef::new(8, 4, "Size of Initialized Data", Some(formatter::LE_32_STRING)),
+    FieldDef::new(12, 4, "Size of Uninitialized Data", Some(formatter::LE_32_STRING)),
+    FieldDef::new(16, 4, "Entry Point Address", Some(formatter::LE_32_PTR))
+        .on_enter(Enter::EntryPoint),
+    FieldDef::new(20, 4, "Base of Code", Some(formatter::LE_32_PTR)),
+    FieldDef::new(24, 4, "Base of Data", Some(formatter::LE_32_PTR)),
+    FieldDef::new(28, 4, "Image Base", Some(formatter::LE_32_PTR)),
+    FieldDef::new(32, 4, "Section Alignment", Some(formatter::LE_32_HEX)),
+    FieldDef::new(36, 4, "File Alignment", Some(formatter::LE_32_HEX)),
+    FieldDef::ignore(40, 16),
+    FieldDef::new(56, 4, "Size of Image", Some(formatter::LE_32_STRING)),
+    FieldDef::new(60, 4, "Size of Headers", Some(formatter::LE_32_STRING)),
+    FieldDef::new(64, 4, "Checksum", Some(formatter::LE_32_HEX)),
+    FieldDef::new(68, 2, "Subsystem", Some(formatter::LE_16_HEX))
+        .val_tbl(formatter::LE_16_USIZE, SUBSYSTEM),
+    FieldDef::new(70, 2, "DLL Characteristics", Some(formatter::BIN_STRING)),
+    FieldDef::new(72, 4, "Stack Reserve Size", Some(formatter::LE_32_HEX)),
+    FieldDef::new(76, 4, "Stack Commit Size", Some(formatter::LE_32_HEX)),
+    FieldDef::new(80, 4, "Heap Reserve Size", Some(formatter::LE_32_HEX)),
+    FieldDef::new(84, 4, "Heap Commit Size", Some(formatter::LE_32_HEX)),
+    FieldDef::ignore(88, 4),
+    FieldDef::new(92, 4, "Number of Data Directories", Some(formatter::LE_32_STRING)),
+];
+
+const OPTIONAL_HEADER_64: &[FieldDef] = &[
+    FieldDef::new(0, 2, "Optional Header Magic", Some(formatter::LE_16_HEX)),
+    FieldDef::new(2, 1, "Linker Major Version", Some(formatter::LE_8_STRING)),
+    FieldDef::new(3, 1, "Linker Minor Version", Some(formatter::LE_8_STRING)),
+    FieldDef::new(4, 4, "Size of Code", Some(formatter::LE_32_STRING)),
+    FieldDef::new(8, 4, "Size of Initialized Data", Some(formatter::LE_32_STRING)),
+    FieldDef::new(12, 4, "Size of Uninitialized Data", Some(formatter::LE_32_STRING)),
+    FieldDef::new(16, 4, "Entry Point Address", Some(formatter::LE_32_PTR))
+        .on_enter(Enter::EntryPoint),
+    FieldDef::new(20, 4, "Base of Code", Some(formatter::LE_32_PTR)),
+    FieldDef::new(24, 8, "Image Base", Some(formatter::LE_64_PTR)),
+    FieldDef::new(32, 4, "Section Alignment", Some(formatter::LE_32_HEX)),
+    FieldDef::new(36, 4, "File Alignment", Some(formatter::LE_32_HEX)),
+    FieldDef::ignore(40, 16),
+    FieldDef::new(56, 4, "Size of Image", Some(formatter::LE_32_STRING)),
+    FieldDef::new(60, 4, "Size of Headers", Some(formatter::LE_32_STRING)),
+    FieldDef::new(64, 4, "Checksum", Some(formatter::LE_32_HEX)),
+    FieldDef::new(68, 2, "Subsystem", Some(formatter::LE_16_HEX))
+        .val_tbl(formatter::LE_16_USIZE, SUBSYSTEM),
+    FieldDef::new(70, 2, "DLL Characteristics", Some(formatter::BIN_STRING)),
+    FieldDef::new(72, 8, "Stack Reserve Size", Some(formatter::LE_64_PTR)),
+    FieldDef::new(80, 8, "Stack Commit Size", Some(formatter::LE_64_PTR)),
+    FieldDef::new(88, 8, "Heap Reserve Size", Some(formatter::LE_64_PTR)),
+    FieldDef::new(96, 8, "Heap Commit Size", Some(formatter::LE_64_PTR)),
+    FieldDef::ignore(104, 4),
+    FieldDef::new(108, 4, "Number of Data Directories", Some(formatter::LE_32_STRING)),
+];
+
+const SUBSYSTEM: &formatter::ValTable = &[
+    (1, "Native", None),
+    (2, "Windows GUI", None),
+    (3, "Windows Console", None),
+    (10, "EFI Application", None),
+];
@@ -0,0 +1,132 @@
+//!
+//! Expand directory arguments to the files under them
+//!
+//! Directories are walked recursively in name order, the files of a
+//! directory before its subdirectories. The include and exclude patterns
+//! are matched against the path relative to the directory walked, with `*`
+//! matching across `/` so that `*.so` matches at any depth. Excluded
+//! directories are not walked at all. Files named on the command line are
+//! always kept. Symbolic links to files are kept as the files they point
+//! to, links to directories are only walked when following links.
+//!
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::exe_types::{self, ExeVec};
+
+// ------------------------------------------------------------------------
+
+/// Which files of the directories to keep
+#[derive(Debug, Default, Clone)]
+pub struct Filters {
+    /// Keep only the files matching one of these, all files if empty
+    pub include: Vec<Pattern>,
+    /// Leave out the files and directories matching one of these
+    pub exclude: Vec<Pattern>,
+    /// Walk into symbolic links to directories
+    pub follow_symlinks: bool,
+}
+
+impl Filters {
+    /// Filters from the patterns given on the command line
+    pub fn new(include: &[String], exclude: &[String], follow_symlinks: bool) -> Result<Self> {
+        let patterns = |globs: &[String]| -> Result<Vec<Pattern>> {
+            globs
+                .iter()
+                .map(|glob| Pattern::new(glob).with_context(|| format!("Invalid pattern \"{}\"", glob)))
+                .collect()
+        };
+        Ok(Self { include: patterns(include)?, exclude: patterns(exclude)?, follow_symlinks })
+    }
+
+    fn includes(&self, relative: &Path) -> bool {
+        self.include.is_empty() || self.include.iter().any(|p| p.matches_path(relative))
+    }
+
+    fn excludes(&self, relative: &Path) -> bool {
+        self.exclude.iter().any(|p| p.matches_path(relative))
+    }
+}
+
+/// The files named and those under the directories named, in order
+pub fn files(paths: &[PathBuf], filters: &Filters) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .flat_map(|path| match path.is_dir() {
+            true => walk(path, filters),
+            false => Vec::from([path.clone()]),
+        })
+        .collect()
+}
+
+/// Open the files on all cores, keeping their order
+pub fn open<'e>(filenames: &[PathBuf]) -> ExeVec<'e> {
+    filenames.par_iter().map(exe_types::new).collect()
+}
+
+/// Files under the directory, skipping entries that cannot be read
+fn walk(dir: &Path, filters: &Filters) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .follow_links(filters.follow_symlinks)
+        .sort_by(|a, b| (is_dir(a), a.file_name()).cmp(&(is_dir(b), b.file_name())))
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            entry.depth() == 0 || !filters.excludes(relative)
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() || (entry.path_is_symlink() && entry.path().is_file()))
+        .filter(|entry| filters.includes(entry.path().strip_prefix(dir).unwrap_or(entry.path())))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Links to directories are sorted with the directories, whether or not
+/// they are walked
+fn is_dir(entry: &DirEntry) -> bool {
+    entry.file_type().is_dir() || (entry.path_is_symlink() && entry.path().is_dir())
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn walk_with_filters() {
+        let root = std::env::temp_dir().join(format!("ncexe-scan-{}", std::process::id()));
+        for file in ["a.so", "b.txt", "lib/c.so", "lib/d.so.1", "skip/e.so"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+        let relative = |filters: &Filters| -> Vec<PathBuf> {
+            files(std::slice::from_ref(&root), filters).iter().map(|f| f.strip_prefix(&root).unwrap().to_path_buf()).collect()
+        };
+
+        let all = ["a.so", "b.txt", "lib/c.so", "lib/d.so.1", "skip/e.so"].map(PathBuf::from);
+        assert_eq!(relative(&Filters::default()), all);
+
+        let filters = Filters::new(&["*.so*".into()], &["skip".into()], false).unwrap();
+        assert_eq!(relative(&filters), ["a.so", "lib/c.so", "lib/d.so.1"].map(PathBuf::from));
+
+        // Links to files are kept, links to directories only walked with -L
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("a.so", root.join("f.so")).unwrap();
+            std::os::unix::fs::symlink("lib", root.join("link")).unwrap();
+            let filters = Filters::new(&["*.so".into()], &[], false).unwrap();
+            assert_eq!(relative(&filters), ["a.so", "f.so", "lib/c.so", "skip/e.so"].map(PathBuf::from));
+            let filters = Filters::new(&["*.so".into()], &[], true).unwrap();
+            assert_eq!(relative(&filters), ["a.so", "f.so", "lib/c.so", "link/c.so", "skip/e.so"].map(PathBuf::from));
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
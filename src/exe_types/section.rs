@@ -0,0 +1,53 @@
+//!
+//! Format independent description of a section
+//!
+
+// ------------------------------------------------------------------------
+
+/// Section of an executable image
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Section {
+    /// Owning segment, only used by Mach-O
+    pub segment: String,
+    pub name: String,
+    pub addr: u64,
+    pub size: u64,
+    pub offset: u64,
+    /// Bytes of the section present in the file, zero for bss style sections
+    pub file_size: u64,
+    /// ELF section type or Mach-O section flags
+    pub kind: u32,
+    pub flags: u64,
+    pub link: u32,
+    pub info: u32,
+    pub entsize: u64,
+}
+
+impl Section {
+
+    /// Name qualified by the segment name, e.g. `__TEXT,__text`
+    pub fn full_name(&self) -> String {
+        if self.segment.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{},{}", self.segment, self.name)
+        }
+    }
+
+    /// The section contents, `None` if not present or out of bounds
+    pub fn data<'d>(&self, mmap: &'d [u8]) -> Option<&'d [u8]> {
+        let start = usize::try_from(self.offset).ok()?;
+        let end = start.checked_add(usize::try_from(self.file_size).ok()?)?;
+        if self.file_size == 0 {
+            None
+        } else {
+            mmap.get(start..end)
+        }
+    }
+
+    /// Does the virtual address fall in the section?
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.addr && addr - self.addr < self.size
+    }
+
+}
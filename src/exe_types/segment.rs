@@ -0,0 +1,63 @@
+//!
+//! Format independent description of a segment
+//!
+
+use super::Mapping;
+
+// ------------------------------------------------------------------------
+
+/// Segment of an executable image, an ELF program header or a Mach-O
+/// `LC_SEGMENT_64` command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Segment {
+    /// ELF segment type such as `LOAD`, or the Mach-O segment name
+    pub name: String,
+    /// ELF `p_type` or the Mach-O load command
+    pub kind: u32,
+    /// Access as the ELF `PF_R`, `PF_W` and `PF_X` bits, the Mach-O
+    /// initial protection is converted to them
+    pub flags: u32,
+    pub addr: u64,
+    /// Bytes of address space
+    pub size: u64,
+    pub offset: u64,
+    /// Bytes taken from the file, the rest is zero filled
+    pub file_size: u64,
+}
+
+impl Segment {
+    pub const EXECUTE: u32 = 0x1;
+    pub const WRITE: u32 = 0x2;
+    pub const READ: u32 = 0x4;
+
+    /// Access in the `R-X` style of `readelf`
+    pub fn permissions(&self) -> String {
+        [(Self::READ, 'R'), (Self::WRITE, 'W'), (Self::EXECUTE, 'X')]
+            .iter()
+            .map(|&(bit, ch)| if self.flags & bit != 0 { ch } else { '-' })
+            .collect()
+    }
+
+    /// The segment contents, `None` if not present or out of bounds
+    pub fn data<'d>(&self, mmap: &'d [u8]) -> Option<&'d [u8]> {
+        let start = usize::try_from(self.offset).ok()?;
+        let end = start.checked_add(usize::try_from(self.file_size).ok()?)?;
+        mmap.get(start..end)
+    }
+
+    /// Does the virtual address fall in the segment?
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.addr && addr - self.addr < self.size
+    }
+
+    /// The segment as part of the address space
+    pub fn mapping(&self) -> Mapping {
+        Mapping {
+            name: self.name.clone(),
+            addr: self.addr,
+            size: self.size,
+            offset: self.offset,
+            file_size: self.file_size,
+        }
+    }
+}
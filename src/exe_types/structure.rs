@@ -0,0 +1,53 @@
+//!
+//! Structures of an image decoded with a field map
+//!
+
+use crate::formatter::FieldMap;
+
+// ------------------------------------------------------------------------
+
+/// A structure decoded with a field map, with the entries of the table it
+/// describes
+#[derive(Clone)]
+pub struct StructureMap<'m> {
+    pub name: &'m str,
+    /// File offsets of the block, as passed to `details::to_lines`
+    pub data: (usize, usize),
+    pub map: &'m FieldMap<'m>,
+    /// Structures the structure holds or points at, such as the symbols
+    /// of an ELF symbol table section
+    pub children: Vec<StructureMap<'m>>,
+}
+
+impl<'m> StructureMap<'m> {
+    pub fn new(name: &'m str, data: (usize, usize), map: &'m FieldMap<'m>) -> Self {
+        Self { name, data, map, children: Vec::new() }
+    }
+
+    /// Bytes of the block in `file`, short if the file is cut off
+    pub fn block<'f>(&self, file: &'f [u8]) -> &'f [u8] {
+        file.get(self.data.0..self.data.1.min(file.len())).unwrap_or_default()
+    }
+
+    /// Structures over the same block that the fields expand to through
+    /// their tables of values, followed by the children
+    pub fn expand(&self, file: &[u8]) -> Vec<StructureMap<'m>> {
+        self.map
+            .expansions(self.block(file))
+            .into_iter()
+            .map(|(name, map)| StructureMap::new(name, self.data, map))
+            .chain(self.children.iter().cloned())
+            .collect()
+    }
+}
+
+/// The structures and everything they expand to, each before the ones it
+/// holds
+pub fn flatten<'m>(structures: &[StructureMap<'m>], file: &[u8]) -> Vec<StructureMap<'m>> {
+    let mut flat = Vec::new();
+    for structure in structures {
+        flat.push(StructureMap { children: Vec::new(), ..structure.clone() });
+        flat.extend(flatten(&structure.expand(file), file));
+    }
+    flat
+}
@@ -0,0 +1,115 @@
+//!
+//! Format independent description of a symbol table entry
+//!
+
+// ------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolKind {
+    Function,
+    Object,
+    #[default]
+    Other,
+}
+
+/// Entry of a symbol table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub addr: u64,
+    /// Size in bytes, zero when unknown
+    pub size: u64,
+    pub kind: SymbolKind,
+    pub global: bool,
+    /// Defined in this image rather than imported
+    pub defined: bool,
+    /// From the dynamic symbol table
+    pub dynamic: bool,
+    /// Index in the symbol table it came from
+    pub index: usize,
+}
+
+impl Symbol {
+
+    /// Does the virtual address fall in the symbol?
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.addr && addr - self.addr < self.size
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+/// The functions and objects of a symbol table sorted by address, to look
+/// up many addresses in
+pub struct SymbolMap<'s> {
+    /// Named and defined functions and objects, by address
+    sorted: Vec<&'s Symbol>,
+    /// Functions of unknown size, by address
+    unsized_fns: Vec<&'s Symbol>,
+    /// Size of the largest symbol, no symbol further back holds an address
+    max_size: u64,
+}
+
+impl<'s> SymbolMap<'s> {
+
+    pub fn new(symbols: &'s [Symbol]) -> Self {
+        let mut sorted: Vec<&Symbol> = symbols
+            .iter()
+            .filter(|s| s.defined && s.kind != SymbolKind::Other && !s.name.is_empty())
+            .collect();
+        sorted.sort_by_key(|s| s.addr);
+        let unsized_fns = sorted.iter().copied().filter(|s| s.size == 0 && s.kind == SymbolKind::Function).collect();
+        let max_size = sorted.iter().map(|s| s.size).max().unwrap_or(0);
+        Self { sorted, unsized_fns, max_size }
+    }
+
+    /// Find the function or object containing `addr`, the one starting
+    /// closest to it if several do, falling back to the closest preceding
+    /// function when sizes are unknown. Returns the symbol and the offset
+    /// of `addr` into it.
+    pub fn lookup(&self, addr: u64) -> Option<(&'s Symbol, u64)> {
+        let end = self.sorted.partition_point(|s| s.addr <= addr);
+        self.sorted[..end]
+            .iter()
+            .rev()
+            .take_while(|s| addr - s.addr < self.max_size)
+            .find(|s| s.contains(addr))
+            .or_else(|| self.unsized_fns[..self.unsized_fns.partition_point(|s| s.addr <= addr)].last())
+            .map(|s| (*s, addr - s.addr))
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, addr: u64, size: u64, kind: SymbolKind) -> Symbol {
+        Symbol { name: name.into(), addr, size, kind, defined: true, ..Default::default() }
+    }
+
+    #[test]
+    fn lookup() {
+        let symbols = [
+            symbol("big", 0x1000, 0x1000, SymbolKind::Object),
+            symbol("main", 0x1100, 0x10, SymbolKind::Function),
+            symbol("start", 0x3000, 0, SymbolKind::Function),
+            symbol("section", 0x3100, 0x100, SymbolKind::Other),
+            symbol("after", 0x4000, 0x8, SymbolKind::Function),
+        ];
+        let map = SymbolMap::new(&symbols);
+        let found = |addr| map.lookup(addr).map(|(s, offset)| (s.name.as_str(), offset));
+
+        assert_eq!(found(0xfff), None);
+        assert_eq!(found(0x1000), Some(("big", 0)));
+        assert_eq!(found(0x1104), Some(("main", 4)));
+        assert_eq!(found(0x1110), Some(("big", 0x110)));
+        assert_eq!(found(0x2000), None);
+        assert_eq!(found(0x3180), Some(("start", 0x180)));
+        assert_eq!(found(0x4004), Some(("after", 4)));
+        assert_eq!(found(0x4008), Some(("start", 0x1008)));
+    }
+}
@@ -9,8 +9,6 @@ use anyhow::{
 };
 use std::ffi::CStr;
 
-use crate::exe_types::ExeRef;
-
 // ------------------------------------------------------------------------
 /// Header for a memory block mapping
 pub struct FieldMap<'fd> {
@@ -40,15 +38,49 @@ impl<'fd> FieldMap<'fd> {
         Self{fields, data_len, max_text_len}
 
     }
+
+    /// Named field holding the byte at `offset` in the block
+    pub fn field_at(&self, offset: usize, block_len: usize) -> Option<&FieldDef<'fd>> {
+        self.fields
+            .iter()
+            .filter(|f| !f.name.is_empty())
+            .find(|f| {
+                let (start, end) = f.block_range(block_len);
+                (start..end).contains(&offset)
+            })
+    }
+
+    /// Name and map of the structures the fields of `block` expand to
+    /// through their tables of values, as a load command expands to the
+    /// fields of its command
+    pub fn expansions(&self, block: &[u8]) -> Vec<(&'fd str, &'fd FieldMap<'fd>)> {
+        self.fields
+            .iter()
+            .filter_map(|field| match field.lookup(block)? {
+                (_, name, Some(map)) => Some((*name, map)),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 // ------------------------------------------------------------------------
 /// Field definition
-
-type StringFn = dyn Fn(&[u8]) -> String;
-type StringFn2 = dyn Fn(&[u8]) -> Result<String>;
-type UsizeFn = dyn Fn(&[u8]) -> usize;
-type EnterFn = fn(ExeRef) -> Result<()>;
+type StringFn = dyn Fn(&[u8]) -> String + Sync;
+type StringFn2 = dyn Fn(&[u8]) -> Result<String> + Sync;
+type UsizeFn = dyn Fn(&[u8]) -> Option<usize> + Sync;
+
+/// Screen opened by pressing enter on a field, `screens::enter_field`
+/// opens it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enter {
+    /// Disassembly from the entry point
+    EntryPoint,
+    /// List of the Mach-O load commands
+    LoadCommands,
+    /// The PE NT and optional headers
+    NtHeaders,
+}
 
 /// Entry in the table of values for a field
 pub type ValEntry<'v> = (
@@ -70,7 +102,7 @@ pub struct FieldDef<'fd> {
     pub string_fn2: Option<&'fd StringFn2>,
     pub usize_fn: Option<&'fd UsizeFn>,
     pub val_tbl: Option<&'fd ValTable<'fd>>,
-    pub enter_fn: Option<EnterFn>,
+    pub enter: Option<Enter>,
 }
 
 impl<'fd> FieldDef<'fd> {
@@ -88,7 +120,7 @@ impl<'fd> FieldDef<'fd> {
             string_fn2: None,
             usize_fn: None,
             val_tbl: None,
-            enter_fn: None,
+            enter: None,
             
         }
     }
@@ -106,7 +138,7 @@ impl<'fd> FieldDef<'fd> {
             string_fn2,
             usize_fn: None,
             val_tbl: None,
-            enter_fn: None,
+            enter: None,
             
         }
     }
@@ -122,7 +154,7 @@ impl<'fd> FieldDef<'fd> {
             string_fn2: None,
             usize_fn: None,
             val_tbl: None,
-            enter_fn: None,
+            enter: None,
         }
     }
 
@@ -144,86 +176,99 @@ impl<'fd> FieldDef<'fd> {
         self
     }
 
-    pub const fn enter_fn(
+    pub const fn on_enter(
         mut self, 
-        enter: EnterFn,
+        enter: Enter,
     ) -> Self {
-        self.enter_fn = Some(enter);
+        self.enter = Some(enter);
         self
     }
 
-    pub fn to_usize(&self, data: &[u8]) -> usize {
-
-        // Yes this could fail but panic is actualy an appropriate response
-        (self.usize_fn.unwrap())(&data[self.range.0..self.range.1])
+    /// Range of the field in a block of `block_len` bytes, an empty range
+    /// runs to the end of the block
+    pub fn block_range(&self, block_len: usize) -> (usize, usize) {
+        if self.range.1 > self.range.0 {
+            self.range
+        } else {
+            (self.range.0, block_len.max(self.range.0))
+        }
     }
 
-    pub fn to_string(&self, data: &[u8]) -> Result<String> {
-
-        Ok(match self.to_string_wrapped(data) {
-            Err(_err) => {
-                // Cannot show blocking popup from within ratatui draw(); return error indicator
-                "???".to_string()
-            },
-            Ok(s) => s
-        })
-
+    /// Bytes of the field in `data`, an error if they run past its end
+    pub fn bytes<'d>(&self, data: &'d [u8]) -> Result<&'d [u8]> {
+        let (start, end) = self.block_range(data.len());
+        data.get(start..end)
+            .ok_or_else(|| anyhow!("{} at {:#x} is past the end of {} bytes", self.name, start, data.len()))
     }
 
-    fn to_string_wrapped(&self, data: &[u8]) -> Result<String> {
+    /// The field as a number, an error if it is out of bounds or has no
+    /// numeric value
+    pub fn to_usize(&self, data: &[u8]) -> Result<usize> {
+        let usize_fn = self.usize_fn.ok_or_else(|| anyhow!("{} has no numeric value", self.name))?;
+        usize_fn(self.bytes(data)?).ok_or_else(|| anyhow!("{} is malformed", self.name))
+    }
 
-        let data_str = if self.range.1 > self.range.0 {
-                &data[self.range.0..self.range.1]
-            } else {
-                &data[self.range.0..]
-            };
+    /// The field as text, with a marker in place of the value when it is
+    /// out of bounds or cannot be converted
+    pub fn to_string(&self, data: &[u8]) -> String {
+        match self.bytes(data) {
+            Err(_) => String::from(OUT_OF_BOUNDS),
+            Ok(bytes) => self.format(bytes).unwrap_or_else(|_| String::from(MALFORMED)),
+        }
+    }
 
+    fn format(&self, bytes: &[u8]) -> Result<String> {
         if let Some(stringfn) = self.string_fn {
-            Ok(stringfn(data_str))
+            Ok(stringfn(bytes))
         } else if let Some(string_fn) = self.string_fn2 {
-            string_fn(data_str)
+            string_fn(bytes)
                 .with_context(|| format!("Unable to generate string for field: {}", 
                                             self.name))
         } else {
-            Err(anyhow!("Should never get here"))
+            Err(anyhow!("No conversion for field: {}", self.name))
         }
-
     }
 
+    /// Entry of the value table for the value of the field
     pub fn lookup(
         &self,
         d: &[u8], 
-    ) -> Option<&ValEntry> {
-
-        if let Some(vt) = self.val_tbl {
-
-            let ufn = self.usize_fn.unwrap();
-            let uv = ufn(&d[self.range.0..self.range.1]);
-
-            vt.iter().find(| v | v.0 == uv )
-
-        } else {
-            None
-        }
-
+    ) -> Option<&'fd ValEntry<'fd>> {
+        let uv = self.to_usize(d).ok()?;
+        self.val_tbl?.iter().find(| v | v.0 == uv )
     }
 
 }
 
+/// Shown in place of a field that runs past the end of the file
+pub const OUT_OF_BOUNDS: &str = "<out of bounds>";
+
+/// Shown in place of a field whose bytes do not convert
+pub const MALFORMED: &str = "<malformed>";
+
 // ------------------------------------------------------------------------
 
 pub fn center_in(width: usize, s: &str) -> (i32, String) {
 
-    let excess = i32::try_from(width).unwrap() - i32::try_from(s.len()).unwrap();
+    let len = s.chars().count();
 
-    if excess <= 0 {
-        (0, String::from(&s[0..width]))
+    if len >= width {
+        (0, s.chars().take(width).collect())
     } else {
-        (excess / 2, String::from(s))
+        (i32::try_from((width - len) / 2).unwrap_or(i32::MAX), String::from(s))
     }
 
 }
 
+// ------------------------------------------------------------------------
+/// Parse an address typed by the user, hexadecimal with or without `0x`
+pub fn parse_address(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    u64::from_str_radix(&digits.replace('_', ""), 16)
+        .map_err(|err| anyhow!("Invalid address '{}': {}", s, err))
+}
+
 // ------------------------------------------------------------------------
 
 trait Converters {
@@ -246,66 +291,71 @@ impl Converters for &[u8] {
             .join(" ") 
     }
     fn to_string(&self) -> String {
-        let mut data: Vec<u8> = self.to_vec();
-        data.append(&mut Vec::from([0]));
-        CStr::from_bytes_until_nul(&data).unwrap().to_str().unwrap().into()
+        let len = self.iter().position(|b| *b == 0).unwrap_or(self.len());
+        String::from_utf8_lossy(&self[..len]).into()
     }
 }
 
-// ------------------------------------------------------------------------
-/// Formatting closures
+/// Bytes of a fixed size field, `None` if the slice is another length
+fn array<const N: usize>(d: &[u8]) -> Option<[u8; N]> {
+    d.try_into().ok()
+}
 
-pub const BE_8_STRING:  &StringFn = &|d: &[u8]| u8::from_be_bytes(d.try_into().unwrap()).to_string();
-pub const BE_16_STRING: &StringFn = &|d: &[u8]| u16::from_be_bytes(d.try_into().unwrap()).to_string();
-pub const BE_32_STRING: &StringFn = &|d: &[u8]| u32::from_be_bytes(d.try_into().unwrap()).to_string();
-pub const BE_64_STRING: &StringFn = &|d: &[u8]| u64::from_be_bytes(d.try_into().unwrap()).to_string();
+fn malformed() -> String {
+    String::from(MALFORMED)
+}
 
-pub const BE_8_USIZE:   &UsizeFn = &|d: &[u8]| u8::from_be_bytes(d.try_into().unwrap()).into();
-pub const BE_16_USIZE:  &UsizeFn = &|d: &[u8]| u16::from_be_bytes(d.try_into().unwrap()).into();
-pub const BE_32_USIZE:  &UsizeFn = &|d: &[u8]| u32::from_be_bytes(d.try_into().unwrap())
-        .try_into().unwrap();
-pub const BE_64_USIZE:  &UsizeFn = &|d: &[u8]| u64::from_be_bytes(d.try_into().unwrap())
-        .try_into().unwrap();
+// ------------------------------------------------------------------------
+/// Formatting closures
+pub const BE_8_STRING:  &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u8::from_be_bytes(a).to_string());
+pub const BE_16_STRING: &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u16::from_be_bytes(a).to_string());
+pub const BE_32_STRING: &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u32::from_be_bytes(a).to_string());
+pub const BE_64_STRING: &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u64::from_be_bytes(a).to_string());
+
+pub const BE_8_USIZE:   &UsizeFn = &|d: &[u8]| array(d).map(|a| u8::from_be_bytes(a).into());
+pub const BE_16_USIZE:  &UsizeFn = &|d: &[u8]| array(d).map(|a| u16::from_be_bytes(a).into());
+pub const BE_32_USIZE:  &UsizeFn = &|d: &[u8]| array(d)
+    .and_then(|a| u32::from_be_bytes(a).try_into().ok());
+pub const BE_64_USIZE:  &UsizeFn = &|d: &[u8]| array(d)
+    .and_then(|a| u64::from_be_bytes(a).try_into().ok());
 
 pub const BE_HEX:       &StringFn = &|d: &[u8]| d.to_hex();
 pub const BE_CHAR:      &StringFn = &|d: &[u8]| d.to_string();
 
-pub const BE_32_PTR:    &StringFn = &|d: &[u8]| format!("{:010p}", 
-    u32::from_be_bytes(d.try_into().unwrap()) as *const u32);
-pub const BE_64_PTR:    &StringFn = &|d: &[u8]| format!("{:018p}", 
-    u64::from_be_bytes(d.try_into().unwrap()) as *const u64);
+pub const BE_32_PTR:    &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| format!("{:010p}", 
+    u32::from_be_bytes(a) as *const u32));
+pub const BE_64_PTR:    &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| format!("{:018p}", 
+    u64::from_be_bytes(a) as *const u64));
 
-pub const LE_8_STRING:  &StringFn = &|d: &[u8]| u8::from_le_bytes(d.try_into().unwrap()).to_string();
-pub const LE_16_STRING: &StringFn = &|d: &[u8]| u16::from_le_bytes(d.try_into().unwrap()).to_string();
-pub const LE_32_STRING: &StringFn = &|d: &[u8]| u32::from_le_bytes(d.try_into().unwrap()).to_string();
-pub const LE_64_STRING: &StringFn = &|d: &[u8]| u64::from_le_bytes(d.try_into().unwrap()).to_string();
+pub const LE_8_STRING:  &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u8::from_le_bytes(a).to_string());
+pub const LE_16_STRING: &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u16::from_le_bytes(a).to_string());
+pub const LE_32_STRING: &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u32::from_le_bytes(a).to_string());
+pub const LE_64_STRING: &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u64::from_le_bytes(a).to_string());
 
 pub const LE_8_HEX:     &StringFn = &|d: &[u8]| d.to_hex();
-pub const LE_16_HEX:    &StringFn = &|d: &[u8]| u16::from_le_bytes(d.try_into().unwrap())
+pub const LE_16_HEX:    &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u16::from_le_bytes(a)
     .to_be_bytes()
     .as_slice()
-    .to_hex();
-pub const LE_32_HEX:    &StringFn = &|d: &[u8]| u32::from_le_bytes(d.try_into().unwrap())
+    .to_hex());
+pub const LE_32_HEX:    &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| u32::from_le_bytes(a)
     .to_be_bytes()
     .as_slice()
-    .to_hex();
+    .to_hex());
 
-pub const LE_32_PTR:    &StringFn = &|d: &[u8]| format!("{:010p}", 
-    u32::from_le_bytes(d.try_into().unwrap()) as *const u32);
-pub const LE_64_PTR:    &StringFn = &|d: &[u8]| format!("{:018p}", 
-    u64::from_le_bytes(d.try_into().unwrap()) as *const u64);
+pub const LE_32_PTR:    &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| format!("{:010p}", 
+    u32::from_le_bytes(a) as *const u32));
+pub const LE_64_PTR:    &StringFn = &|d: &[u8]| array(d).map_or_else(malformed, |a| format!("{:018p}", 
+    u64::from_le_bytes(a) as *const u64));
 pub const BIN_STRING:   &StringFn = &|d: &[u8]| d.to_bits();
     
-pub const LE_8_USIZE:   &UsizeFn = &|d: &[u8]| u8::from_le_bytes(d.try_into().unwrap())
-    .into();
-pub const LE_16_USIZE:  &UsizeFn = &|d: &[u8]| u16::from_le_bytes(d.try_into().unwrap())
-    .into();
-pub const LE_32_USIZE:  &UsizeFn = &|d: &[u8]| u32::from_le_bytes(d.try_into().unwrap())
-    .try_into()
-    .unwrap();
-pub const LE_64_USIZE:  &UsizeFn = &|d: &[u8]| u64::from_le_bytes(d.try_into().unwrap())
-    .try_into()
-    .unwrap();
+pub const LE_8_USIZE:   &UsizeFn = &|d: &[u8]| array(d).map(|a| u8::from_le_bytes(a)
+    .into());
+pub const LE_16_USIZE:  &UsizeFn = &|d: &[u8]| array(d).map(|a| u16::from_le_bytes(a)
+    .into());
+pub const LE_32_USIZE:  &UsizeFn = &|d: &[u8]| array(d)
+    .and_then(|a| u32::from_le_bytes(a).try_into().ok());
+pub const LE_64_USIZE:  &UsizeFn = &|d: &[u8]| array(d)
+    .and_then(|a| u64::from_le_bytes(a).try_into().ok());
 
 pub const C_STR:        &StringFn2 = &|d: &[u8]| Ok(CStr::from_bytes_until_nul(d)?
     .to_str()?
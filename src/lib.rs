@@ -1,5 +1,9 @@
+#[cfg(feature = "tui")]
 pub mod color;
+pub mod decoders;
 pub mod exe_types;
 pub mod formatter;
+#[cfg(feature = "tui")]
 pub mod screens;
-pub mod windows;
\ No newline at end of file
+#[cfg(feature = "tui")]
+pub mod windows;
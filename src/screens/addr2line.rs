@@ -0,0 +1,79 @@
+//!
+//! Prompt for a virtual address and show its function and source position
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::Colors,
+    decoders::addr2line::{self, Frame},
+    exe_types::ExeRef,
+    formatter,
+    screens::{details_list, dump},
+    windows::{
+        line::LineVec,
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const NAME_LEN: usize = "Inlined By".len();
+
+pub fn is_present(exe: ExeRef) -> bool {
+    addr2line::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let address = match dump::active() {
+        // Nothing can be typed in a dump, so show where the program starts
+        true => exe.entry_point().map(Ok),
+        false => popup::prompt("Address Lookup", "Address").map(|input| formatter::parse_address(&input)),
+    };
+    let Some(address) = address else {
+        return Ok(());
+    };
+    let location = match address.and_then(|address| addr2line::lookup(exe, address)) {
+        Ok(location) => location,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let mut lines: LineVec = Vec::from([
+        TextLine::field("Address", NAME_LEN, &format!("{:#x}", location.address), wc).boxed(),
+        TextLine::field("Symbol", NAME_LEN, &match &location.symbol {
+            Some((name, 0)) => name.clone(),
+            Some((name, offset)) => format!("{}+{:#x}", name, offset),
+            None => "Not found".into(),
+        }, wc).boxed(),
+    ]);
+
+    if location.frames.is_empty() {
+        lines.push(TextLine::field("Source", NAME_LEN, "No debug information", wc).boxed());
+    }
+    for (idx, frame) in location.frames.iter().enumerate() {
+        let name = if idx == 0 { "Function" } else { "Inlined By" };
+        lines.push(TextLine::field(name, NAME_LEN, frame.function.as_deref().unwrap_or("??"), wc).boxed());
+        lines.push(TextLine::field("", NAME_LEN, &source(frame), wc).boxed());
+    }
+
+    let footer = format!("{} frames, press q to return", location.frames.len());
+
+    details_list::show(exe, lines, &format!("Address {:#x}", location.address), &footer, wsc)
+}
+
+/// `file:line:column` in the form used by compilers
+fn source(frame: &Frame) -> String {
+    let file = frame.file.as_deref().unwrap_or("??");
+    match (frame.line, frame.column) {
+        (0, _) => format!("{}:?", file),
+        (line, 0) => format!("{}:{}", file, line),
+        (line, column) => format!("{}:{}:{}", file, line, column),
+    }
+}
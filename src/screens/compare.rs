@@ -0,0 +1,186 @@
+//!
+//! Show two executables side by side, entry by entry
+//!
+//! Each group starts collapsed to a summary of its changes, those with
+//! changes are expanded to the entries that differ. The entries that are
+//! the same in both files are under a line of their own.
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::compare::{self, Change, Group, Row},
+    exe_types::ExeRef,
+    formatter::center_in,
+    screens::{self, history},
+    windows::{
+        footer::Footer,
+        header::Header,
+        line::{ActionType, Line, LineItem, LineVec, PairVec},
+        scrollable_region::ScrollableRegion,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+/// Longest key shown in full, longer ones are cut short
+const MAX_KEY_LEN: usize = 32;
+
+/// Columns between the two sides, holding the change marker
+const SEPARATOR_LEN: usize = 3;
+
+pub fn show(old: ExeRef, new: ExeRef) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("compare")?;
+    let wc = wsc.scrollable_region;
+
+    let groups = compare::compare(old, new);
+
+    let lines: LineVec = groups
+        .iter()
+        .map(|group| {
+            let summary = format!(
+                "{}: {} entries, {} changed, {} added, {} removed",
+                group.name,
+                group.rows.len(),
+                group.count(Change::Changed),
+                group.count(Change::Added),
+                group.count(Change::Removed),
+            );
+            TextLine::text(&summary, wc)
+                .action(ActionType::Expandable(Box::new(move || group_lines(group, wc)), 0, 0))
+                .boxed()
+        })
+        .collect();
+
+    // Create header window
+
+    let names = (old.filename().display().to_string(), new.filename().display().to_string());
+    let hdr_fn = move |sc: usize| {
+        let half = sc.saturating_sub(2 + SEPARATOR_LEN) / 2;
+        (0, format!(" {:half$}{:sl$}{}", last_chars(&names.0, half), "", names.1, sl = SEPARATOR_LEN))
+    };
+    let mut hdr_win = Header::new(&wsc.header, Box::new(hdr_fn));
+
+    // Create the scrollable window, with the groups that changed expanded
+
+    let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+    for (idx, group) in groups.iter().enumerate().rev() {
+        if group.count(Change::Same) < group.rows.len() {
+            scr_win.select(idx);
+            scr_win.key_enter_handler()?;
+        }
+    }
+    scr_win.select(0);
+
+    // Create the footer window
+
+    let count = |change| groups.iter().map(|group| group.count(change)).sum::<usize>();
+    let footer = format!(
+        "{} changed, {} added, {} removed",
+        count(Change::Changed),
+        count(Change::Added),
+        count(Change::Removed),
+    );
+    let mut ftr_win = Footer::new(&wsc.footer, Box::new(move |sc: usize| center_in(sc, &footer)));
+
+    // Create and show the set of windows
+
+    history::name("Compare");
+    screens::show(None, &mut hdr_win, &mut scr_win, &mut ftr_win)
+}
+
+/// Lines of the entries of a group that differ, followed by a line
+/// expanding to those that do not
+fn group_lines(group: &Group, wc: WindowColors) -> LineVec<'_> {
+    let key_len = group.rows.iter().map(|row| row.key.len()).max().unwrap_or(0).min(MAX_KEY_LEN);
+    let mut lines: LineVec = group
+        .rows
+        .iter()
+        .filter(|row| row.change() != Change::Same)
+        .map(|row| DiffLine::boxed(row, key_len, wc))
+        .collect();
+
+    let same = group.count(Change::Same);
+    if same > 0 {
+        let unchanged = move || -> LineVec {
+            group
+                .rows
+                .iter()
+                .filter(|row| row.change() == Change::Same)
+                .map(|row| DiffLine::boxed(row, key_len, wc))
+                .collect()
+        };
+        lines.push(
+            TextLine::text(&format!("Unchanged, {} entries", same), wc)
+                .action(ActionType::Expandable(Box::new(unchanged), 0, 0))
+                .boxed(),
+        );
+    }
+    lines
+}
+
+/// The end of `text` that fits in `width` characters
+fn last_chars(text: &str, width: usize) -> &str {
+    match text.char_indices().rev().nth(width.saturating_sub(1)) {
+        Some((start, _)) if width > 0 => &text[start..],
+        _ => text,
+    }
+}
+
+// ------------------------------------------------------------------------
+/// Line with the entry of the old file on the left and of the new one on
+/// the right, marked as in `sdiff`
+struct DiffLine<'dl> {
+    old: LineItem<'dl>,
+    new: LineItem<'dl>,
+    marker: char,
+}
+
+impl<'dl> DiffLine<'dl> {
+    fn boxed(row: &Row, key_len: usize, wc: WindowColors) -> LineItem<'dl> {
+        let (marker, old_wc, new_wc) = match row.change() {
+            Change::Same => (' ', WindowColors { value: wc.text, ..wc }, WindowColors { value: wc.text, ..wc }),
+            Change::Changed => ('|', side_colors(wc, wc.title), side_colors(wc, wc.value)),
+            Change::Added => ('>', wc, side_colors(wc, wc.value)),
+            Change::Removed => ('<', side_colors(wc, wc.title), wc),
+        };
+        let side = |value: &Option<String>, wc| match value {
+            Some(value) => TextLine::field(&row.key, key_len, value, wc).boxed(),
+            None => TextLine::text("", wc).boxed(),
+        };
+        Box::new(DiffLine { old: side(&row.old, old_wc), new: side(&row.new, new_wc), marker })
+    }
+}
+
+/// Colors for a side, all in `style`
+fn side_colors(wc: WindowColors, style: ratatui::style::Style) -> WindowColors {
+    WindowColors { text: style, value: style, ..wc }
+}
+
+impl<'l> Line<'l> for DiffLine<'l> {
+    fn as_pairs(&self, max_len: usize) -> Result<PairVec> {
+        let half = max_len.saturating_sub(SEPARATOR_LEN) / 2;
+        let mut pairs = fit(self.old.as_pairs(half)?, half);
+        pairs.push((None, format!(" {} ", self.marker)));
+        let rest = max_len.saturating_sub(half + SEPARATOR_LEN);
+        pairs.extend(fit(self.new.as_pairs(rest)?, rest));
+        Ok(pairs)
+    }
+}
+
+/// Cut the pairs short or pad them to `width` characters
+fn fit(pairs: PairVec, width: usize) -> PairVec {
+    let mut left = width;
+    let mut fitted: PairVec = pairs
+        .into_iter()
+        .map(|(style, text)| {
+            let text: String = text.chars().take(left).collect();
+            left -= text.chars().count();
+            (style, text)
+        })
+        .collect();
+    fitted.push((None, " ".repeat(left)));
+    fitted
+}
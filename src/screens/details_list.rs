@@ -6,6 +6,7 @@ use anyhow::Result;
 
 use crate::{
     color::WindowSetColors,
+    exe_types::ExeRef,
     formatter::center_in,
     screens,
     windows::{
@@ -19,6 +20,7 @@ use crate::{
 // ------------------------------------------------------------------------
 
 pub fn show<'s>(
+    exe: ExeRef<'s>,
     lines: LineVec<'s>,
     title: &str,
     trailer: &str,
@@ -51,6 +53,7 @@ pub fn show<'s>(
     // Create and show the set of windows
 
     screens::show(
+        Some(exe),
         &mut hdr_win, 
         &mut scr_win, 
         &mut ftr_win,
@@ -0,0 +1,217 @@
+//!
+//! Show the instructions of the code sections
+//!
+
+use anyhow::{anyhow, Result};
+use std::ops::Range;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::disassembly::{self, Arch, Instruction},
+    exe_types::{ExeRef, Section, SymbolMap},
+    formatter::center_in,
+    screens::{self, details_list},
+    windows::{
+        footer::Footer,
+        header::Header,
+        line::{ActionType, EnterFn, Line, LineVec, PairVec},
+        popup,
+        scrollable_region::ScrollableRegion,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+/// Most instruction bytes shown in a line, longer ones are cut short
+const MAX_BYTES: usize = 8;
+
+pub fn is_present(exe: ExeRef) -> bool {
+    disassembly::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let sections = disassembly::code_sections(exe);
+    let name_len = sections.iter().map(|s| s.full_name().len()).max().unwrap_or(0).max("Entry Point".len());
+
+    let mut lines = LineVec::new();
+    if let Some(entry) = exe.entry_point() {
+        lines.push(
+            TextLine::field("Entry Point", name_len, &format!("{:#x}", entry), wc)
+                .action(ActionType::NewWindow(Box::new(move || show_at(exe, entry))))
+                .boxed(),
+        );
+    }
+    lines.extend(sections.iter().map(|section| {
+        TextLine::field(
+            &section.full_name(),
+            name_len,
+            &format!("{:#x}, {} bytes", section.addr, section.size),
+            wc,
+        )
+        .action(ActionType::NewWindow(Box::new(move || show_section(exe, section, None))))
+        .boxed()
+    }));
+
+    let arch = Arch::from_exe(exe).map(|a| a.to_string()).unwrap_or_default();
+    let footer = format!("{} code sections, {}", sections.len(), arch);
+
+    details_list::show(exe, lines, "Disassembly", &footer, wsc)
+}
+
+/// Open the listing at the entry point, for the entry point field of the
+/// header
+pub fn show_entry(exe: ExeRef) -> Result<()> {
+    match exe.entry_point() {
+        Some(addr) => show_at(exe, addr),
+        None => {
+            popup::error_window(&anyhow!("The image has no entry point"));
+            Ok(())
+        }
+    }
+}
+
+/// Open the listing of the section holding `addr`, with its instruction
+/// selected
+pub fn show_at(exe: ExeRef, addr: u64) -> Result<()> {
+    let section = disassembly::code_sections(exe)
+        .into_iter()
+        .find(|s| s.contains(addr))
+        .or_else(|| exe.sections().into_iter().find(|s| s.file_size > 0 && s.contains(addr)));
+
+    match section {
+        Some(section) => show_section(exe, &section, Some(addr)),
+        None => {
+            popup::error_window(&anyhow!("Address {:#x} is not in a section of the file", addr));
+            Ok(())
+        }
+    }
+}
+
+fn show_section(exe: ExeRef, section: &Section, selected: Option<u64>) -> Result<()> {
+    let Some(arch) = Arch::from_exe(exe) else {
+        popup::error_window(&anyhow!("Disassembly of this instruction set is not supported"));
+        return Ok(());
+    };
+    let insns = match disassembly::disassemble(exe, arch, section) {
+        Ok(insns) => insns,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let symbols = exe.symbols();
+    let labels = disassembly::labels(&symbols);
+    let symbol_map = SymbolMap::new(&symbols);
+    let addr_len = format!("{:x}", section.addr.saturating_add(section.size)).len().max(8);
+
+    // Line of each instruction, allowing for the label lines
+    let mut positions = Vec::with_capacity(insns.len());
+    let mut idx = 0;
+    for insn in &insns {
+        if labels.contains_key(&insn.addr) {
+            idx += if idx == 0 { 1 } else { 2 };
+        }
+        positions.push(idx);
+        idx += 1;
+    }
+
+    let mut lines = LineVec::with_capacity(idx);
+    for insn in &insns {
+        if let Some(name) = labels.get(&insn.addr) {
+            if !lines.is_empty() {
+                lines.push(TextLine::text("", wc).boxed());
+            }
+            lines.push(TextLine::text(&format!("{:0al$x} <{}>:", insn.addr, name, al = addr_len), wc).boxed());
+        }
+        let target_idx = insn
+            .target
+            .filter(|&target| section.contains(target))
+            .map(|target| positions[insns.partition_point(|i| i.addr <= target).saturating_sub(1)]);
+        lines.push(Box::new(InstructionLine {
+            exe,
+            insn,
+            symbols: &symbol_map,
+            addr_len,
+            bytes_len: arch.max_len().min(MAX_BYTES),
+            target_idx,
+            wc,
+        }));
+    }
+
+    // Create the set of windows
+
+    let title = format!("Disassembly of {}, {}", section.full_name(), arch);
+    let mut hdr_win = Header::new(&wsc.header, Box::new(move |_sc: usize| (1, title.clone())));
+
+    let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+    if let Some(addr) = selected {
+        scr_win.select(positions[insns.partition_point(|i| i.addr <= addr).saturating_sub(1)]);
+    }
+
+    let footer = format!("{} instructions in {} bytes", insns.len(), section.size);
+    let mut ftr_win = Footer::new(&wsc.footer, Box::new(move |sc: usize| center_in(sc, &footer)));
+
+    screens::show(Some(exe), &mut hdr_win, &mut scr_win, &mut ftr_win)
+}
+
+// ------------------------------------------------------------------------
+/// Line of the listing, enter follows a direct jump or call
+struct InstructionLine<'il> {
+    exe: ExeRef<'il>,
+    insn: &'il Instruction,
+    symbols: &'il SymbolMap<'il>,
+    addr_len: usize,
+    /// Instruction bytes shown
+    bytes_len: usize,
+    /// Line of the branch target when it is in the same listing
+    target_idx: Option<usize>,
+    wc: WindowColors,
+}
+
+impl<'l> Line<'l> for InstructionLine<'l> {
+    fn as_pairs(&self, _max_len: usize) -> Result<PairVec> {
+        let insn = self.insn;
+        let insn_bytes = self.exe.mmap().get(insn.offset..insn.offset + insn.len).unwrap_or_default();
+        let mut bytes: String = insn_bytes.iter().take(self.bytes_len).map(|b| format!("{:02x} ", b)).collect();
+        if insn.len > self.bytes_len {
+            bytes.replace_range(bytes.len() - 3.., "...");
+        }
+
+        let mut pairs = Vec::from([
+            (Some(self.wc.text), format!("{:0al$x}  {:bl$} ", insn.addr, bytes, al = self.addr_len, bl = self.bytes_len * 3)),
+            (Some(self.wc.value), format!("{:7} {}", insn.mnemonic, insn.operands)),
+        ]);
+        if let Some((symbol, offset)) = insn.target.and_then(|target| self.symbols.lookup(target)) {
+            let name = match offset {
+                0 => format!("  <{}>", symbol.name),
+                _ => format!("  <{}+{:#x}>", symbol.name, offset),
+            };
+            pairs.push((Some(self.wc.text), name));
+        }
+
+        Ok(pairs)
+    }
+
+    fn enter_fn(&self) -> Option<EnterFn<'l>> {
+        let target = self.insn.target?;
+        let exe = self.exe;
+        Some(match self.target_idx {
+            Some(idx) => Box::new(move |sr: &mut ScrollableRegion| {
+                sr.select(idx);
+                Ok(())
+            }),
+            None => Box::new(move |_sr: &mut ScrollableRegion| show_at(exe, target)),
+        })
+    }
+
+    fn file_range(&self) -> Option<Range<usize>> {
+        Some(self.insn.offset..self.insn.offset + self.insn.len)
+    }
+}
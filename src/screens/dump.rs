@@ -0,0 +1,207 @@
+//!
+//! Write the screens to stdout as plain text in place of showing them
+//!
+//! Each screen prints its heading and lines, indented by how deeply it is
+//! nested. The file header prints its fields, followed by the views asked
+//! for. Within the views, lines opening another screen can have it printed
+//! under them.
+//!
+
+use anyhow::{bail, Result};
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+};
+
+use crate::{
+    exe_types::ExeVec,
+    screens::{self, file_header, file_list},
+    windows::{header::Header, popup, scrollable_region::ScrollableRegion},
+};
+
+// ------------------------------------------------------------------------
+
+/// Width the lines are formatted for
+const WIDTH: usize = 160;
+
+/// Spaces per level of nesting
+const INDENT: usize = 2;
+
+/// Deepest screen printed, as lines can open screens without end
+const MAX_DEPTH: usize = 4;
+
+/// What to print
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Names of the views to print, ignoring case, "all" for every one
+    pub views: Vec<String>,
+    /// Print the screens opened by lines in the views, and expand lines
+    pub nested: bool,
+}
+
+struct State {
+    options: Options,
+    depth: usize,
+    /// Printing a view?
+    in_view: bool,
+    /// Last line printed, not to repeat a view name as the screen heading
+    last_line: String,
+    /// Opening every screen without printing?
+    walk: bool,
+}
+
+thread_local! {
+    static DUMP: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+/// Print the file list, when there is more than one file, and each file
+pub fn run(executables: &ExeVec, options: Options) -> Result<()> {
+    for name in &options.views {
+        if !name.eq_ignore_ascii_case("all") && !file_header::view_names().any(|v| v.eq_ignore_ascii_case(name)) {
+            bail!(
+                "Unknown view \"{}\", expected \"all\" or one of: {}",
+                name,
+                file_header::view_names().collect::<Vec<_>>().join(", "),
+            );
+        }
+    }
+
+    start(options, false);
+
+    let mut rc = Ok(());
+    if executables.len() > 1 {
+        rc = file_list::show(executables);
+    }
+    for exe in executables.iter().filter(|exe| !exe.is_empty()) {
+        if rc.is_err() {
+            break;
+        }
+        rc = write_line("").and_then(|_| file_header::show(exe.as_ref()));
+    }
+
+    DUMP.with_borrow_mut(|dump| *dump = None);
+
+    // Stop quietly when the reader of a pipe has had enough
+    match rc {
+        Err(err) if err.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => Ok(()),
+        rc => rc,
+    }
+}
+
+/// Open every screen the files lead to without printing them, as
+/// the most a user could look at. The fields of each file header are
+/// followed too, into the screens they open.
+pub fn walk(executables: &ExeVec) -> Result<()> {
+    start(Options { views: Vec::from([String::from("all")]), nested: true }, true);
+
+    let rc = file_list::show(executables)
+        .and_then(|_| executables.iter().try_for_each(|exe| file_header::show(exe.as_ref())));
+
+    DUMP.with_borrow_mut(|dump| *dump = None);
+    rc
+}
+
+fn start(options: Options, walk: bool) {
+    DUMP.with_borrow_mut(|dump| {
+        *dump = Some(State { options, depth: 0, in_view: false, last_line: String::new(), walk })
+    });
+}
+
+/// Are the screens being printed?
+pub fn active() -> bool {
+    DUMP.with_borrow(|dump| dump.is_some())
+}
+
+/// Was the view asked for?
+pub fn wants(view: &str) -> bool {
+    DUMP.with_borrow(|dump| {
+        dump.as_ref().is_some_and(|dump| {
+            dump.options.views.iter().any(|v| v.eq_ignore_ascii_case("all") || v.eq_ignore_ascii_case(view))
+        })
+    })
+}
+
+/// Print the heading `name` and the screens of a file shown by `show_fn`
+/// under it
+pub fn file(name: &str, show_fn: impl FnOnce() -> Result<()>) -> Result<()> {
+    write_line(name)?;
+    nested(false, show_fn)
+}
+
+/// Print the heading `name` and the view shown by `show_fn` under it
+pub fn view(name: &str, show_fn: impl FnOnce() -> Result<()>) -> Result<()> {
+    write_line(name)?;
+    nested(true, show_fn)
+}
+
+/// Print a screen, with the screens opened by its lines when asked to
+pub fn screen(hdr_win: &Header, scr_win: &mut ScrollableRegion) -> Result<()> {
+    let (depth, follow) = DUMP.with_borrow(|dump| match dump {
+        Some(dump) => (
+            dump.depth,
+            dump.options.nested && (dump.in_view || dump.walk && dump.depth > 0) && dump.depth < MAX_DEPTH,
+        ),
+        None => (0, false),
+    });
+    let indent = depth * INDENT;
+
+    if follow && !scr_win.expand_all() {
+        popup::error_window(&screens::too_many_entries());
+    }
+    let heading = hdr_win.heading(WIDTH);
+    let repeated = DUMP.with_borrow(|dump| dump.as_ref().is_some_and(|dump| dump.last_line == heading));
+    if !heading.is_empty() && !repeated {
+        write_line(&heading)?;
+    }
+    for (idx, text) in scr_win.text(WIDTH.saturating_sub(indent)).iter().enumerate() {
+        write_line(text)?;
+        if follow && scr_win.opens_window(idx) {
+            scr_win.select(idx);
+            nested(true, || scr_win.key_enter_handler())?;
+        }
+    }
+    Ok(())
+}
+
+/// Print lines at the depth of the current screen
+pub fn lines(heading: &str, mut lines: impl Iterator<Item = String>) -> Result<()> {
+    write_line(heading)?;
+    lines.try_for_each(|line| write_line(line.trim_end()))
+}
+
+/// Run `show_fn` one level deeper, reporting an error of the screen and
+/// going on
+fn nested(in_view: bool, show_fn: impl FnOnce() -> Result<()>) -> Result<()> {
+    let outer = DUMP.with_borrow_mut(|dump| {
+        dump.as_mut().map(|dump| {
+            dump.depth += 1;
+            std::mem::replace(&mut dump.in_view, in_view)
+        })
+    });
+    let rc = show_fn();
+    DUMP.with_borrow_mut(|dump| {
+        if let (Some(dump), Some(outer)) = (dump.as_mut(), outer) {
+            dump.depth -= 1;
+            dump.in_view = outer;
+        }
+    });
+    screens::carry_on(rc)
+}
+
+fn write_line(text: &str) -> Result<()> {
+    let (depth, walk) = DUMP.with_borrow_mut(|dump| {
+        dump.as_mut().map_or((0, false), |dump| {
+            dump.last_line = text.to_string();
+            (dump.depth, dump.walk)
+        })
+    });
+    if walk {
+        return Ok(());
+    }
+    let mut stdout = io::stdout().lock();
+    match text.is_empty() {
+        true => writeln!(stdout)?,
+        false => writeln!(stdout, "{:indent$}{}", "", text, indent = depth * INDENT)?,
+    }
+    Ok(())
+}
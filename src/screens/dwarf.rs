@@ -0,0 +1,203 @@
+//!
+//! Browse the DWARF debugging information entries by compilation unit
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::dwarf::{
+        self,
+        constants::{self, ATTRIBUTES, TAGS},
+        Die, Dwarf, Unit,
+    },
+    exe_types::ExeRef,
+    screens::details_list,
+    windows::{
+        line::{ActionType, LineItem, LineVec},
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const INDENT: usize = 2;
+const NAME_LEN: usize = "DW_AT_GNU_dwo_name".len() + 4;
+
+pub fn is_present(exe: ExeRef) -> bool {
+    dwarf::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let dwarf = match Dwarf::load(exe) {
+        Some(Ok(dwarf)) => dwarf,
+        Some(Err(err)) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+    let units = match dwarf.units() {
+        Ok(units) => units,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let dwarf_ref = &dwarf;
+
+    let lines: LineVec = units
+        .iter()
+        .map(|unit| unit_line(exe, dwarf_ref, unit, wc))
+        .collect();
+
+    let title = format!("{:10} {:3} {:16} Name / Directory / Producer", "Offset", "Ver", "Language");
+    let footer = format!("{} units in {} bytes of .debug_info", units.len(), dwarf.info.len());
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
+// ------------------------------------------------------------------------
+
+fn unit_line<'l>(exe: ExeRef<'l>, dwarf: &'l Dwarf<'l>, unit: &'l Unit, wc: WindowColors) -> LineItem<'l> {
+    let mut pairs = Vec::from([
+        (Some(wc.text), format!("{:#010x} ", unit.offset)),
+        (Some(wc.value), format!("{:<3} ", unit.version)),
+    ]);
+
+    match dwarf.unit_summary(unit) {
+        Ok(summary) => pairs.extend([
+            (Some(wc.text), format!("{:16} ", summary.language.as_deref().unwrap_or("-"))),
+            (Some(wc.value), summary.name.unwrap_or("-").to_string()),
+            (Some(wc.text), format!("  {}  ", summary.comp_dir.unwrap_or("-"))),
+            (Some(wc.value), summary.producer.unwrap_or("-").to_string()),
+        ]),
+        Err(err) => pairs.push((Some(wc.text), err.to_string())),
+    }
+
+    TextLine::new(pairs)
+        .action(ActionType::Expandable(
+            Box::new(move || die_lines(exe, dwarf, unit, unit.first_die, wc)),
+            0,
+            INDENT,
+        ))
+        .boxed()
+}
+
+/// Attributes and children of the entry at `offset`
+fn die_lines<'l>(
+    exe: ExeRef<'l>,
+    dwarf: &'l Dwarf<'l>,
+    unit: &'l Unit,
+    offset: usize,
+    wc: WindowColors,
+) -> LineVec<'l> {
+    let error_line = |err: anyhow::Error| TextLine::text(&err.to_string(), wc).boxed();
+
+    let die = match dwarf.die(unit, offset) {
+        Ok(Some(die)) => die,
+        Ok(None) => return LineVec::new(),
+        Err(err) => return Vec::from([error_line(err)]),
+    };
+
+    let mut lines = LineVec::new();
+    if let Some(stmt_list) = die.attr(constants::DW_AT_STMT_LIST).and_then(|a| a.udata()) {
+        lines.push(
+            TextLine::text("Line Table", wc)
+                .action(ActionType::NewWindow(Box::new(move || show_line_table(exe, dwarf, unit, stmt_list))))
+                .boxed(),
+        );
+    }
+
+    lines.extend(die.attrs
+        .iter()
+        .map(|attr| TextLine::new(Vec::from([
+                (Some(wc.text), format!("{:NAME_LEN$} :", constants::name(ATTRIBUTES, attr.name))),
+                (Some(wc.value), format!(" {}", dwarf.value_string(unit, attr))),
+            ])).boxed()));
+
+    match dwarf.children(unit, &die) {
+        Ok(children) => lines.extend(children.iter().map(|child| die_line(exe, dwarf, unit, child, wc))),
+        Err(err) => lines.push(error_line(err)),
+    }
+
+    lines
+}
+
+/// Collapsed line for an entry showing its tag and name
+fn die_line<'l>(
+    exe: ExeRef<'l>,
+    dwarf: &'l Dwarf<'l>,
+    unit: &'l Unit,
+    die: &Die,
+    wc: WindowColors,
+) -> LineItem<'l> {
+    let name = die.attr(constants::DW_AT_NAME)
+        .and_then(|a| dwarf.attr_string(unit, a))
+        .unwrap_or("");
+    let offset = die.offset;
+
+    let line = TextLine::new(Vec::from([
+        (Some(wc.text), format!("<{:#x}> ", offset)),
+        (Some(wc.value), constants::name(TAGS, die.tag)),
+        (Some(wc.text), format!(" {}", name)),
+    ]));
+
+    if die.attrs.is_empty() && !die.has_children {
+        return line.boxed();
+    }
+
+    line.action(ActionType::Expandable(
+            Box::new(move || die_lines(exe, dwarf, unit, offset, wc)),
+            0,
+            INDENT,
+        ))
+        .boxed()
+}
+
+// ------------------------------------------------------------------------
+
+/// Rows of the unit's line number program
+fn show_line_table(exe: ExeRef, dwarf: &Dwarf, unit: &Unit, offset: u64) -> Result<()> {
+    let program = match usize::try_from(offset)
+        .map_err(anyhow::Error::from)
+        .and_then(|offset| dwarf.line_program(offset, unit.addr_size))
+    {
+        Ok(program) => program,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let lines: LineVec = program.rows
+        .iter()
+        .map(|row| {
+            let flags = [(row.is_stmt, "stmt"), (row.prologue_end, "prologue_end"), (row.end_sequence, "end_sequence")]
+                .iter()
+                .filter(|f| f.0)
+                .map(|f| f.1)
+                .collect::<Vec<_>>()
+                .join(",");
+            TextLine::new(Vec::from([
+                (Some(wc.value), format!("{:#018x} ", row.address)),
+                (Some(wc.text), format!("{:6} {:6} ", row.line, row.column)),
+                (Some(wc.value), format!("{:24} ", flags)),
+                (Some(wc.text), program.file_name(row.file)),
+            ])).boxed()
+        })
+        .collect();
+
+    let title = format!("{:18} {:6} {:6} {:24} File", "Address", "Line", "Column", "Flags");
+    let footer = format!("{} rows, {} files, line program version {}",
+        program.rows.len(), program.files.len(), program.version);
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
@@ -8,11 +8,90 @@ use crate::{
     color::Colors,
     exe_types::ExeRef,
     formatter::center_in,
-    screens,
-    windows::{details, footer::Footer, header::Header, scrollable_region::ScrollableRegion},
+    screens::{self, addr2line, disassembly, dump, dwarf, go_buildinfo, hex_dump, history, rust_info, security, strings, symbol_hash, symbol_versions, unwind},
+    windows::{
+        details,
+        footer::Footer,
+        header::Header,
+        line::ActionType,
+        scrollable_region::ScrollableRegion,
+        text::TextLine,
+    },
 };
 
 // ------------------------------------------------------------------------
+/// Additional views offered after the header fields when they apply to
+/// the file
+struct View {
+    name: &'static str,
+    is_present: fn(ExeRef) -> bool,
+    show: fn(ExeRef) -> Result<()>,
+}
+
+const VIEWS: &[View] = &[
+    View {
+        name: "Hex Dump",
+        is_present: hex_dump::is_present,
+        show: hex_dump::show,
+    },
+    View {
+        name: "Security",
+        is_present: security::is_present,
+        show: security::show,
+    },
+    View {
+        name: "Disassembly",
+        is_present: disassembly::is_present,
+        show: disassembly::show,
+    },
+    View {
+        name: "Strings",
+        is_present: strings::is_present,
+        show: strings::show,
+    },
+    View {
+        name: "Go Build Information",
+        is_present: go_buildinfo::is_present,
+        show: go_buildinfo::show,
+    },
+    View {
+        name: "Rust Information",
+        is_present: rust_info::is_present,
+        show: rust_info::show,
+    },
+    View {
+        name: "DWARF Debug Information",
+        is_present: dwarf::is_present,
+        show: dwarf::show,
+    },
+    View {
+        name: "Address Lookup",
+        is_present: addr2line::is_present,
+        show: addr2line::show,
+    },
+    View {
+        name: "Unwind Information",
+        is_present: unwind::is_present,
+        show: unwind::show,
+    },
+    View {
+        name: "Symbol Versions",
+        is_present: symbol_versions::is_present,
+        show: symbol_versions::show,
+    },
+    View {
+        name: "Symbol Hash Tables",
+        is_present: symbol_hash::is_present,
+        show: symbol_hash::show,
+    },
+];
+
+// ------------------------------------------------------------------------
+
+/// Names of the views, whether or not a file has them
+pub fn view_names() -> impl Iterator<Item = &'static str> {
+    VIEWS.iter().map(|view| view.name)
+}
 
 pub fn show(exe: ExeRef) -> Result<()> {
     let wsc = Colors::global().get_window_set_colors("file_header")?;
@@ -24,13 +103,35 @@ pub fn show(exe: ExeRef) -> Result<()> {
 
     // Create the scrollable window
 
-    let lines = details::to_lines(
+    let mut lines = details::to_lines(
         exe,
         (0, exe.mmap().len()),
         exe.header_map(),
         wsc.scrollable_region,
     );
 
+    if dump::active() {
+        let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+        return dump::file(&exe.filename().display().to_string(), || {
+            dump::screen(&hdr_win, &mut scr_win)?;
+            VIEWS
+                .iter()
+                .filter(|view| (view.is_present)(exe) && dump::wants(view.name))
+                .try_for_each(|view| dump::view(view.name, || (view.show)(exe)))
+        });
+    }
+
+    lines.extend(
+        VIEWS
+            .iter()
+            .filter(|view| (view.is_present)(exe))
+            .map(|view| {
+                TextLine::text(view.name, wsc.scrollable_region)
+                    .action(ActionType::NewWindow(Box::new(move || (view.show)(exe))))
+                    .boxed()
+            }),
+    );
+
     let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
 
     // Create the footer window
@@ -41,5 +142,7 @@ pub fn show(exe: ExeRef) -> Result<()> {
 
     // Create and show the set of windows
 
-    screens::show(&mut hdr_win, &mut scr_win, &mut ftr_win)
+    let filename = exe.filename();
+    history::name(&filename.file_name().unwrap_or(filename.as_os_str()).to_string_lossy());
+    screens::show(Some(exe), &mut hdr_win, &mut scr_win, &mut ftr_win)
 }
@@ -1,19 +1,42 @@
 //!
 //! Show the file list window
 //!
+//! The files are listed in the order given, under a heading for each
+//! directory when they are in more than one, or sorted on a column as a
+//! single list. The list can be narrowed down to one type of file and to
+//! the names containing some text.
+//!
 
 use anyhow::{Ok, Result};
+use crossterm::event::KeyCode;
+use rayon::prelude::*;
+use std::{
+    fmt,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     color::Colors,
+    decoders::{
+        reader::Endian,
+        security::{self, Level},
+    },
     exe_types::{ExeRef, ExeVec, ETYPE_LENGTH},
     formatter::center_in,
-    screens,
+    screens::{
+        self,
+        history,
+        terminal::TERMWIN,
+    },
     windows::{
         footer::Footer,
         header::Header,
-        line::{ActionType, EnterFn, Line, LineItem, LineVec, PairVec},
+        line::{ActionType, EnterFn, Line, LineVec, PairVec},
+        popup,
         scrollable_region::ScrollableRegion,
+        text::TextLine,
         FSIZE_LENGTH,
     },
 };
@@ -22,104 +45,472 @@ use super::file_header;
 
 // ------------------------------------------------------------------------
 
+/// Width of the column of enabled hardening checks
+const SECURITY_LENGTH: usize = "PIE NX RELRO SSP FORT CET".len();
+
+/// Column of the file list, in the order shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Type,
+    Size,
+    Security,
+    Arch,
+    Bits,
+    Endian,
+    Pie,
+    Stripped,
+    Linking,
+    Name,
+}
+
+impl Column {
+    const ALL: [Column; 10] = [
+        Self::Type,
+        Self::Size,
+        Self::Security,
+        Self::Arch,
+        Self::Bits,
+        Self::Endian,
+        Self::Pie,
+        Self::Stripped,
+        Self::Linking,
+        Self::Name,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Type => "Type",
+            Self::Size => "Size",
+            Self::Security => "Security",
+            Self::Arch => "Arch",
+            Self::Bits => "Bits",
+            Self::Endian => "Endian",
+            Self::Pie => "PIE",
+            Self::Stripped => "Stripped",
+            Self::Linking => "Linking",
+            Self::Name => "Name",
+        }
+    }
+
+    /// Width, with room for the sort marker after the title
+    fn width(self) -> usize {
+        match self {
+            Self::Type => ETYPE_LENGTH,
+            Self::Size => FSIZE_LENGTH,
+            Self::Security => SECURITY_LENGTH,
+            Self::Arch => "PowerPC64".len(),
+            Self::Bits => "Bits ".len(),
+            Self::Endian => "Little ".len(),
+            Self::Pie => "Shared".len(),
+            Self::Stripped => "Stripped ".len(),
+            Self::Linking => "Dynamic ".len(),
+            Self::Name => 0,
+        }
+    }
+
+    /// Shown only along with the other extra columns?
+    fn is_extra(self) -> bool {
+        matches!(self, Self::Arch | Self::Bits | Self::Endian | Self::Pie | Self::Stripped | Self::Linking)
+    }
+
+    /// Text of the column padded to its width, and a space after it
+    fn cell(self, text: &str) -> String {
+        match self {
+            Self::Size => format!("{:>w$.w$} ", text, w = self.width()),
+            Self::Name => text.to_string(),
+            _ => format!("{:<w$.w$} ", text, w = self.width()),
+        }
+    }
+}
+
+/// Type of file, to show only those of one type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Elf,
+    MachO,
+    Pe,
+    Archive,
+    NotExecutable,
+}
+
+impl Kind {
+    const ALL: [Kind; 5] = [Self::Elf, Self::MachO, Self::Pe, Self::Archive, Self::NotExecutable];
+
+    fn of(exe: ExeRef) -> Self {
+        if exe.is_empty() {
+            return if is_archive(exe.filename()) { Self::Archive } else { Self::NotExecutable };
+        }
+        match exe.mmap().get(..4) {
+            Some([0x7f, b'E', b'L', b'F']) => Self::Elf,
+            Some([0xce | 0xcf, 0xfa, 0xed, 0xfe]) => Self::MachO,
+            Some([b'M', b'Z', ..]) => Self::Pe,
+            _ if is_archive(exe.filename()) => Self::Archive,
+            _ => Self::NotExecutable,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Elf => "ELF",
+            Self::MachO => "Mach-O",
+            Self::Pe => "PE",
+            Self::Archive => "Archive",
+            Self::NotExecutable => "Not Executable",
+        })
+    }
+}
+
+/// Static libraries are not opened as executables, so look at the file
+fn is_archive(fname: &Path) -> bool {
+    let mut magic = [0; 8];
+    File::open(fname).and_then(|mut f| f.read_exact(&mut magic)).is_ok() && &magic == b"!<arch>\n"
+}
+
+/// What the columns show of a file, worked out once as most of it needs
+/// the symbols
+struct FileInfo {
+    kind: Kind,
+    /// Path relative to the directory common to all the files
+    path: String,
+    security: String,
+    arch: String,
+    bits: String,
+    endian: String,
+    pie: String,
+    stripped: String,
+    linking: String,
+}
+
+impl FileInfo {
+    fn new(exe: ExeRef, root: &Path) -> Self {
+        let kind = Kind::of(exe);
+        let none = || String::from("-");
+        if exe.is_empty() {
+            return Self {
+                kind,
+                path: relative(exe.filename(), root),
+                security: String::new(),
+                arch: none(),
+                bits: none(),
+                endian: none(),
+                pie: none(),
+                stripped: none(),
+                linking: none(),
+            };
+        }
+
+        let checks = security::checks(exe).unwrap_or_default();
+        let check = |names: &[&str]| checks.iter().find(|c| names.contains(&c.name));
+
+        Self {
+            kind,
+            path: relative(exe.filename(), root),
+            security: security::summary(&checks),
+            arch: exe.architecture().map_or_else(none, |arch| arch.to_string()),
+            bits: exe.bitness().map_or_else(none, |bits| bits.to_string()),
+            endian: match exe.endianness() {
+                Some(Endian::Little) => String::from("Little"),
+                Some(Endian::Big) => String::from("Big"),
+                None => none(),
+            },
+            pie: match check(&["PIE", "ASLR"]) {
+                Some(c) if c.level == Level::Enabled && c.value.starts_with("Shared") => String::from("Shared"),
+                Some(c) if c.level == Level::Enabled => String::from("Yes"),
+                Some(c) if c.level == Level::Disabled => String::from("No"),
+                _ => none(),
+            },
+            stripped: match check(&["Stripped"]) {
+                Some(c) if c.value.starts_with("Yes") => String::from("Yes"),
+                Some(_) => String::from("No"),
+                None => none(),
+            },
+            linking: match exe.dependencies().is_empty() {
+                false => String::from("Dynamic"),
+                true => String::from("Static"),
+            },
+        }
+    }
+
+    /// Text of a column for the file
+    fn value(&self, column: Column, exe: ExeRef) -> String {
+        match column {
+            Column::Type if self.kind == Kind::Archive => self.kind.to_string(),
+            Column::Type => exe.to_string(),
+            Column::Size => exe.len().to_string(),
+            Column::Security => self.security.clone(),
+            Column::Arch => self.arch.clone(),
+            Column::Bits => self.bits.clone(),
+            Column::Endian => self.endian.clone(),
+            Column::Pie => self.pie.clone(),
+            Column::Stripped => self.stripped.clone(),
+            Column::Linking => self.linking.clone(),
+            Column::Name => self.path.clone(),
+        }
+    }
+}
+
+/// What is shown of the files
+#[derive(Debug, Default)]
+struct Settings {
+    /// Column sorted on, the order given if none
+    sort: Option<Column>,
+    reverse: bool,
+    kind: Option<Kind>,
+    /// Case insensitive text the names contain
+    filter: String,
+    /// Show the extra columns?
+    extra: bool,
+}
+
+impl Settings {
+    fn columns(&self) -> impl Iterator<Item = Column> + '_ {
+        Column::ALL.into_iter().filter(|c| self.extra || !c.is_extra())
+    }
+
+    fn matches(&self, info: &FileInfo) -> bool {
+        self.kind.is_none_or(|k| k == info.kind)
+            && (self.filter.is_empty() || info.path.to_lowercase().contains(&self.filter))
+    }
+
+    /// Indexes of the files shown, in the order shown
+    fn shown(&self, executables: &ExeVec, infos: &[FileInfo]) -> Vec<usize> {
+        let mut shown: Vec<usize> = (0..executables.len()).filter(|&idx| self.matches(&infos[idx])).collect();
+        if let Some(column) = self.sort {
+            shown.sort_by(|&a, &b| {
+                let order = match column {
+                    Column::Size => executables[a].len().cmp(&executables[b].len()),
+                    _ => infos[a].value(column, executables[a].as_ref()).cmp(&infos[b].value(column, executables[b].as_ref())),
+                };
+                if self.reverse { order.reverse() } else { order }
+            });
+        } else if self.reverse {
+            shown.reverse();
+        }
+        shown
+    }
+
+    /// Next column sorted on, the order given after the last
+    fn next_sort(&mut self) {
+        let columns: Vec<Column> = self.columns().collect();
+        self.sort = match self.sort {
+            None => Some(columns[0]),
+            Some(c) => columns.iter().skip_while(|&&a| a != c).nth(1).copied(),
+        };
+    }
+
+    /// Next type of file shown, all of them after the last
+    fn next_kind(&mut self) {
+        self.kind = match self.kind {
+            None => Some(Kind::ALL[0]),
+            Some(k) => Kind::ALL.iter().skip_while(|&&a| a != k).nth(1).copied(),
+        };
+    }
+
+    fn toggle_extra(&mut self) {
+        self.extra = !self.extra;
+        if self.sort.is_some_and(|c| c.is_extra()) {
+            self.sort = None;
+        }
+    }
+
+    fn header(&self) -> String {
+        let marker = if self.reverse { '↓' } else { '↑' };
+        " ".to_string()
+            + &self
+                .columns()
+                .map(|c| match self.sort == Some(c) {
+                    true => c.cell(&format!("{}{}", c.title(), marker)),
+                    false => c.cell(c.title()),
+                })
+                .collect::<String>()
+    }
+}
+
 pub fn show<'s>(executables: &'s ExeVec<'s>) -> Result<()> {
     let wsc = Colors::global().get_window_set_colors("file_list")?;
+    let wc = wsc.scrollable_region;
 
     let num_exe = executables.len();
+    let root = common_dir(executables.iter().map(|exe| exe.filename()));
 
-    // Create header window
-
-    let hdr = format!(
-        " {etype:<tl$.tl$} {size:>ml$.ml$} {filename}",
-        tl = ETYPE_LENGTH,
-        etype = "Type",
-        ml = FSIZE_LENGTH,
-        size = "Size",
-        filename = "Name",
-    );
-
-    let hdr_fn = |_sc: usize| (0, hdr.clone());
-
-    let mut hdr_win = Header::new(&wsc.header, Box::new(hdr_fn));
-
-    // Create the scrollable window
-
-    let mut total_len = 0;
-    let lines: LineVec<'s> = executables
-        .iter()
-        .map(|exe| -> LineItem<'s> {
-            total_len += exe.len();
-            Box::new(FileLine {
-                exe: exe.as_ref(),
-                action: if exe.is_empty() {
-                    None
-                } else {
-                    Some(ActionType::NewWindow(Box::new(|| file_header::show(exe.as_ref()))))
-                },
-            })
-        })
-        .collect();
+    // Checking needs the symbols, so it is worth spreading over the cores
+    let infos: Vec<FileInfo> = executables.par_iter().map(|exe| FileInfo::new(exe.as_ref(), &root)).collect();
 
-    let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+    // Lines of the files shown, with the files under a heading for each
+    // directory when in the order given and in more than one
+
+    let to_lines = |settings: &Settings| -> (LineVec<'s>, usize, usize) {
+        let shown = settings.shown(executables, &infos);
+        let shown_len = shown.iter().map(|&idx| executables[idx].len()).sum();
+
+        let groups = match settings.sort {
+            None => by_directory(executables, &shown, &root),
+            Some(_) => Vec::from([(PathBuf::new(), shown)]),
+        };
 
-    // Create the footer window
+        let mut lines: LineVec<'s> = Vec::new();
+        let mut num_shown = 0;
+        for (dir, members) in groups.iter() {
+            if groups.len() > 1 {
+                let heading = match dir.as_os_str().is_empty() {
+                    true => String::from("./"),
+                    false => format!("{}/", dir.display()),
+                };
+                lines.push(TextLine::new(Vec::from([(Some(wc.title), heading)])).boxed());
+            }
+            for &idx in members {
+                let exe = &executables[idx];
+                let info = &infos[idx];
+                num_shown += 1;
+                lines.push(Box::new(FileLine {
+                    exe: (!exe.is_empty()).then(|| exe.as_ref()),
+                    columns: settings
+                        .columns()
+                        .filter(|&c| c != Column::Name)
+                        .map(|c| c.cell(&info.value(c, exe.as_ref())))
+                        .collect(),
+                    name: relative(exe.filename(), &root.join(dir)),
+                    action: if exe.is_empty() {
+                        None
+                    } else {
+                        Some(ActionType::NewWindow(Box::new(|| file_header::show(exe.as_ref()))))
+                    },
+                }));
+            }
+        }
+        (lines, num_shown, shown_len)
+    };
 
-    let footer_fn = |sc: usize| {
-        let txt = format!("{} Files, {} Bytes", num_exe, total_len);
-        center_in(sc, &txt)
+    let mut settings = Settings::default();
+    let (lines, shown, shown_len) = to_lines(&settings);
+    let header = |settings: &Settings| {
+        let hdr_text = settings.header();
+        Header::new(&wsc.header, Box::new(move |_sc: usize| (0, hdr_text.clone())))
+    };
+    let footer = |settings: &Settings, shown: usize, shown_len: usize| {
+        let ftr_text = format!(
+            "{} of {} Files, {} Bytes{}{}{}{}",
+            shown,
+            num_exe,
+            shown_len,
+            if root.as_os_str().is_empty() { String::new() } else { format!(" in {}", root.display()) },
+            settings.sort.map_or(String::new(), |c| format!(", by {}", c.title())),
+            settings.kind.map_or(String::new(), |k| format!(", {} only", k)),
+            if settings.filter.is_empty() { String::new() } else { format!(", matching \"{}\"", settings.filter) },
+        );
+        Footer::new(&wsc.footer, Box::new(move |sc: usize| center_in(sc, &ftr_text)))
     };
+    let mut hdr_win = header(&settings);
+    let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+    let mut ftr_win = footer(&settings, shown, shown_len);
 
-    let mut ftr_win = Footer::new(&wsc.footer, Box::new(footer_fn));
+    history::name("Files");
 
-    // Create and show the set of windows
+    if screens::dump::active() {
+        return screens::dump::screen(&hdr_win, &mut scr_win);
+    }
 
-    screens::show(&mut hdr_win, &mut scr_win, &mut ftr_win)
+    screens::run(None, &mut hdr_win, &mut scr_win, &mut ftr_win, |code, hdr_win, scr_win, ftr_win| {
+        match code {
+            KeyCode::Char('s') => settings.next_sort(),
+            KeyCode::Char('r') => settings.reverse = !settings.reverse,
+            KeyCode::Char('t') => settings.next_kind(),
+            KeyCode::Char('c') => settings.toggle_extra(),
+            KeyCode::Char('f') => {
+                if let Some(input) = popup::prompt("Filter Files", "Name containing") {
+                    settings.filter = input.trim().to_lowercase();
+                }
+                TERMWIN.terminal.lock().unwrap().clear()?;
+            }
+            _ => return Ok(false),
+        }
+
+        let (lines, shown, shown_len) = to_lines(&settings);
+        *hdr_win = header(&settings);
+        *scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+        *ftr_win = footer(&settings, shown, shown_len);
+        Ok(true)
+    })
 }
 
 // ------------------------------------------------------------------------
-/// Line in the file list
+/// Deepest directory holding all the files, empty if they have none in common
+fn common_dir<'f>(filenames: impl Iterator<Item = &'f Path>) -> PathBuf {
+    let mut common: Option<PathBuf> = None;
+    for fname in filenames {
+        let dir = fname.parent().unwrap_or(Path::new(""));
+        common = Some(match common {
+            None => dir.to_path_buf(),
+            Some(common) => common.components().zip(dir.components()).take_while(|(a, b)| a == b).map(|c| c.0).collect(),
+        });
+    }
+    common.unwrap_or_default()
+}
+
+/// The executables `shown` by their directory relative to `root`, in the
+/// order the directories first appear
+fn by_directory(executables: &ExeVec, shown: &[usize], root: &Path) -> Vec<(PathBuf, Vec<usize>)> {
+    let mut groups: Vec<(PathBuf, Vec<usize>)> = Vec::new();
+    for &idx in shown {
+        let dir = executables[idx].filename().parent().unwrap_or(Path::new(""));
+        let dir = dir.strip_prefix(root).unwrap_or(dir).to_path_buf();
+        match groups.iter_mut().find(|group| group.0 == dir) {
+            Some(group) => group.1.push(idx),
+            None => groups.push((dir, Vec::from([idx]))),
+        }
+    }
+    groups
+}
+
+/// Path of the file relative to `dir`
+fn relative(path: &Path, dir: &Path) -> String {
+    path.strip_prefix(dir).unwrap_or(path).display().to_string()
+}
 
+// ------------------------------------------------------------------------
+/// Line in the file list
 struct FileLine<'fl> {
-    exe: ExeRef<'fl>,
+    /// `None` when the file is not an executable
+    exe: Option<ExeRef<'fl>>,
+    /// Columns before the name, padded to their width
+    columns: String,
+    /// File name relative to the directory heading it
+    name: String,
     action: Option<ActionType<'fl>>,
 }
 
 impl<'l> Line<'l> for FileLine<'l> {
     fn as_pairs(&self, width: usize) -> Result<PairVec> {
-        let max_fname = width as isize - (ETYPE_LENGTH + FSIZE_LENGTH + 2) as isize;
-        let fname = self.exe.filename();
-
-        let first_part = format!(
-            "{etype:<tl$.tl$} {size:>ml$.ml$} ",
-            tl = ETYPE_LENGTH,
-            etype = self.exe.to_string(),
-            ml = FSIZE_LENGTH,
-            size = self.exe.len()
-        );
+        let max_fname = width as isize - self.columns.chars().count() as isize;
+        let fname = self.name.as_str();
 
-        let line = &(first_part
-            + if max_fname < FSIZE_LENGTH as isize {
-                &fname[(fname.len() - width)..]
-            } else {
-                let start = max_fname - fname.len() as isize;
-                if start < 0 {
-                    &fname[(-start as usize)..]
-                } else {
-                    fname
-                }
-            });
-
-        let line = if width < line.len() {
-            &line[..width]
+        // Keep the end of the name, counting characters so a multi-byte
+        // one is never split
+        let keep = if max_fname < FSIZE_LENGTH as isize {
+            width
         } else {
-            line
+            max_fname as usize
         };
+        let skip = fname.chars().count().saturating_sub(keep);
+        let line: String = self
+            .columns
+            .chars()
+            .chain(fname.chars().skip(skip))
+            .take(width)
+            .collect();
 
-        Ok(Vec::from([(None, line.into())]))
+        Ok(Vec::from([(None, line)]))
     }
 
     fn enter_fn(&self) -> Option<EnterFn<'l>> {
-        Some(Box::new(|_sr| file_header::show(self.exe)))
+        let exe = self.exe?;
+        Some(Box::new(move |_sr| file_header::show(exe)))
+    }
+
+    fn exe(&self) -> Option<ExeRef<'l>> {
+        self.exe
     }
 
     fn action_type(&self) -> Option<&ActionType<'l>> {
@@ -130,3 +521,82 @@ impl<'l> Line<'l> for FileLine<'l> {
         self.action.as_mut()
     }
 }
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exe_types;
+
+    const SAMPLES: [&str; 4] = ["exit-x86_64.exe", "header-i386.macho", "hello-x86_64.elf", "hello-x86_64.macho"];
+
+    fn names(executables: &ExeVec, infos: &[FileInfo], settings: &Settings) -> Vec<String> {
+        settings.shown(executables, infos).into_iter().map(|idx| infos[idx].path.clone()).collect()
+    }
+
+    #[test]
+    fn sort_and_filter() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus/samples");
+        let executables: ExeVec = SAMPLES.iter().map(|name| exe_types::new(dir.join(name))).collect();
+        let root = common_dir(executables.iter().map(|exe| exe.filename()));
+        assert_eq!(root, dir);
+        let infos: Vec<FileInfo> = executables.iter().map(|exe| FileInfo::new(exe.as_ref(), &root)).collect();
+        let mut settings = Settings::default();
+
+        assert_eq!(names(&executables, &infos, &settings), SAMPLES);
+        settings.reverse = true;
+        assert_eq!(names(&executables, &infos, &settings), SAMPLES.iter().rev().copied().collect::<Vec<_>>());
+
+        // Sizes sort as numbers, not as text
+        settings.sort = Some(Column::Size);
+        let mut sizes: Vec<usize> = settings.shown(&executables, &infos).iter().map(|&idx| executables[idx].len()).collect();
+        assert!(sizes.is_sorted_by(|a, b| a >= b));
+        settings.reverse = false;
+        sizes.reverse();
+        assert_eq!(settings.shown(&executables, &infos).iter().map(|&idx| executables[idx].len()).collect::<Vec<_>>(), sizes);
+
+        // Types, then names within a type
+        settings.sort = Some(Column::Type);
+        assert_eq!(names(&executables, &infos, &settings)[..2], ["hello-x86_64.elf", "header-i386.macho"]);
+
+        settings.kind = Some(Kind::MachO);
+        settings.sort = Some(Column::Name);
+        assert_eq!(names(&executables, &infos, &settings), ["header-i386.macho", "hello-x86_64.macho"]);
+        settings.filter = String::from("hello");
+        assert_eq!(names(&executables, &infos, &settings), ["hello-x86_64.macho"]);
+        settings.kind = None;
+        assert_eq!(names(&executables, &infos, &settings), ["hello-x86_64.elf", "hello-x86_64.macho"]);
+        settings.kind = Some(Kind::Archive);
+        assert!(names(&executables, &infos, &settings).is_empty());
+    }
+
+    #[test]
+    fn settings_cycle() {
+        let mut settings = Settings::default();
+
+        // The extra columns are only sorted on when shown
+        let mut sorts = Vec::new();
+        for _ in 0..5 {
+            settings.next_sort();
+            sorts.push(settings.sort);
+        }
+        assert_eq!(sorts, [Some(Column::Type), Some(Column::Size), Some(Column::Security), Some(Column::Name), None]);
+
+        settings.toggle_extra();
+        settings.sort = Some(Column::Size);
+        settings.next_sort();
+        settings.next_sort();
+        assert_eq!(settings.sort, Some(Column::Arch));
+        settings.toggle_extra();
+        assert_eq!(settings.sort, None);
+
+        let mut kinds = Vec::new();
+        for _ in 0..Kind::ALL.len() + 1 {
+            settings.next_kind();
+            kinds.push(settings.kind);
+        }
+        assert_eq!(kinds.last(), Some(&None));
+        assert_eq!(kinds[..Kind::ALL.len()], Kind::ALL.map(Some));
+    }
+}
@@ -0,0 +1,129 @@
+//!
+//! Show the build information embedded in a Go executable
+//!
+
+use anyhow::{anyhow, Result};
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::go_buildinfo::{self, BuildInfo, Module},
+    exe_types::ExeRef,
+    screens::details_list,
+    windows::{
+        line::{ActionType, LineVec},
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const NAME_LEN: usize = "Build Settings".len();
+
+/// Is there any Go build information in the file?
+pub fn is_present(exe: ExeRef) -> bool {
+    go_buildinfo::find_in(exe).is_some()
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let Some(offset) = go_buildinfo::find_in(exe) else {
+        return Err(anyhow!("No Go build information in {}", exe.filename().display()));
+    };
+    let info = match go_buildinfo::decode_at(exe.mmap(), offset, &exe.address_space()) {
+        Ok(info) => info,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let main = info.main.as_ref().map(module_string).unwrap_or_default();
+
+    let mut lines: LineVec = Vec::from([
+        TextLine::field("Go Version", NAME_LEN, &info.go_version, wc).boxed(),
+        TextLine::field("Path", NAME_LEN, &info.path, wc).boxed(),
+        TextLine::field("Main Module", NAME_LEN, &main, wc).boxed(),
+    ]);
+
+    let info_ref = &info;
+
+    lines.push(
+        TextLine::field("Dependencies", NAME_LEN, &info.deps.len().to_string(), wc)
+            .action(ActionType::NewWindow(Box::new(move || show_deps(exe, info_ref))))
+            .boxed(),
+    );
+    lines.push(
+        TextLine::field("Build Settings", NAME_LEN, &info.settings.len().to_string(), wc)
+            .action(ActionType::NewWindow(Box::new(move || show_settings(exe, info_ref))))
+            .boxed(),
+    );
+
+    let footer = format!("Go build information at {:#x}", info.offset);
+
+    details_list::show(exe, lines, "Go Build Information", &footer, wsc)
+}
+
+// ------------------------------------------------------------------------
+
+fn module_string(module: &Module) -> String {
+    let mut rc = format!("{} {}", module.path, module.version);
+    if let Some(replace) = &module.replace {
+        rc += &format!(" => {} {}", replace.path, replace.version);
+    }
+    rc
+}
+
+fn show_deps(exe: ExeRef, info: &BuildInfo) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+
+    let path_len = info.deps.iter().map(|m| m.path.len()).max().unwrap_or(0);
+    let ver_len = info.deps.iter().map(|m| m.version.len()).max().unwrap_or(0);
+
+    let lines: LineVec = info.deps
+        .iter()
+        .map(|m| module_line(m, path_len, ver_len, wsc.scrollable_region).boxed())
+        .collect();
+
+    let title = format!(
+        "{:path_len$} {:ver_len$} Checksum",
+        "Module", "Version",
+    );
+    let footer = format!("{} dependencies", info.deps.len());
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
+fn module_line<'m>(
+    module: &Module,
+    path_len: usize,
+    ver_len: usize,
+    wc: WindowColors,
+) -> TextLine<'m> {
+    let mut pairs = Vec::from([
+        (Some(wc.text), format!("{:path_len$} ", module.path)),
+        (Some(wc.value), format!("{:ver_len$} ", module.version)),
+        (Some(wc.text), module.sum.clone()),
+    ]);
+    if let Some(replace) = &module.replace {
+        pairs.push((Some(wc.value), format!(" => {} {}", replace.path, replace.version)));
+    }
+    TextLine::new(pairs)
+}
+
+fn show_settings(exe: ExeRef, info: &BuildInfo) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+
+    let key_len = info.settings.iter().map(|s| s.0.len()).max().unwrap_or(0);
+
+    let lines: LineVec = info.settings
+        .iter()
+        .map(|(k, v)| TextLine::field(k, key_len, v, wsc.scrollable_region).boxed())
+        .collect();
+
+    let footer = format!("{} build settings", info.settings.len());
+
+    details_list::show(exe, lines, "Build Settings", &footer, wsc)
+}
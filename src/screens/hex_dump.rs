@@ -0,0 +1,151 @@
+//!
+//! Show the bytes of the whole file as a hex and ASCII dump
+//!
+
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use std::ops::Range;
+
+use crate::{
+    color::Colors,
+    exe_types::{structure, AddressSpace, ExeRef, Mapping, Section, StructureMap},
+    formatter::{self, center_in},
+    screens::{self, dump, terminal::TERMWIN},
+    windows::{
+        footer::Footer,
+        header::Header,
+        hex_region::HexRegion,
+        popup,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const BYTES_PER_ROW: usize = 16;
+
+pub fn is_present(exe: ExeRef) -> bool {
+    !exe.is_empty()
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    show_range(exe, None)
+}
+
+/// Why an address has no file offset
+fn unmapped(space: &AddressSpace, addr: u64) -> anyhow::Error {
+    match space.mapping(addr) {
+        _ if space.is_empty() => anyhow!("No virtual addresses in this file"),
+        Some(mapping) => anyhow!("Address {:#x} is zero filled in {}", addr, mapping_name(mapping)),
+        None => anyhow!("Address {:#x} is not mapped from the file", addr),
+    }
+}
+
+fn mapping_name(mapping: &Mapping) -> String {
+    match mapping.name.is_empty() {
+        true => format!("the segment at {:#x}", mapping.addr),
+        false => mapping.name.clone(),
+    }
+}
+
+/// Show the dump with a range of bytes highlighted and selected
+pub fn show_range(exe: ExeRef, highlight: Option<Range<usize>>) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let mut hex_win = HexRegion::new(&wsc.scrollable_region, exe.mmap(), BYTES_PER_ROW);
+    if let Some(range) = highlight {
+        hex_win.highlight(range);
+    }
+    let structures = structure::flatten(&exe.structures(), exe.mmap());
+    let sections = exe.sections();
+    let space = exe.address_space();
+
+    if dump::active() {
+        return dump::lines(&hex_win.column_header(), (0..hex_win.num_rows()).map(|row| hex_win.row_text(row)));
+    }
+
+    // The column header follows the bytes per row, the footer the cursor
+    let header = |hex_win: &HexRegion| {
+        let hdr_text = hex_win.column_header();
+        Header::new(&wsc.header, Box::new(move |_sc: usize| (0, hdr_text.clone())))
+    };
+    let footer = |hex_win: &HexRegion| {
+        let addr = space.to_addr(hex_win.cursor()).map_or(String::new(), |addr| format!(" at {:#x}", addr));
+        let ftr_text = format!("{:#x} of {:#x}{}: {}",
+            hex_win.cursor(), exe.len(), addr, location(&structures, &sections, hex_win.cursor()));
+        Footer::new(&wsc.footer, Box::new(move |sc: usize| center_in(sc, &ftr_text)))
+    };
+    let mut hdr_win = header(&hex_win);
+    let mut ftr_win = footer(&hex_win);
+
+    screens::run(Some(exe), &mut hdr_win, &mut hex_win, &mut ftr_win, |code, hdr_win, hex_win, ftr_win| {
+        match code {
+            KeyCode::Char('g') => {
+                if let Some(input) = popup::prompt("Go To Offset", "Offset") {
+                    match formatter::parse_address(&input) {
+                        Ok(offset) => hex_win.goto(offset.try_into().unwrap_or(usize::MAX)),
+                        Err(err) => popup::error_window(&err),
+                    }
+                }
+                TERMWIN.terminal.lock().unwrap().clear()?;
+            }
+            KeyCode::Char('a') => {
+                if let Some(input) = popup::prompt("Go To Address", "Virtual address") {
+                    match formatter::parse_address(&input) {
+                        Ok(addr) => match space.to_offset(addr) {
+                            Some(offset) => hex_win.goto(offset),
+                            None => popup::error_window(&unmapped(&space, addr)),
+                        },
+                        Err(err) => popup::error_window(&err),
+                    }
+                }
+                TERMWIN.terminal.lock().unwrap().clear()?;
+            }
+            KeyCode::Char('w') => {
+                if let Some(input) = popup::prompt("Bytes Per Row", "Bytes") {
+                    match input.trim().parse() {
+                        Ok(bytes_per_row) => hex_win.set_bytes_per_row(bytes_per_row),
+                        Err(_) => popup::error_window(&anyhow!("Invalid number of bytes: {}", input)),
+                    }
+                }
+                TERMWIN.terminal.lock().unwrap().clear()?;
+            }
+            KeyCode::Char('>') => hex_win.set_bytes_per_row(hex_win.bytes_per_row() + 4),
+            KeyCode::Char('<') => hex_win.set_bytes_per_row(hex_win.bytes_per_row().saturating_sub(4)),
+            code => hex_win.handle_key(code)?,
+        }
+
+        *hdr_win = header(hex_win);
+        *ftr_win = footer(hex_win);
+        Ok(true)
+    })
+}
+
+/// Structure and field, or else the section, holding the byte at `offset`
+fn location(structures: &[StructureMap], sections: &[Section], offset: usize) -> String {
+    let fields = structures
+        .iter()
+        .filter(|s| (s.data.0..s.data.1).contains(&offset))
+        .map(|s| (&s.name, s.data.1 - s.data.0, s.map.field_at(offset - s.data.0, s.data.1 - s.data.0)));
+
+    // The field with the fewest bytes is the most specific
+    let mut structure = None;
+    let mut best: Option<(&str, &str, usize)> = None;
+    for (name, block_len, field) in fields {
+        structure = structure.or(Some(*name));
+        if let Some(field) = field {
+            let (start, end) = field.block_range(block_len);
+            if best.is_none_or(|b| end - start < b.2) {
+                best = Some((name, field.name, end - start));
+            }
+        }
+    }
+
+    match (best, structure) {
+        (Some((name, field, _)), _) => format!("{}, {}", name, field),
+        (None, Some(name)) => name.to_string(),
+        (None, None) => sections
+            .iter()
+            .find(|s| s.file_size > 0 && (s.offset..s.offset + s.file_size).contains(&(offset as u64)))
+            .map(|s| format!("Section {} + {:#x}", s.full_name(), offset as u64 - s.offset))
+            .unwrap_or_default(),
+    }
+}
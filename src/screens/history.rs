@@ -0,0 +1,375 @@
+//!
+//! Trail of the open screens, shown as a breadcrumb in the header
+//!
+//! Each screen is shown by a call nested in the one of the screen that
+//! opened it, so going back returns from the call and finds the screen
+//! below as it was left. Going back records where the screen was opened
+//! from and its position, so going forward opens it again from the same
+//! line and restores the position. The line is found again by its text, as
+//! the lines of the screen below may have been sorted or filtered since.
+//! Jumping to an earlier screen closes each screen above it in turn.
+//!
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cell::RefCell;
+
+// ------------------------------------------------------------------------
+
+const SEPARATOR: &str = " > ";
+
+/// Longest label shown for a screen
+const MAX_LABEL_LEN: usize = 32;
+
+#[derive(Debug, Clone)]
+struct Crumb {
+    label: String,
+    /// Key, and index and text of the line, of the screen below that
+    /// opened it
+    opened_by: Option<(KeyCode, usize, String)>,
+    /// Position it was left at, restored going forward
+    position: Option<(usize, usize)>,
+    /// Opened again going forward?
+    revisit: bool,
+}
+
+#[derive(Debug, Default)]
+struct History {
+    /// Screens open, the first at the bottom
+    open: Vec<Crumb>,
+    /// Screens gone back from, the next to go forward to last
+    forward: Vec<(usize, Crumb)>,
+    /// Crumb for the screen being opened
+    next: Option<Crumb>,
+    /// Number of screens to leave open while going back
+    keep: Option<usize>,
+}
+
+thread_local! {
+    static HISTORY: RefCell<History> = RefCell::new(History::default());
+}
+
+/// Name the screen about to be shown `label`, in place of the line that
+/// opened it
+pub fn name(label: &str) {
+    HISTORY.with_borrow_mut(|h| match &mut h.next {
+        Some(next) => next.label = shorten(label),
+        None => {
+            h.next = Some(Crumb {
+                label: shorten(label),
+                opened_by: None,
+                position: None,
+                revisit: false,
+            })
+        }
+    });
+}
+
+/// Run `open_fn`, naming any screen it opens `label` and recording that it
+/// was opened by `key` on the line at `line` reading `text`
+pub fn open<F>(label: &str, key: KeyCode, line: usize, text: &str, open_fn: F) -> Result<()>
+where
+    F: FnOnce() -> Result<()>,
+{
+    HISTORY.with_borrow_mut(|h| {
+        if h.next.as_ref().is_none_or(|next| !next.revisit) {
+            h.next = Some(Crumb {
+                label: shorten(label),
+                opened_by: Some((key, line, text.to_string())),
+                position: None,
+                revisit: false,
+            });
+        }
+    });
+    let result = open_fn();
+    HISTORY.with_borrow_mut(|h| h.next = None);
+    result
+}
+
+/// Drop the screen set up to be opened again going forward, when the line
+/// that opened it is gone
+pub fn forget() {
+    HISTORY.with_borrow_mut(|h| h.next = None);
+}
+
+/// The open screens, numbered for the keys jumping back to them
+pub fn trail() -> String {
+    HISTORY.with_borrow(|h| {
+        h.open
+            .iter()
+            .enumerate()
+            .map(|(idx, crumb)| format!("{} {}", idx + 1, crumb.label))
+            .collect::<Vec<_>>()
+            .join(SEPARATOR)
+    })
+}
+
+/// Label of a screen, cut short if need be
+fn shorten(label: &str) -> String {
+    let label = label.trim();
+    match label.char_indices().nth(MAX_LABEL_LEN) {
+        Some((end, _)) => format!("{}...", &label[..end]),
+        None => label.to_string(),
+    }
+}
+
+// ------------------------------------------------------------------------
+
+/// What a navigation key asks of the screen
+pub enum Step {
+    /// Close the screen
+    Close,
+    /// Open the screen gone back from, selecting the line and pressing the
+    /// key that opened it. The line is the one with the text, the index is
+    /// where it was.
+    Reopen(KeyCode, usize, String),
+    /// Nothing to do
+    Stay,
+}
+
+/// Place of a screen in the trail, for as long as it is shown
+pub struct Visit {
+    depth: usize,
+    position: Option<(usize, usize)>,
+}
+
+impl Visit {
+
+    /// Add the screen being opened to the trail
+    pub fn enter() -> Self {
+        HISTORY.with_borrow_mut(|h| {
+            let crumb = h.next.take().unwrap_or_else(|| Crumb {
+                label: String::from("?"),
+                opened_by: None,
+                position: None,
+                revisit: false,
+            });
+            if !crumb.revisit {
+                h.forward.clear();
+            }
+            let position = crumb.position.filter(|_| crumb.revisit);
+            h.open.push(Crumb { revisit: false, ..crumb });
+            Visit { depth: h.open.len() - 1, position }
+        })
+    }
+
+    /// Position to restore, when the screen is opened again going forward
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+
+    /// Is the trail going back past the screen?
+    pub fn closing(&self) -> bool {
+        HISTORY.with_borrow(|h| h.keep.is_some_and(|keep| self.depth >= keep))
+    }
+
+    /// Handle the keys going back, forward, or with `Alt` to a numbered
+    /// screen of the trail. None if the key is not one of them.
+    pub fn handle_key(&self, key: KeyEvent) -> Option<Step> {
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Backspace => Some(Step::Close),
+            KeyCode::Left if alt => Some(Step::Close),
+            KeyCode::Right if alt => Some(self.forward()),
+            code if alt => self.jump(code),
+            _ => None,
+        }
+    }
+
+    /// Handle a digit key going to that screen of the trail, for the keys
+    /// the screen itself has no use for. None if the key is not a digit.
+    pub fn jump(&self, code: KeyCode) -> Option<Step> {
+        let KeyCode::Char(c @ '1'..='9') = code else {
+            return None;
+        };
+        let keep = c as usize - '0' as usize;
+        if keep > self.depth {
+            return Some(Step::Stay);
+        }
+        HISTORY.with_borrow_mut(|h| h.keep = Some(keep));
+        Some(Step::Close)
+    }
+
+    /// Close the screen, keeping `position` to restore going forward
+    pub fn leave(mut self, position: (usize, usize)) {
+        self.position = Some(position);
+    }
+
+    /// Set up the screen gone back from last to be opened again
+    fn forward(&self) -> Step {
+        HISTORY.with_borrow_mut(|h| {
+            let opened_by = match h.forward.last() {
+                Some((depth, crumb)) if *depth == self.depth + 1 => crumb.opened_by.clone(),
+                _ => None,
+            };
+            let Some((key, line, text)) = opened_by else {
+                return Step::Stay;
+            };
+            h.next = h.forward.pop().map(|(_, crumb)| Crumb { revisit: true, ..crumb });
+            Step::Reopen(key, line, text)
+        })
+    }
+
+}
+
+impl Drop for Visit {
+    fn drop(&mut self) {
+        HISTORY.with_borrow_mut(|h| {
+            h.open.truncate(self.depth + 1);
+            if let Some(mut crumb) = h.open.pop() {
+                crumb.position = self.position;
+                h.forward.push((self.depth, crumb));
+            }
+            if h.keep.is_some_and(|keep| keep >= h.open.len()) {
+                h.keep = None;
+            }
+        });
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Keys to press and the trail seen at each
+    #[derive(Default)]
+    struct Session {
+        keys: VecDeque<KeyEvent>,
+        trails: Vec<String>,
+    }
+
+    impl Session {
+        fn new(keys: &[(KeyCode, KeyModifiers)]) -> Self {
+            let keys = keys.iter().map(|&(code, modifiers)| KeyEvent::new(code, modifiers)).collect();
+            Session { keys, trails: Vec::new() }
+        }
+    }
+
+    const NONE: KeyModifiers = KeyModifiers::NONE;
+    const ALT: KeyModifiers = KeyModifiers::ALT;
+
+    /// Screen of lines each opening a screen of the same lines with enter.
+    /// `r` rotates the lines, as sorting a list would, and `5` is a key of
+    /// the screen's own.
+    fn screen(session: &mut Session, mut lines: Vec<&'static str>) -> Result<()> {
+        let visit = Visit::enter();
+        let mut selected = visit.position().map_or(0, |(_, win_idx)| win_idx);
+
+        while !visit.closing() {
+            let Some(key) = session.keys.pop_front() else { break };
+            session.trails.push(trail());
+            let reopen = match visit.handle_key(key) {
+                Some(Step::Close) => break,
+                Some(Step::Reopen(_, _, text)) => lines.iter().position(|line| *line == text),
+                Some(Step::Stay) => continue,
+                None => match key.code {
+                    KeyCode::Down => {
+                        selected = (selected + 1).min(lines.len() - 1);
+                        continue;
+                    }
+                    KeyCode::Char('r') => {
+                        lines.rotate_left(1);
+                        continue;
+                    }
+                    KeyCode::Char('5') => continue,
+                    KeyCode::Enter => Some(selected),
+                    code => match visit.jump(code) {
+                        Some(Step::Close) => break,
+                        _ => continue,
+                    },
+                },
+            };
+            if let Some(line) = reopen {
+                selected = line;
+                let text = lines[line];
+                let child = lines.clone();
+                open(text, KeyCode::Enter, line, text, || screen(session, child))?;
+            }
+        }
+
+        visit.leave((0, selected));
+        Ok(())
+    }
+
+    fn run(keys: &[(KeyCode, KeyModifiers)]) -> Vec<String> {
+        let mut session = Session::new(keys);
+        name("top");
+        screen(&mut session, Vec::from(["a", "b", "c"])).unwrap();
+        session.trails
+    }
+
+    #[test]
+    fn back_and_forward() {
+        let trails = run(&[
+            (KeyCode::Down, NONE),
+            (KeyCode::Enter, NONE),
+            (KeyCode::Down, NONE),
+            (KeyCode::Enter, NONE),
+            (KeyCode::Backspace, NONE),
+            (KeyCode::Left, ALT),
+            (KeyCode::Right, ALT),
+            (KeyCode::Right, ALT),
+            (KeyCode::Right, ALT),
+            (KeyCode::Char('q'), NONE),
+        ]);
+        assert_eq!(trails, [
+            "1 top",
+            "1 top",
+            "1 top > 2 b",
+            "1 top > 2 b",
+            "1 top > 2 b > 3 b",
+            "1 top > 2 b",
+            "1 top",
+            "1 top > 2 b",
+            // Nothing left to go forward to
+            "1 top > 2 b > 3 b",
+            "1 top > 2 b > 3 b",
+        ]);
+    }
+
+    #[test]
+    fn forward_finds_the_line_moved() {
+        // After b moves up a line the screen it opened is opened again
+        // from it and not from the line now where it was
+        let trails = run(&[
+            (KeyCode::Down, NONE),
+            (KeyCode::Enter, NONE),
+            (KeyCode::Backspace, NONE),
+            (KeyCode::Char('r'), NONE),
+            (KeyCode::Right, ALT),
+            (KeyCode::Backspace, NONE),
+        ]);
+        assert_eq!(trails.last().unwrap(), "1 top > 2 b");
+    }
+
+    #[test]
+    fn jump() {
+        let enter = (KeyCode::Enter, NONE);
+        let trails = run(&[
+            enter,
+            enter,
+            enter,
+            // A digit the screen uses stays with it, Alt jumps anyway
+            (KeyCode::Char('5'), NONE),
+            (KeyCode::Char('2'), NONE),
+            enter,
+            (KeyCode::Char('5'), ALT),
+            (KeyCode::Char('1'), ALT),
+            (KeyCode::Down, NONE),
+        ]);
+        assert_eq!(trails, [
+            "1 top",
+            "1 top > 2 a",
+            "1 top > 2 a > 3 a",
+            "1 top > 2 a > 3 a > 4 a",
+            "1 top > 2 a > 3 a > 4 a",
+            "1 top > 2 a",
+            "1 top > 2 a > 3 a",
+            "1 top > 2 a > 3 a",
+            "1 top",
+        ]);
+    }
+}
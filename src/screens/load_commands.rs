@@ -0,0 +1,131 @@
+//!
+//! List of the load commands of a Mach-O image, each expanding to the
+//! fields of the command
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::{Colors, WindowColors},
+    exe_types::{
+        macho64::{self, CMD_HEADER, HEADER, LAYOUT32, LAYOUT64},
+        ExeRef,
+    },
+    formatter::{FieldDef, ValEntry},
+    screens::details_list,
+    windows::{
+        details,
+        line::{self, ActionType, Line, PairVec},
+    },
+};
+
+// ------------------------------------------------------------------------
+
+/// Load commands line -> new window listing the load commands
+struct CmdLine<'e> {
+    exe: ExeRef<'e>,
+    data: (usize, usize),
+    val_entry: Option<&'e ValEntry<'e>>,
+    fields: &'e [FieldDef<'e>],
+    wc: WindowColors,
+    action: Option<ActionType<'e>>
+
+}
+
+const DTL_INDENT: usize = 7;
+
+impl<'cl> CmdLine<'cl> {
+    fn new(
+        exe: ExeRef<'cl>,
+        cmd_offset: usize,
+        cmd_len: usize,
+        cmd_slice: &[u8],
+        wc: WindowColors,
+    ) -> CmdLine<'cl> {
+
+        let val_entry = CMD_HEADER[0].lookup(cmd_slice);
+        let data = (cmd_offset, cmd_offset.saturating_add(cmd_len));
+
+        let action = 
+            if let Some(val_entry) = val_entry {
+                val_entry.2.as_ref().map(|detail_map| ActionType::Expandable(
+                    Box::new(move | | details::to_lines(exe, data, detail_map, wc)), 
+                    0, 
+                    DTL_INDENT
+                ))
+            } else {
+                None
+            };
+
+        CmdLine {
+            exe, wc, val_entry, action, data,
+            fields: CMD_HEADER,
+        }
+
+    }
+}
+
+impl<'l> Line<'l> for CmdLine<'l> {
+    fn as_pairs(&self, _max_len: usize) -> Result<PairVec> {
+        let mmap = self.exe.mmap();
+        let data = mmap.get(self.data.0..self.data.1.min(mmap.len())).unwrap_or_default();
+
+        let mut pairs = Vec::from([
+            (Some(self.wc.text), format!("{:>6}", self.fields[1].to_string(data))),
+            (Some(self.wc.text), String::from(" ")),
+            (Some(self.wc.text), self.fields[0].to_string(data)),
+        ]);
+
+        if let Some(desc) = self.val_entry {
+            pairs.push((Some(self.wc.value), format!(" ({})", desc.1)));
+        };
+
+        Ok(pairs)
+    }
+
+    fn action_type(&self) -> Option<&ActionType<'l>> {
+        if let Some(ref at) = self.action {
+            Some(at)
+        } else {
+            None
+        }
+    }
+    fn action_type_mut(&mut self) -> Option<&mut ActionType<'l>> {
+        if let Some(ref mut at) = self.action {
+            Some(at)
+        } else {
+            None
+        }
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+pub fn show<'lce>(exe: ExeRef<'lce>) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+
+    let mmap = exe.mmap();
+    let num_cmds = HEADER[4].to_usize(mmap)?;
+    let cmds_len = HEADER[5].to_usize(mmap)?;
+    let layout = if exe.bitness() == Some(32) { &LAYOUT32 } else { &LAYOUT64 };
+
+    // The commands before one that is cut off or too short to step over
+    // are listed, the footer says why the rest are not
+    let cmds = macho64::load_commands(mmap, layout);
+    let problem = cmds.error.as_ref().map_or(String::new(), |err| format!(". {}", err));
+    let lines: line::LineVec<'lce> = cmds
+        .into_iter()
+        .map(|(_, cmd_offset, cmd_len)| -> line::LineItem<'lce> {
+            let cmd_slice = mmap.get(cmd_offset..).unwrap_or_default();
+            Box::new(CmdLine::new(exe, cmd_offset, cmd_len, cmd_slice, wsc.scrollable_region))
+        })
+        .collect();
+
+    let footer = format!(
+        "Mach-O Load Commands: {} commands, {} bytes{}",
+        num_cmds, cmds_len, problem
+    );
+
+    details_list::show(exe, lines, "Length Command", &footer, wsc)
+}
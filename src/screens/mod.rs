@@ -2,75 +2,210 @@
 //! Modules for the various screens in the program
 //!
 
+pub mod addr2line;
+pub mod compare;
 pub mod details_list;
+pub mod disassembly;
+pub mod dump;
+pub mod dwarf;
 pub mod file_header;
 pub mod file_list;
+pub mod go_buildinfo;
+pub mod hex_dump;
+pub mod history;
+pub mod load_commands;
+pub mod nt_headers;
+pub mod rust_info;
+pub mod security;
+pub mod strings;
+pub mod symbol_hash;
+pub mod symbol_versions;
 pub mod terminal;
+pub mod unwind;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use crossterm::event::{self, Event, KeyCode};
 use ratatui::layout::{Constraint, Direction, Layout};
+use std::io;
 
 use crate::{
-    screens::terminal::TERMWIN,
+    exe_types::ExeRef,
+    formatter::Enter,
+    screens::{
+        history::{Step, Visit},
+        terminal::TERMWIN,
+    },
     windows::{
         footer::Footer,
-        header::Header,
-        scrollable_region::ScrollableRegion,
+        header::{self, Header},
+        popup,
+        scrollable_region::{ScrollableRegion, MAX_EXPANDED},
+        search::Search,
+        Region,
     },
 };
 
 // ------------------------------------------------------------------------
 
 pub fn show(
+    exe: Option<ExeRef>,
     hdr_win: &mut Header,
     scr_win: &mut ScrollableRegion,
     ftr_win: &mut Footer,
 ) -> Result<()> {
 
-    loop {
+    if dump::active() {
+        return dump::screen(hdr_win, scr_win);
+    }
+
+    run(exe, hdr_win, scr_win, ftr_win, |_, _, _, _| Ok(false))
+}
+
+/// Show a screen until it is closed, handling the search, the navigation
+/// trail and the keys every screen has. The screen's own keys go to
+/// `on_key` first, which returns false for those it has no use for and
+/// may replace the header and footer to show what the key changed.
+pub fn run<'h, 'r, 'f, R: Region<'r>>(
+    exe: Option<ExeRef>,
+    hdr_win: &mut Header<'h>,
+    region: &mut R,
+    ftr_win: &mut Footer<'f>,
+    mut on_key: impl FnMut(KeyCode, &mut Header<'h>, &mut R, &mut Footer<'f>) -> Result<bool>,
+) -> Result<()> {
+
+    let visit = Visit::enter();
+    if let Some(position) = visit.position() {
+        region.set_position(position);
+    }
+    let mut search = Search::default();
+
+    while !visit.closing() {
         {
+            hdr_win.set_trail(history::trail());
+            let prompt = search.prompt();
             let mut terminal = TERMWIN.terminal.lock().unwrap();
             terminal.draw(|f| {
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Length(2),
+                        Constraint::Length(header::HEIGHT),
                         Constraint::Min(0),
                         Constraint::Length(1),
                     ])
                     .split(f.area());
 
                 hdr_win.render(f, chunks[0]);
-                scr_win.render(f, chunks[1]);
-                ftr_win.render(f, chunks[2]);
+                region.render(f, chunks[1]);
+                match prompt {
+                    Some(prompt) => search_footer(ftr_win, prompt).render(f, chunks[2]),
+                    None => ftr_win.render(f, chunks[2]),
+                }
             })?;
         }
 
-        match event::read()? {
-            Event::Key(key) => match key.code {
-                KeyCode::Char('q') | KeyCode::Esc => break,
-                KeyCode::Enter => {
-                    scr_win.key_enter_handler()?;
-                    // Force full redraw after enter (may have opened/closed nested window)
-                    TERMWIN.terminal.lock().unwrap().clear()?;
+        // ratatui uses a new size on the next draw
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if region.lines().is_some_and(|scr_win| search.handle_key(key.code, scr_win)) {
+            continue;
+        }
+        match visit.handle_key(key) {
+            Some(Step::Close) => break,
+            Some(Step::Reopen(code, line, text)) => {
+                match region.lines().and_then(|scr_win| Some((scr_win.find_line(line, &text)?, scr_win))) {
+                    Some((idx, scr_win)) => {
+                        scr_win.select(idx);
+                        open_selected(exe, code, scr_win)?;
+                    }
+                    None => history::forget(),
                 }
-                code @ (KeyCode::Down
-                | KeyCode::Up
-                | KeyCode::PageDown
-                | KeyCode::PageUp
-                | KeyCode::Home
-                | KeyCode::End) => {
-                    scr_win.handle_key(code)?;
+                continue;
+            }
+            Some(Step::Stay) => continue,
+            None => {}
+        }
+        if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+            break;
+        }
+        if on_key(key.code, hdr_win, region, ftr_win)? {
+            continue;
+        }
+        match visit.jump(key.code) {
+            Some(Step::Close) => break,
+            Some(_) => continue,
+            None => {}
+        }
+        let Some(scr_win) = region.lines() else {
+            continue;
+        };
+        match key.code {
+            code @ (KeyCode::Enter | KeyCode::Char('x')) => open_selected(exe, code, scr_win)?,
+            KeyCode::Char('+') => {
+                if !scr_win.expand_all() {
+                    popup::error_window(&too_many_entries());
                 }
-                _ => {}
-            },
-            Event::Resize(_, _) => {
-                // ratatui automatically uses the new size on next draw()
             }
-            _ => {}
+            KeyCode::Char('-') => scr_win.collapse_all(),
+            code => scr_win.handle_key(code)?,
         }
     }
 
+    visit.leave(region.position());
+    Ok(())
+}
+
+/// Open the selected line with enter, or its bytes in the hex dump of the
+/// file with x. In a list of files x dumps the selected file, and it does
+/// nothing where there is no file, as in the comparison of two.
+pub fn open_selected(exe: Option<ExeRef>, code: KeyCode, scr_win: &mut ScrollableRegion) -> Result<()> {
+    let label = match code {
+        KeyCode::Enter => scr_win.selected_label(),
+        _ => match exe.or_else(|| scr_win.selected_exe()) {
+            Some(exe) if !exe.is_empty() => String::from("Hex Dump"),
+            _ => return Ok(()),
+        },
+    };
+    let (line, text) = (scr_win.selected(), scr_win.line_text(scr_win.selected()));
+    carry_on(history::open(&label, code, line, &text, || match code {
+        KeyCode::Enter => scr_win.key_enter_handler(),
+        _ => match exe {
+            Some(exe) => hex_dump::show_range(exe, scr_win.selected_file_range()),
+            None => scr_win.selected_exe().map_or(Ok(()), hex_dump::show),
+        },
+    }))?;
+    // Force full redraw (may have opened/closed nested window)
+    TERMWIN.terminal.lock().unwrap().clear()?;
     Ok(())
 }
+
+/// Why some lines were left collapsed by expanding them all
+pub fn too_many_entries() -> anyhow::Error {
+    anyhow!("Too many entries to expand them all, stopped at {} lines", MAX_EXPANDED)
+}
+
+/// Show why a screen could not be opened and go on, so a malformed file
+/// does not end the session. Failures of the terminal are passed on.
+pub fn carry_on(rc: Result<()>) -> Result<()> {
+    match rc {
+        Err(err) if err.downcast_ref::<io::Error>().is_none() => {
+            popup::error_window(&err);
+            Ok(())
+        }
+        rc => rc,
+    }
+}
+
+/// Open the screen a field leads to when enter is pressed on it
+pub fn enter_field(enter: Enter, exe: ExeRef) -> Result<()> {
+    match enter {
+        Enter::EntryPoint => disassembly::show_entry(exe),
+        Enter::LoadCommands => load_commands::show(exe),
+        Enter::NtHeaders => nt_headers::show(exe),
+    }
+}
+
+/// Footer showing the search being typed, in the colors of `ftr_win`
+pub fn search_footer<'f>(ftr_win: &'f Footer, prompt: String) -> Footer<'f> {
+    Footer::new(ftr_win.window_colors(), Box::new(move |_sc: usize| (0, prompt.clone())))
+}
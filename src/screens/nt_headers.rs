@@ -0,0 +1,32 @@
+//!
+//! The NT headers of a PE image with the optional header after them
+//!
+
+use anyhow::{bail, Result};
+
+use crate::{
+    color::Colors,
+    exe_types::ExeRef,
+    screens::details_list,
+    windows::details,
+};
+
+// ------------------------------------------------------------------------
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+
+    let structures = exe.structures();
+    let find = |name| structures.iter().find(|s| s.name == name).map(|s| (s.data, s.map));
+    let Some((nt_range, nt_map)) = find("NT Headers") else {
+        bail!("No NT headers in {}", exe.filename().display());
+    };
+    let Some((opt_range, opt_map)) = find("Optional Header") else {
+        bail!("Unknown optional header magic at {:#x}", nt_range.1);
+    };
+
+    let mut lines = details::to_lines(exe, nt_range, nt_map, wsc.scrollable_region);
+    lines.extend(details::to_lines(exe, opt_range, opt_map, wsc.scrollable_region));
+
+    details_list::show(exe, lines, "NT Headers", &format!("NT Headers at {:#x}", nt_range.0), wsc)
+}
@@ -0,0 +1,131 @@
+//!
+//! Show the compiler and crate information of a Rust executable
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::Colors,
+    decoders::rust_info::{self, Package, RustInfo},
+    exe_types::ExeRef,
+    screens::details_list,
+    windows::{
+        line::{ActionType, LineVec},
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const NAME_LEN: usize = "Auditable Packages".len();
+
+pub fn is_present(exe: ExeRef) -> bool {
+    rust_info::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let info = match rust_info::decode(exe) {
+        Ok(info) => info,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let info_ref = &info;
+
+    let mut lines: LineVec = Vec::from([
+        TextLine::field("Compiler", NAME_LEN, info.compiler.as_deref().unwrap_or("Unknown"), wc).boxed(),
+        TextLine::field("Library Commit", NAME_LEN, info.commit.as_deref().unwrap_or("Unknown"), wc).boxed(),
+        TextLine::field("Crates", NAME_LEN, &info.crates.len().to_string(), wc)
+            .action(ActionType::NewWindow(Box::new(move || show_crates(exe, info_ref))))
+            .boxed(),
+    ]);
+
+    lines.push(match &info.auditable {
+        Some(packages) => TextLine::field("Auditable Packages", NAME_LEN, &packages.len().to_string(), wc)
+            .action(ActionType::NewWindow(Box::new(move || show_packages(exe, packages))))
+            .boxed(),
+        None => TextLine::field("Auditable Packages", NAME_LEN, "No .dep-v0 section", wc).boxed(),
+    });
+
+    lines.push(
+        TextLine::field("Panic Locations", NAME_LEN, &info.panic_locations.len().to_string(), wc)
+            .action(ActionType::NewWindow(Box::new(move || show_locations(exe, info_ref))))
+            .boxed(),
+    );
+
+    details_list::show(exe, lines, "Rust Information", &exe.filename().display().to_string(), wsc)
+}
+
+// ------------------------------------------------------------------------
+
+fn show_crates(exe: ExeRef, info: &RustInfo) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let name_len = info.crates.iter().map(|c| c.name.len()).max().unwrap_or(0).max(5);
+    let ver_len = info.crates.iter().map(|c| c.version.len()).max().unwrap_or(0).max(7);
+
+    let lines: LineVec = info.crates
+        .iter()
+        .map(|c| TextLine::new(Vec::from([
+                (Some(wc.text), format!("{:name_len$} ", c.name)),
+                (Some(wc.value), format!("{:ver_len$} ", c.version)),
+                (Some(wc.text), c.source.into()),
+            ])).boxed())
+        .collect();
+
+    let title = format!("{:name_len$} {:ver_len$} Source", "Crate", "Version");
+    let footer = format!("{} crates from embedded source paths", info.crates.len());
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
+fn show_packages(exe: ExeRef, packages: &[Package]) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let name_len = packages.iter().map(|p| p.name.len()).max().unwrap_or(0).max(7);
+    let ver_len = packages.iter().map(|p| p.version.len()).max().unwrap_or(0).max(7);
+
+    let lines: LineVec = packages
+        .iter()
+        .map(|p| TextLine::new(Vec::from([
+                (Some(wc.text), format!("{}{:name_len$} ", if p.root { '*' } else { ' ' }, p.name)),
+                (Some(wc.value), format!("{:ver_len$} ", p.version)),
+                (Some(wc.text), format!("{:11} {:7} ", p.source, p.kind)),
+                (Some(wc.value), dependency_names(p, packages)),
+            ])).boxed())
+        .collect();
+
+    let title = format!(" {:name_len$} {:ver_len$} {:11} {:7} Dependencies",
+        "Package", "Version", "Source", "Kind");
+    let footer = format!("{} packages from .dep-v0, * is the root", packages.len());
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
+fn dependency_names(package: &Package, packages: &[Package]) -> String {
+    package.dependencies
+        .iter()
+        .map(|idx| packages.get(*idx).map_or("???", |p| p.name.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn show_locations(exe: ExeRef, info: &RustInfo) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+
+    let lines: LineVec = info.panic_locations
+        .iter()
+        .map(|p| TextLine::text(p, wsc.scrollable_region).boxed())
+        .collect();
+
+    let footer = format!("{} source files", info.panic_locations.len());
+
+    details_list::show(exe, lines, "Panic Locations", &footer, wsc)
+}
@@ -0,0 +1,45 @@
+//!
+//! Show the binary hardening report
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::Colors,
+    decoders::security::{self, Level},
+    exe_types::ExeRef,
+    screens::details_list,
+    windows::{line::LineVec, popup, text::TextLine},
+};
+
+// ------------------------------------------------------------------------
+
+const NAME_LEN: usize = "NX Stack / Heap".len() + 1;
+
+pub fn is_present(exe: ExeRef) -> bool {
+    security::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let checks = match security::checks(exe) {
+        Ok(checks) => checks,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let lines: LineVec = checks
+        .iter()
+        .map(|check| TextLine::field(check.name, NAME_LEN, &check.value, wc).boxed())
+        .collect();
+
+    let enabled = checks.iter().filter(|c| c.level == Level::Enabled).count();
+    let scored = checks.iter().filter(|c| c.level != Level::Info && !c.short.is_empty()).count();
+    let footer = format!("{} of {} protections enabled", enabled, scored);
+
+    details_list::show(exe, lines, "Security", &footer, wsc)
+}
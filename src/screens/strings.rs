@@ -0,0 +1,183 @@
+//!
+//! Show the strings in the file, filtered by text and encoding
+//!
+
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use std::ops::Range;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::strings::{self, Encoding, Hit},
+    exe_types::{ExeRef, Section},
+    formatter::center_in,
+    screens::{self, terminal::TERMWIN},
+    windows::{
+        footer::Footer,
+        header::Header,
+        line::{Line, LineItem, LineVec, PairVec},
+        popup,
+        scrollable_region::ScrollableRegion,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const SECTION_LEN: usize = 16;
+const ENCODING_LEN: usize = "UTF-16LE".len();
+
+pub fn is_present(exe: ExeRef) -> bool {
+    !exe.is_empty()
+}
+
+/// What is shown of the strings found
+struct Settings {
+    min_len: usize,
+    /// Case insensitive text to look for
+    filter: String,
+    encoding: Option<Encoding>,
+}
+
+impl Settings {
+    fn matches(&self, hit: &Hit) -> bool {
+        self.encoding.is_none_or(|e| e == hit.encoding)
+            && (self.filter.is_empty() || hit.text.to_lowercase().contains(&self.filter))
+    }
+
+    /// Next encoding shown, all of them after the last
+    fn next_encoding(&mut self) {
+        self.encoding = match self.encoding {
+            None => Some(Encoding::ALL[0]),
+            Some(e) => Encoding::ALL.iter().skip_while(|&&a| a != e).nth(1).copied(),
+        };
+    }
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let sections = exe.sections();
+    let addr_len = sections
+        .iter()
+        .map(|s| format!("{:x}", s.addr.saturating_add(s.size)).len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let offset_len = format!("{:x}", exe.len()).len().max(8);
+
+    let mut settings = Settings { min_len: strings::DEFAULT_MIN_LEN, filter: String::new(), encoding: None };
+    let mut hits = strings::scan(exe.mmap(), settings.min_len);
+    let to_lines = |hits: &[Hit], settings: &Settings| -> LineVec<'static> {
+        hits.iter()
+            .filter(|hit| settings.matches(hit))
+            .map(|hit| -> LineItem<'static> {
+                Box::new(StringLine::new(hit, &sections, offset_len, addr_len, wc))
+            })
+            .collect()
+    };
+    let lines = to_lines(&hits, &settings);
+    let footer_text = |shown: usize, hits: &[Hit], settings: &Settings| format!(
+        "{} of {} strings, {} or more characters, {}{}",
+        shown,
+        hits.len(),
+        settings.min_len,
+        settings.encoding.map_or("all encodings".into(), |e| e.to_string()),
+        if settings.filter.is_empty() { String::new() } else { format!(", matching \"{}\"", settings.filter) },
+    );
+    let footer = |text: String| Footer::new(&wsc.footer, Box::new(move |sc: usize| center_in(sc, &text)));
+    let mut ftr_win = footer(footer_text(lines.len(), &hits, &settings));
+    let mut scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+
+    let hdr_text = format!(
+        "{:ol$}  {:al$}  {:sl$}  {:el$}  String",
+        "Offset", "Address", "Section", "Encoding",
+        ol = offset_len, al = addr_len, sl = SECTION_LEN, el = ENCODING_LEN,
+    );
+    let mut hdr_win = Header::new(&wsc.header, Box::new(move |_sc: usize| (1, hdr_text.clone())));
+
+    if screens::dump::active() {
+        return screens::dump::screen(&hdr_win, &mut scr_win);
+    }
+
+    screens::run(Some(exe), &mut hdr_win, &mut scr_win, &mut ftr_win, |code, _, scr_win, ftr_win| {
+        match code {
+            KeyCode::Char('f') => {
+                if let Some(input) = popup::prompt("Filter Strings", "Containing") {
+                    settings.filter = input.trim().to_lowercase();
+                }
+                TERMWIN.terminal.lock().unwrap().clear()?;
+            }
+            KeyCode::Char('m') => {
+                if let Some(input) = popup::prompt("Minimum Length", "Characters") {
+                    match input.trim().parse::<usize>() {
+                        Ok(min_len) if min_len > 0 => {
+                            settings.min_len = min_len;
+                            hits = strings::scan(exe.mmap(), min_len);
+                        }
+                        _ => popup::error_window(&anyhow!("Invalid length: {}", input)),
+                    }
+                }
+                TERMWIN.terminal.lock().unwrap().clear()?;
+            }
+            KeyCode::Char('e') => settings.next_encoding(),
+            // The string's bytes in the hex dump
+            KeyCode::Enter => {
+                screens::open_selected(Some(exe), KeyCode::Char('x'), scr_win)?;
+                return Ok(true);
+            }
+            _ => return Ok(false),
+        }
+
+        let lines = to_lines(&hits, &settings);
+        *ftr_win = footer(footer_text(lines.len(), &hits, &settings));
+        *scr_win = ScrollableRegion::new(&wsc.scrollable_region, lines);
+        Ok(true)
+    })
+}
+
+// ------------------------------------------------------------------------
+/// Line for a string, with its location in the file and image
+struct StringLine {
+    location: String,
+    encoding: Encoding,
+    text: String,
+    range: Range<usize>,
+    wc: WindowColors,
+}
+
+impl StringLine {
+    fn new(hit: &Hit, sections: &[Section], offset_len: usize, addr_len: usize, wc: WindowColors) -> Self {
+        let (section, addr) = match strings::locate(sections, hit.offset) {
+            Some((section, addr)) => (
+                section.full_name(),
+                addr.map(|addr| format!("{:0al$x}", addr, al = addr_len)).unwrap_or_default(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        Self {
+            location: format!(
+                "{:0ol$x}  {:al$}  {:sl$.sl$}  ",
+                hit.offset, addr, section,
+                ol = offset_len, al = addr_len, sl = SECTION_LEN,
+            ),
+            encoding: hit.encoding,
+            text: hit.text.replace('\t', "\\t"),
+            range: hit.offset..hit.offset + hit.len,
+            wc,
+        }
+    }
+}
+
+impl<'l> Line<'l> for StringLine {
+    fn as_pairs(&self, _max_len: usize) -> Result<PairVec> {
+        Ok(Vec::from([
+            (Some(self.wc.text), self.location.clone()),
+            (Some(self.wc.text), format!("{:el$}  ", self.encoding.to_string(), el = ENCODING_LEN)),
+            (Some(self.wc.value), self.text.clone()),
+        ]))
+    }
+
+    fn file_range(&self) -> Option<Range<usize>> {
+        Some(self.range.clone())
+    }
+}
@@ -0,0 +1,186 @@
+//!
+//! Show the symbol hash tables and look symbols up through them
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::{
+        symbol_hash::{self, GnuHash, HashTables, Lookup, Probe},
+        symbol_versions::{self, SymbolVersions},
+    },
+    exe_types::{ExeRef, Symbol},
+    screens::details_list,
+    windows::{
+        line::{ActionType, LineItem, LineVec},
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const INDENT: &str = "    ";
+const NAME_LEN: usize = 20;
+
+pub fn is_present(exe: ExeRef) -> bool {
+    symbol_hash::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let tables = match symbol_hash::decode(exe) {
+        Ok(tables) => tables,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+    let symbols: Vec<Symbol> = exe.symbols().into_iter().filter(|s| s.dynamic).collect();
+    let versions = symbol_versions::decode(exe).ok().filter(|v| !v.versym.is_empty());
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let tables_ref = &tables;
+    let symbols_ref = &symbols;
+    let versions_ref = versions.as_ref();
+
+    let mut lines = LineVec::from([
+        TextLine::text("Look Up a Symbol", wc)
+            .action(ActionType::NewWindow(Box::new(move || show_lookup(exe, tables_ref, symbols_ref, versions_ref))))
+            .boxed(),
+    ]);
+
+    if let Some(gnu) = &tables.gnu {
+        lines.push(TextLine::text(".gnu.hash", wc).boxed());
+        lines.extend(gnu_fields(gnu).iter().map(|(name, value)| field(name, value, wc)));
+        histogram_lines(&mut lines, &gnu.chain_lengths(), wc);
+    }
+    if let Some(sysv) = &tables.sysv {
+        lines.push(TextLine::text(".hash", wc).boxed());
+        lines.push(field("Buckets", &sysv.buckets.len().to_string(), wc));
+        lines.push(field("Chains", &sysv.chains.len().to_string(), wc));
+        histogram_lines(&mut lines, &sysv.chain_lengths(), wc);
+    }
+
+    let footer = format!("{} dynamic symbols", symbols.len());
+    details_list::show(exe, lines, "Symbol Hash Tables", &footer, wsc)
+}
+
+// ------------------------------------------------------------------------
+
+fn field<'l>(name: &str, value: &str, wc: WindowColors) -> LineItem<'l> {
+    TextLine::field(&format!("{INDENT}{name}"), NAME_LEN, value, wc).boxed()
+}
+
+fn gnu_fields(gnu: &GnuHash) -> Vec<(&'static str, String)> {
+    let total = gnu.bloom.len() as u32 * gnu.bloom_bits;
+    let set = gnu.bloom_bits_set();
+    Vec::from([
+        ("Buckets", gnu.buckets.len().to_string()),
+        ("Symbol Offset", gnu.symoffset.to_string()),
+        ("Hashed Symbols", gnu.chains.len().to_string()),
+        ("Bloom Words", format!("{} of {} bits", gnu.bloom.len(), gnu.bloom_bits)),
+        ("Bloom Shift", gnu.bloom_shift.to_string()),
+        ("Bloom Bits Set", format!("{} of {}, {:.1}%", set, total, percent(set as usize, total as usize))),
+    ])
+}
+
+/// Chain length histogram in the layout of `readelf --histogram`
+fn histogram_lines(lines: &mut LineVec, lengths: &[usize], wc: WindowColors) {
+    let counts = symbol_hash::histogram(lengths);
+    let total: usize = lengths.iter().sum();
+    let longest = counts.len() - 1;
+    let average = total as f64 / lengths.len().max(1) as f64;
+
+    lines.push(field("Longest Chain", &longest.to_string(), wc));
+    lines.push(field("Average Chain", &format!("{:.2}", average), wc));
+    lines.push(TextLine::text(&format!("{INDENT}{:>6} {:>8} {:>11} {:>9}",
+        "Length", "Number", "% of total", "Coverage"), wc).boxed());
+
+    let mut covered = 0;
+    for (len, &count) in counts.iter().enumerate() {
+        covered += len * count;
+        lines.push(TextLine::new(Vec::from([
+            (Some(wc.text), format!("{INDENT}{:>6} ", len)),
+            (Some(wc.value), format!("{:>8} {:>10.1}% {:>8.1}%",
+                count, percent(count, lengths.len()), percent(covered, total))),
+        ])).boxed());
+    }
+}
+
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 * 100.0 / total as f64 }
+}
+
+fn show_lookup(exe: ExeRef, tables: &HashTables, symbols: &[Symbol], versions: Option<&SymbolVersions>) -> Result<()> {
+    let Some(name) = popup::prompt("Symbol Lookup", "Name[@version]") else {
+        return Ok(());
+    };
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let mut lines = LineVec::new();
+    let mut found = None;
+    if let Some(gnu) = &tables.gnu {
+        let lookup = gnu.lookup(&name, symbols, versions);
+        lines.push(TextLine::text(".gnu.hash", wc).boxed());
+        if let Some((word, bit1, bit2, pass)) = lookup.bloom {
+            lines.push(field("Bloom Filter", &format!("word {}, bits {} and {}, {}",
+                word, bit1, bit2, if pass { "may be present" } else { "not present" }), wc));
+        }
+        lookup_lines(&mut lines, &lookup, symbols, versions, wc);
+        found = found.or(lookup.found);
+    }
+    if let Some(sysv) = &tables.sysv {
+        let lookup = sysv.lookup(&name, symbols, versions);
+        lines.push(TextLine::text(".hash", wc).boxed());
+        lookup_lines(&mut lines, &lookup, symbols, versions, wc);
+        found = found.or(lookup.found);
+    }
+
+    let footer = match found.and_then(|index| symbols.get(index)) {
+        Some(symbol) => format!("Found at index {}, value {:#x}", symbol.index, symbol.addr),
+        None => "Not found".into(),
+    };
+    details_list::show(exe, lines, &format!("Lookup of {}", name), &footer, wsc)
+}
+
+fn lookup_lines(
+    lines: &mut LineVec,
+    lookup: &Lookup,
+    symbols: &[Symbol],
+    versions: Option<&SymbolVersions>,
+    wc: WindowColors,
+) {
+    lines.push(field("Hash", &format!("{:#010x}", lookup.hash), wc));
+    if lookup.bloom.is_some_and(|b| !b.3) {
+        lines.push(field("Result", "Not found, rejected by the bloom filter", wc));
+        return;
+    }
+    lines.push(field("Bucket", &lookup.bucket.to_string(), wc));
+    lines.push(field("Comparisons", &lookup.probes.len().to_string(), wc));
+
+    for &(index, probe) in &lookup.probes {
+        let name = symbols.get(index).map_or_else(
+            || "???".into(),
+            |s| versions.map_or_else(|| s.name.clone(), |v| v.versioned_name(s)),
+        );
+        lines.push(TextLine::new(Vec::from([
+            (Some(wc.text), format!("{INDENT}{INDENT}{:5} ", index)),
+            (Some(wc.value), name),
+            (Some(wc.text), format!(", {}", match probe {
+                Probe::HashDiffers => "hash differs",
+                Probe::NameDiffers => "name differs",
+                Probe::Undefined => "undefined, skipped",
+                Probe::Local => "local, skipped",
+                Probe::VersionDiffers => "version differs",
+                Probe::Found => "found",
+            })),
+        ])).boxed());
+    }
+    if lookup.found.is_none() {
+        lines.push(field("Result", "Not found", wc));
+    }
+}
@@ -0,0 +1,120 @@
+//!
+//! Show the GNU symbol version requirements and definitions
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::symbol_versions::{self, Library, SymbolVersions, VER_FLG_BASE, VER_FLG_WEAK},
+    exe_types::{ExeRef, Symbol, SymbolKind},
+    screens::details_list,
+    windows::{
+        line::{ActionType, LineItem, LineVec},
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const INDENT: &str = "    ";
+
+pub fn is_present(exe: ExeRef) -> bool {
+    symbol_versions::is_present(exe)
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let versions = match symbol_versions::decode(exe) {
+        Ok(versions) => versions,
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+    let symbols: Vec<Symbol> = exe.symbols().into_iter().filter(|s| s.dynamic).collect();
+
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let versions_ref = &versions;
+    let symbols_ref = &symbols;
+
+    let highest = versions.highest_required();
+    let name_len = highest.iter().map(|h| h.0.len()).max().unwrap_or(0);
+
+    let mut lines: LineVec = Vec::from([TextLine::text("Highest Required Versions", wc).boxed()]);
+    lines.extend(highest.iter().map(|(file, version)| {
+        TextLine::field(&format!("{INDENT}{file}"), name_len + INDENT.len(), version, wc).boxed()
+    }));
+
+    lines.push(TextLine::text(&format!("Version Requirements, {} libraries", versions.needs.len()), wc).boxed());
+    lines.extend(versions.needs.iter().map(|library| library_line(library, wc)));
+
+    lines.push(TextLine::text(&format!("Version Definitions, {} versions", versions.defs.len()), wc).boxed());
+    lines.extend(versions.defs.iter().map(|def| TextLine::new(Vec::from([
+            (Some(wc.text), format!("{INDENT}{:5} ", def.index)),
+            (Some(wc.value), def.names.join(" <- ")),
+            (Some(wc.text), if def.flags & VER_FLG_BASE != 0 { " (base)".into() } else { String::new() }),
+        ])).boxed()));
+
+    lines.push(
+        TextLine::text(&format!("Dynamic Symbols, {} entries", symbols.len()), wc)
+            .action(ActionType::NewWindow(Box::new(move || show_symbols(exe, versions_ref, symbols_ref))))
+            .boxed(),
+    );
+
+    details_list::show(exe, lines, "Symbol Versions", &exe.filename().display().to_string(), wsc)
+}
+
+// ------------------------------------------------------------------------
+
+fn library_line<'l>(library: &'l Library, wc: WindowColors) -> LineItem<'l> {
+    TextLine::new(Vec::from([
+            (Some(wc.text), INDENT.into()),
+            (Some(wc.value), library.file.clone()),
+            (Some(wc.text), format!(", {} versions", library.versions.len())),
+        ]))
+        .action(ActionType::Expandable(
+            Box::new(move || library.versions
+                .iter()
+                .map(|v| TextLine::new(Vec::from([
+                        (Some(wc.text), format!("{INDENT}{:5} ", v.index)),
+                        (Some(wc.value), v.name.clone()),
+                        (Some(wc.text), if v.flags & VER_FLG_WEAK != 0 { " (weak)".into() } else { String::new() }),
+                    ])).boxed())
+                .collect()),
+            0,
+            INDENT.len(),
+        ))
+        .boxed()
+}
+
+fn show_symbols(exe: ExeRef, versions: &SymbolVersions, symbols: &[Symbol]) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let lines: LineVec = symbols
+        .iter()
+        .map(|symbol| TextLine::new(Vec::from([
+                (Some(wc.text), format!("{:5} ", symbol.index)),
+                (Some(wc.value), format!("{:#018x} ", symbol.addr)),
+                (Some(wc.text), format!("{:8} {:6} ", kind_name(symbol), if symbol.global { "global" } else { "local" })),
+                (Some(wc.value), versions.versioned_name(symbol)),
+            ])).boxed())
+        .collect();
+
+    let title = format!("{:5} {:18} {:8} {:6} Name", "Index", "Value", "Type", "Bind");
+    let undefined = symbols.iter().filter(|s| !s.defined && !s.name.is_empty()).count();
+    let footer = format!("{} symbols, {} imported", symbols.len(), undefined);
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
+fn kind_name(symbol: &Symbol) -> &'static str {
+    match (symbol.kind, symbol.defined) {
+        (_, false) if !symbol.name.is_empty() => "import",
+        (SymbolKind::Function, _) => "function",
+        (SymbolKind::Object, _) => "object",
+        (SymbolKind::Other, _) => "",
+    }
+}
@@ -0,0 +1,169 @@
+//!
+//! Show the exception handling frames and compact unwind information
+//!
+
+use anyhow::Result;
+
+use crate::{
+    color::{Colors, WindowColors},
+    decoders::{
+        eh_frame::{self, EhFrame, EhFrameHdr, Entry, RegisterSet},
+        unwind_info::{self, UnwindInfo},
+    },
+    exe_types::ExeRef,
+    screens::details_list,
+    windows::{
+        line::{ActionType, LineItem, LineVec},
+        popup,
+        text::TextLine,
+    },
+};
+
+// ------------------------------------------------------------------------
+
+const INDENT: usize = 4;
+
+pub fn is_present(exe: ExeRef) -> bool {
+    EhFrame::load(exe).is_some() || unwind_info::decode(exe).is_some()
+}
+
+pub fn show(exe: ExeRef) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+    let regs = RegisterSet::from_exe(exe);
+
+    let frame = EhFrame::load(exe);
+    let entries = match frame.as_ref().map(|f| f.entries()).transpose() {
+        Ok(entries) => entries.unwrap_or_default(),
+        Err(err) => {
+            popup::error_window(&err);
+            return Ok(());
+        }
+    };
+    let hdr = eh_frame::eh_frame_hdr(exe).and_then(|h| h.map_err(|err| popup::error_window(&err)).ok());
+    let compact = unwind_info::decode(exe).and_then(|u| u.map_err(|err| popup::error_window(&err)).ok());
+
+    let mut lines = LineVec::new();
+    if let Some(hdr) = &hdr {
+        lines.push(
+            TextLine::text(&format!(".eh_frame_hdr search table, {} entries", hdr.table.len()), wc)
+                .action(ActionType::NewWindow(Box::new(move || show_hdr(exe, hdr))))
+                .boxed(),
+        );
+    }
+    if let Some(compact) = &compact {
+        lines.push(
+            TextLine::text(&format!("__unwind_info compact encodings, {} functions", compact.entries.len()), wc)
+                .action(ActionType::NewWindow(Box::new(move || show_compact(exe, compact, regs))))
+                .boxed(),
+        );
+    }
+    if let Some(frame) = &frame {
+        let entries = &entries;
+        lines.extend(entries.iter().map(|entry| entry_line(frame, entries, entry, regs, wc)));
+    }
+
+    let fdes = entries.iter().filter(|e| matches!(e, Entry::Fde(_))).count();
+    let footer = format!("{} CIEs, {} FDEs", entries.len() - fdes, fdes);
+
+    details_list::show(exe, lines, "Unwind Information", &footer, wsc)
+}
+
+// ------------------------------------------------------------------------
+
+fn entry_line<'l>(
+    frame: &'l EhFrame<'l>,
+    entries: &'l [Entry<'l>],
+    entry: &'l Entry<'l>,
+    regs: RegisterSet,
+    wc: WindowColors,
+) -> LineItem<'l> {
+    let pairs = match entry {
+        Entry::Cie(cie) => Vec::from([
+            (Some(wc.text), format!("{:#010x} CIE ", cie.offset)),
+            (Some(wc.value), format!("\"{}\"", cie.augmentation)),
+            (Some(wc.text), format!(" version {} code align {} data align {} return {}{}",
+                cie.version, cie.code_align, cie.data_align, regs.name(cie.ra_register),
+                if cie.signal_frame { " signal frame" } else { "" })),
+        ]),
+        Entry::Fde(fde) => Vec::from([
+            (Some(wc.text), format!("{:#010x} FDE ", fde.offset)),
+            (Some(wc.value), format!("{:#x}..{:#x}", fde.pc_begin, fde.pc_begin.wrapping_add(fde.pc_range))),
+            (Some(wc.text), format!(" cie {:#x}{}", fde.cie_offset,
+                fde.lsda.map(|l| format!(" lsda {:#x}", l)).unwrap_or_default())),
+        ]),
+    };
+
+    TextLine::new(pairs)
+        .action(ActionType::Expandable(
+            Box::new(move || instruction_lines(frame, entries, entry, regs, wc)),
+            0,
+            INDENT,
+        ))
+        .boxed()
+}
+
+fn instruction_lines<'l>(
+    frame: &EhFrame,
+    entries: &[Entry],
+    entry: &Entry,
+    regs: RegisterSet,
+    wc: WindowColors,
+) -> LineVec<'l> {
+    let find_cie = |offset| entries.iter().find_map(|e| match e {
+        Entry::Cie(cie) if cie.offset == offset => Some(cie),
+        _ => None,
+    });
+    let decoded = match entry {
+        Entry::Cie(cie) => eh_frame::instructions(frame, cie, cie.instructions, 0, regs),
+        Entry::Fde(fde) => match find_cie(fde.cie_offset) {
+            Some(cie) => eh_frame::instructions(frame, cie, fde.instructions, fde.pc_begin, regs),
+            None => Ok(Vec::from([format!("CIE at {:#x} not found", fde.cie_offset)])),
+        },
+    };
+
+    let text = decoded.unwrap_or_else(|err| Vec::from([err.to_string()]));
+    text.iter()
+        .map(|t| TextLine::new(Vec::from([(Some(wc.value), t.clone())])).boxed())
+        .collect()
+}
+
+fn show_hdr(exe: ExeRef, hdr: &EhFrameHdr) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let lines: LineVec = hdr.table
+        .iter()
+        .map(|(location, fde)| TextLine::new(Vec::from([
+                (Some(wc.value), format!("{:#018x} ", location)),
+                (Some(wc.text), format!("{:#018x}", fde)),
+            ])).boxed())
+        .collect();
+
+    let title = format!("{:18} {:18}", "Initial Location", "FDE Address");
+    let footer = format!(".eh_frame at {:#x}, {} entries",
+        hdr.eh_frame_ptr.unwrap_or(0), hdr.table.len());
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
+fn show_compact(exe: ExeRef, info: &UnwindInfo, regs: RegisterSet) -> Result<()> {
+    let wsc = Colors::global().get_window_set_colors("list")?;
+    let wc = wsc.scrollable_region;
+
+    let lines: LineVec = info.entries
+        .iter()
+        .map(|entry| TextLine::new(Vec::from([
+                (Some(wc.value), format!("{:#018x} ", entry.addr)),
+                (Some(wc.text), format!("{:#010x} ", entry.encoding)),
+                (Some(wc.value), unwind_info::describe(entry.encoding, regs)),
+            ])).boxed())
+        .collect();
+
+    let title = format!("{:18} {:10} Description", "Function", "Encoding");
+    let footer = format!("{} functions, {} common encodings, {} personalities",
+        info.entries.len(), info.common_encodings.len(), info.personalities.len());
+
+    details_list::show(exe, lines, &title, &footer, wsc)
+}
+
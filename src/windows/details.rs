@@ -3,7 +3,9 @@
 //!
 
 use anyhow::Result;
-use crate::{color, exe_types, formatter};
+use std::ops::Range;
+
+use crate::{color, exe_types, formatter, screens};
 use super::line;
 
 // ------------------------------------------------------------------------
@@ -24,11 +26,9 @@ pub fn to_lines<'l>(
                 field_def,
                 wc,
                 max_text_len: map.max_text_len,
-                action: if field_def.enter_fn.is_some() {
-                    Some(line::ActionType::NewWindow(Box::new(|| field_def.enter_fn.unwrap()(exe))))
-                } else {
-                    None
-                },
+                action: field_def.enter.map(|enter| -> line::ActionType<'l> {
+                    line::ActionType::NewWindow(Box::new(move || screens::enter_field(enter, exe)))
+                }),
             })
         })
         .collect()
@@ -47,7 +47,9 @@ struct DetailLine<'dl> {
 
 impl<'l> line::Line<'l> for DetailLine<'l> {
     fn as_pairs(&self, _max_len: usize) -> Result<line::PairVec> {
-        let data_slice = &self.exe.mmap()[self.data.0..self.data.1];
+        // Fields past the end of a truncated file show as out of bounds
+        let mmap = self.exe.mmap();
+        let data_slice = mmap.get(self.data.0..self.data.1.min(mmap.len())).unwrap_or_default();
 
         let mut pairs = Vec::from([
             (
@@ -60,7 +62,7 @@ impl<'l> line::Line<'l> for DetailLine<'l> {
             ),
             (
                 Some(self.wc.value),
-                format!(" {}", (self.field_def.to_string(data_slice)?)),
+                format!(" {}", self.field_def.to_string(data_slice)),
             ),
         ]);
 
@@ -72,10 +74,15 @@ impl<'l> line::Line<'l> for DetailLine<'l> {
     }
 
     fn enter_fn(&self) -> Option<line::EnterFn<'l>> {
-        match self.field_def.enter_fn {
-            Some(_) => Some(Box::new(|_sr| self.field_def.enter_fn.unwrap()(self.exe))),
-            None => None,
-        }
+        let exe = self.exe;
+        self.field_def
+            .enter
+            .map(|enter| -> line::EnterFn<'l> { Box::new(move |_sr| screens::enter_field(enter, exe)) })
+    }
+
+    fn file_range(&self) -> Option<Range<usize>> {
+        let (start, end) = self.field_def.block_range(self.data.1 - self.data.0);
+        Some(self.data.0 + start..self.data.0 + end)
     }
 
     fn action_type(&self) -> Option<&line::ActionType<'l>> {
@@ -29,6 +29,10 @@ impl Footer<'_> {
         Footer { window_colors, line_fn }
     }
 
+    pub fn window_colors(&self) -> &WindowColors {
+        self.window_colors
+    }
+
     // --------------------------------------------------------------------
 
     pub fn render(&self, f: &mut Frame, area: Rect) {
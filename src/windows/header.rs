@@ -15,6 +15,9 @@ use crate::color::WindowColors;
 const NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Rows taken by the header
+pub const HEIGHT: u16 = 3;
+
 // ------------------------------------------------------------------------
 
 type LineFn<'a> = Box<dyn Fn(usize) -> (i32, String) + 'a>;
@@ -23,19 +26,30 @@ type LineFn<'a> = Box<dyn Fn(usize) -> (i32, String) + 'a>;
 
 pub struct Header<'a> {
     window_colors: &'a WindowColors,
-    line2_fn: LineFn<'a>,
+    /// Screens leading to this one, for the second line
+    trail: String,
+    line3_fn: LineFn<'a>,
 }
 
 impl Header<'_> {
 
-    /// Create a header using `window_colors`, building the second
-    /// line using `line2_fn`
-
+    /// Create a header using `window_colors`, building the last line
+    /// using `line3_fn`
     pub fn new<'a>(
         window_colors: &'a WindowColors,
-        line2_fn: LineFn<'a>,
+        line3_fn: LineFn<'a>,
     ) -> Header<'a> {
-        Header { window_colors, line2_fn }
+        Header { window_colors, trail: String::new(), line3_fn }
+    }
+
+    /// Set the breadcrumb trail shown on the second line
+    pub fn set_trail(&mut self, trail: String) {
+        self.trail = trail;
+    }
+
+    /// Text of the last line, without the padding placing it
+    pub fn heading(&self, width: usize) -> String {
+        (self.line3_fn)(width).1.trim().to_string()
     }
 
     // --------------------------------------------------------------------
@@ -50,13 +64,22 @@ impl Header<'_> {
             width,
         ).unwrap_or_default();
 
-        let (x2, line2_str) = (self.line2_fn)(width);
-        let x2 = x2.max(0) as usize;
-        let line2_padded = format!("{}{}", " ".repeat(x2), line2_str);
+        // Keep the end of a trail too long for the line
+        let trail = match self.trail.char_indices().rev().nth(width.saturating_sub(5)) {
+            Some((start, _)) if self.trail.chars().count() >= width && width > 4 => {
+                format!(" ...{}", &self.trail[start..])
+            }
+            _ => format!(" {}", self.trail),
+        };
+
+        let (x3, line3_str) = (self.line3_fn)(width);
+        let x3 = x3.max(0) as usize;
+        let line3_padded = format!("{}{}", " ".repeat(x3), line3_str);
 
         let lines = vec![
             Line::styled(title, self.window_colors.title),
-            Line::styled(line2_padded, self.window_colors.title),
+            Line::styled(trail, self.window_colors.title),
+            Line::styled(line3_padded, self.window_colors.title),
         ];
 
         let paragraph = Paragraph::new(Text::from(lines))
@@ -69,7 +92,6 @@ impl Header<'_> {
 
 // ------------------------------------------------------------------------
 /// Create the title string
-
 fn make_title(left: &str, middle: &str, right: &str, cols: usize) -> Result<String> {
 
     let gutter_size = isize::try_from(cols)?
@@ -0,0 +1,261 @@
+//!
+//! Hex and ASCII dump region of the screen
+//!
+//! Only the rows in view are formatted, so the size of the data makes no
+//! difference to drawing.
+//!
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::ops::Range;
+
+use crate::color::WindowColors;
+
+use super::{Coords, Region};
+
+// ------------------------------------------------------------------------
+
+pub const MIN_BYTES_PER_ROW: usize = 4;
+pub const MAX_BYTES_PER_ROW: usize = 64;
+
+/// Bytes between the extra spaces separating groups in a row
+const GROUP_LEN: usize = 8;
+
+pub struct HexRegion<'hr> {
+
+    /// Bytes to display
+    data: &'hr [u8],
+
+    bytes_per_row: usize,
+
+    /// Row at the top of the window
+    top_row: usize,
+
+    /// Offset of the selected byte
+    cursor: usize,
+
+    /// Bytes to show in the value color
+    highlight: Option<Range<usize>>,
+
+    /// Cached dimensions from last render
+    size: Coords,
+
+    /// Colors to use for this region
+    window_colors: &'hr WindowColors,
+
+}
+
+impl<'hr> HexRegion<'hr> {
+
+    pub fn new(window_colors: &'hr WindowColors, data: &'hr [u8], bytes_per_row: usize) -> HexRegion<'hr> {
+        HexRegion {
+            data,
+            bytes_per_row: bytes_per_row.clamp(MIN_BYTES_PER_ROW, MAX_BYTES_PER_ROW),
+            top_row: 0,
+            cursor: 0,
+            highlight: None,
+            size: Coords { y: 0, x: 0 },
+            window_colors,
+        }
+    }
+
+    /// Highlight a range of bytes and select its first byte
+    pub fn highlight(&mut self, range: Range<usize>) {
+        self.goto(range.start);
+        self.highlight = Some(range);
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Top row and selected byte, to return to later
+    pub fn position(&self) -> (usize, usize) {
+        (self.top_row, self.cursor)
+    }
+
+    /// Return to a position, keeping the selected byte in view
+    pub fn set_position(&mut self, (top_row, cursor): (usize, usize)) {
+        self.goto(cursor);
+        if top_row <= self.cursor / self.bytes_per_row {
+            self.top_row = top_row;
+        }
+    }
+
+    pub fn bytes_per_row(&self) -> usize {
+        self.bytes_per_row
+    }
+
+    pub fn set_bytes_per_row(&mut self, bytes_per_row: usize) {
+        self.bytes_per_row = bytes_per_row.clamp(MIN_BYTES_PER_ROW, MAX_BYTES_PER_ROW);
+        self.top_row = self.cursor / self.bytes_per_row;
+        self.scroll_to_cursor();
+    }
+
+    /// Select the byte at `offset`, limited to the end of the data
+    pub fn goto(&mut self, offset: usize) {
+        self.cursor = offset.min(self.data.len().saturating_sub(1));
+        self.scroll_to_cursor();
+    }
+
+    /// Hex digits needed for the largest offset, at least 8
+    fn gutter_len(&self) -> usize {
+        let digits = (usize::BITS - self.data.len().leading_zeros()).div_ceil(4) as usize;
+        digits.max(8)
+    }
+
+    /// Column headings matching the layout of the rows
+    pub fn column_header(&self) -> String {
+        let mut text = format!(" {:gl$}  ", "Offset", gl = self.gutter_len());
+        for idx in 0..self.bytes_per_row {
+            if idx > 0 && idx % GROUP_LEN == 0 {
+                text.push(' ');
+            }
+            text += &format!("{:02x} ", idx % 0x100);
+        }
+        text.push(' ');
+        text.extend((0..self.bytes_per_row).map(|idx| char::from_digit((idx % 16) as u32, 16).unwrap_or(' ')));
+        text
+    }
+
+    /// Keep the cursor row in view
+    fn scroll_to_cursor(&mut self) {
+        let row = self.cursor / self.bytes_per_row;
+        let height = self.size.y.max(1);
+        if row < self.top_row {
+            self.top_row = row;
+        } else if row >= self.top_row + height {
+            self.top_row = row + 1 - height;
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.data.len().div_ceil(self.bytes_per_row)
+    }
+
+    /// Plain text of a row, as it is shown
+    pub fn row_text(&self, row: usize) -> String {
+        let len = self.data.len();
+        let start = row * self.bytes_per_row;
+        let bytes = &self.data[start.min(len)..(start + self.bytes_per_row).min(len)];
+
+        let mut text = format!(" {:0gl$x}  ", start, gl = self.gutter_len());
+        for idx in 0..self.bytes_per_row {
+            if idx > 0 && idx % GROUP_LEN == 0 {
+                text.push(' ');
+            }
+            match bytes.get(idx) {
+                Some(byte) => text += &format!("{:02x} ", byte),
+                None => text += "   ",
+            }
+        }
+        text.push(' ');
+        text.extend(bytes.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+        text
+    }
+
+    // --------------------------------------------------------------------
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let height = area.height as usize;
+        let width = area.width as usize;
+        self.size = Coords { y: height, x: width };
+        if height == 0 || width == 0 {
+            return;
+        }
+        self.scroll_to_cursor();
+
+        let wc = self.window_colors;
+        let lim = (self.top_row + height).min(self.num_rows());
+        let mut ratatui_lines: Vec<Line<'static>> = Vec::with_capacity(height);
+
+        for row in self.top_row..lim {
+            let start = row * self.bytes_per_row;
+            let bytes = &self.data[start..(start + self.bytes_per_row).min(self.data.len())];
+
+            let mut spans = Vec::with_capacity(self.bytes_per_row * 2 + 4);
+            spans.push(Span::styled(format!(" {:0gl$x}  ", start, gl = self.gutter_len()), wc.text));
+
+            let mut ascii = Vec::with_capacity(self.bytes_per_row);
+            for idx in 0..self.bytes_per_row {
+                if idx > 0 && idx % GROUP_LEN == 0 {
+                    spans.push(Span::styled(" ", wc.text));
+                }
+                let Some(&byte) = bytes.get(idx) else {
+                    spans.push(Span::styled("   ", wc.bkgr));
+                    continue;
+                };
+                let offset = start + idx;
+                let mut style = match &self.highlight {
+                    Some(range) if range.contains(&offset) => wc.value,
+                    _ => wc.text,
+                };
+                if offset == self.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(format!("{:02x}", byte), style));
+                spans.push(Span::styled(" ", wc.text));
+
+                let ch = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                ascii.push(Span::styled(ch.to_string(), style));
+            }
+
+            spans.push(Span::styled(" ", wc.text));
+            spans.extend(ascii);
+            ratatui_lines.push(Line::from(spans));
+        }
+
+        while ratatui_lines.len() < height {
+            ratatui_lines.push(Line::styled(" ".repeat(width), wc.bkgr));
+        }
+
+        let paragraph = Paragraph::new(ratatui_lines).style(wc.bkgr);
+        f.render_widget(paragraph, area);
+    }
+
+    // --------------------------------------------------------------------
+
+    pub fn handle_key(&mut self, code: KeyCode) -> Result<()> {
+        let page = self.size.y.max(1) * self.bytes_per_row;
+        match code {
+            KeyCode::Left => self.goto(self.cursor.saturating_sub(1)),
+            KeyCode::Right => self.goto(self.cursor + 1),
+            KeyCode::Up => self.goto(self.cursor.saturating_sub(self.bytes_per_row)),
+            KeyCode::Down if self.cursor + self.bytes_per_row < self.data.len() => self.goto(self.cursor + self.bytes_per_row),
+            KeyCode::PageUp => {
+                self.top_row = self.top_row.saturating_sub(self.size.y);
+                self.goto(self.cursor.saturating_sub(page));
+            }
+            KeyCode::PageDown => {
+                self.top_row = (self.top_row + self.size.y).min(self.num_rows().saturating_sub(self.size.y));
+                self.goto(self.cursor + page);
+            }
+            KeyCode::Home => self.goto(0),
+            KeyCode::End => self.goto(self.data.len()),
+            _ => {}
+        }
+        Ok(())
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+impl<'r> Region<'r> for HexRegion<'_> {
+    fn render(&mut self, f: &mut Frame, area: Rect) {
+        HexRegion::render(self, f, area)
+    }
+    fn position(&self) -> (usize, usize) {
+        HexRegion::position(self)
+    }
+    fn set_position(&mut self, position: (usize, usize)) {
+        HexRegion::set_position(self, position)
+    }
+}
@@ -4,7 +4,9 @@
 
 use anyhow::Result;
 use ratatui::style::Style;
+use std::ops::Range;
 
+use crate::exe_types::ExeRef;
 use super::scrollable_region::ScrollableRegion;
 
 // ------------------------------------------------------------------------
@@ -19,7 +21,6 @@ pub type ExpandLinesFn<'l> = Box<dyn Fn() -> LineVec<'l> + 'l>;
 
 // ------------------------------------------------------------------------
 /// Definition of the line trait used by the scrollable window
-
 pub trait Line<'l> {
 
     /// Return a set of style/string pairs.
@@ -29,15 +30,29 @@ pub trait Line<'l> {
     fn action_type(&self) -> Option<&ActionType<'l>> { None }
     fn action_type_mut(&mut self) -> Option<&mut ActionType<'l>> { None }
 
-    /// Expand in-line? Return the indentation amount
-    fn expand(&self) -> Option<usize> { None }
-
-    /// Function to expand
-    fn expand_fn(&self) -> Result<Option<LineVec<'l>>> { Ok(None) }
-
     // Function to call when enter is hit on the line
     fn enter_fn(&self) -> Option<EnterFn<'l>> { None }
 
+    /// Offsets of the file bytes the line shows, for the hex view
+    fn file_range(&self) -> Option<Range<usize>> { None }
+
+    /// File the line stands for, in lists of several files
+    fn exe(&self) -> Option<ExeRef<'l>> { None }
+
+    /// Name for the screen the line opens, the text before any value
+    fn label(&self, max_len: usize) -> String {
+        let text: String = self
+            .as_pairs(max_len)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect();
+        match text.split_once(" :") {
+            Some((name, _)) => name.trim().to_string(),
+            None => text.trim().to_string(),
+        }
+    }
+
 }
 
 // --------------------------------------------------------------------
@@ -51,6 +66,5 @@ pub enum ActionType<'at> {
 
 // ------------------------------------------------------------------------
 /// The generated line to be displayed is a vector of tuples (style, string)
-
 pub type Pair = (Option<Style>, String);
 pub type PairVec = Vec<Pair>;
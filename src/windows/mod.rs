@@ -7,9 +7,27 @@
 pub mod details;
 pub mod footer;
 pub mod header;
+pub mod hex_region;
 pub mod line;
 pub mod popup;
 pub mod scrollable_region;
+pub mod search;
+pub mod text;
+
+use ratatui::{layout::Rect, Frame};
+
+use scrollable_region::ScrollableRegion;
+
+// ------------------------------------------------------------------------
+/// Window between the header and footer of a screen
+pub trait Region<'r> {
+    fn render(&mut self, f: &mut Frame, area: Rect);
+    fn position(&self) -> (usize, usize);
+    fn set_position(&mut self, position: (usize, usize));
+
+    /// The lines of the region, if it has lines to search and open
+    fn lines(&mut self) -> Option<&mut ScrollableRegion<'r>> { None }
+}
 
 // ------------------------------------------------------------------------
 /// Y/X coordinates and/or dimensions
@@ -5,7 +5,7 @@
 use anyhow::Error;
 use crossterm::{
     cursor::MoveTo,
-    event::{self, Event},
+    event::{self, Event, KeyCode},
     execute, queue,
     style::{Color, Colors, Print, ResetColor, SetColors},
     terminal,
@@ -14,6 +14,8 @@ use std::io::{self, Write};
 
 // ------------------------------------------------------------------------
 
+/// Show an error, or write it to stderr when the terminal is not in use,
+/// as when dumping
 pub fn error_window(error: &Error) {
     let mut lines = vec![];
     for cause in error.chain() {
@@ -26,7 +28,10 @@ pub fn error_window(error: &Error) {
             _ => lines.push(format!("    {}", cause)),
         }
     }
-    window("Error", lines, (Color::White, Color::Red));
+    match terminal::is_raw_mode_enabled() {
+        Ok(true) => window("Error", lines, (Color::White, Color::Red)),
+        _ => eprintln!("{}", lines.join("\n")),
+    }
 }
 
 // ------------------------------------------------------------------------
@@ -35,7 +40,6 @@ pub fn error_window(error: &Error) {
 /// NOTE: Must NOT be called from within a ratatui `terminal.draw()` closure,
 /// as the direct crossterm writes would be overwritten when draw() flushes.
 /// Call from event loop handlers only; the next draw() cycle will redraw cleanly.
-
 pub fn window(title: &str, lines: Vec<String>, colors: (Color, Color)) {
 
     let (term_width, term_height) = terminal::size().unwrap_or((80, 24));
@@ -91,3 +95,56 @@ pub fn window(title: &str, lines: Vec<String>, colors: (Color, Color)) {
         }
     }
 }
+
+// ------------------------------------------------------------------------
+/// Prompt for a line of text, `None` if cancelled with escape.
+///
+/// The same restriction as `window` applies, call from event handlers only.
+/// There is nobody to answer when the terminal is not in use.
+pub fn prompt(title: &str, label: &str) -> Option<String> {
+    const INPUT_LEN: usize = 40;
+
+    if !terminal::is_raw_mode_enabled().unwrap_or(false) {
+        return None;
+    }
+
+    let (term_width, term_height) = terminal::size().unwrap_or((80, 24));
+    let width = (4 + title.len().max(label.len() + 2 + INPUT_LEN)) as u16;
+    let height = 5;
+    let ypos = term_height.saturating_sub(height) / 2;
+    let xpos = term_width.saturating_sub(width) / 2;
+
+    let mut stdout = io::stdout();
+    let mut input = String::new();
+
+    loop {
+        let _ = queue!(stdout, SetColors(Colors::new(Color::Black, Color::Cyan)));
+        for row in 0..height {
+            let text = match row {
+                0 => format!("+{:-^w$}+", title, w = (width - 2) as usize),
+                2 => {
+                    // Keep the end of long input visible
+                    let start = input.len().saturating_sub(INPUT_LEN - 1);
+                    format!("|{:w$}|", format!(" {}: {}_", label, &input[start..]), w = (width - 2) as usize)
+                }
+                r if r == height - 1 => format!("+{}+", "-".repeat((width - 2) as usize)),
+                _ => format!("|{}|", " ".repeat((width - 2) as usize)),
+            };
+            let _ = queue!(stdout, MoveTo(xpos, ypos + row), Print(text));
+        }
+        let _ = execute!(stdout, ResetColor);
+        let _ = stdout.flush();
+
+        if let Ok(Event::Key(key)) = event::read() {
+            match key.code {
+                KeyCode::Enter => return Some(input),
+                KeyCode::Esc => return None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => input.push(c),
+                _ => {}
+            }
+        }
+    }
+}
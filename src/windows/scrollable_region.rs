@@ -12,17 +12,28 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use crate::color::WindowColors;
+use std::ops::Range;
 
-use super::{line, Coords};
+use crate::{color::WindowColors, exe_types::ExeRef};
+
+use super::{line, Coords, Region};
 
 // ------------------------------------------------------------------------
 
+/// Most lines expanding every line leaves in a region
+pub const MAX_EXPANDED: usize = 100_000;
+
+/// Width lines are formatted at to find them by their text
+const FIND_WIDTH: usize = 256;
+
 pub struct ScrollableRegion<'sr> {
 
     /// Set of lines to display
     lines: line::LineVec<'sr>,
 
+    /// Indentation of each line, from the expansions it is nested in
+    indents: Vec<usize>,
+
     /// Index into lines of the top line in the window
     top_idx: usize,
 
@@ -35,6 +46,9 @@ pub struct ScrollableRegion<'sr> {
     /// Colors to use for this scrollable region
     window_colors: &'sr WindowColors,
 
+    /// Text searched for, in ASCII lower case, empty if none
+    search: String,
+
 }
 
 impl<'sr> ScrollableRegion<'sr> {
@@ -44,11 +58,13 @@ impl<'sr> ScrollableRegion<'sr> {
         lines: line::LineVec<'sr>,
     ) -> ScrollableRegion<'sr> {
         ScrollableRegion {
+            indents: vec![0; lines.len()],
             lines,
             size: Coords { y: 0, x: 0 },
             top_idx: 0,
             win_idx: 0,
             window_colors,
+            search: String::new(),
         }
     }
 
@@ -81,7 +97,8 @@ impl<'sr> ScrollableRegion<'sr> {
             let line = &self.lines[line_idx];
 
             // Reserve 2 chars: 1 for action indicator, 1 for scroll indicator
-            let content_width = width.saturating_sub(2);
+            let indent = self.indents[line_idx].min(width.saturating_sub(2));
+            let content_width = width.saturating_sub(2 + indent);
             let pairs = line.as_pairs(content_width).unwrap_or_default();
 
             // Action indicator character
@@ -96,16 +113,12 @@ impl<'sr> ScrollableRegion<'sr> {
             // Build spans
             let mut spans: Vec<Span<'static>> = Vec::new();
             spans.push(Span::styled(
-                indicator.to_string(),
+                format!("{:indent$}{}", "", indicator),
                 self.window_colors.text,
             ));
 
-            let mut content_len = 0usize;
-            for (style_opt, text) in &pairs {
-                let style = style_opt.unwrap_or(self.window_colors.text);
-                content_len += text.len();
-                spans.push(Span::styled(text.clone(), style));
-            }
+            let content_len = pairs.iter().map(|(_, text)| text.len()).sum::<usize>();
+            spans.extend(self.highlight(pairs));
 
             // Padding to fill the row before the scroll indicator column
             let pad_len = content_width.saturating_sub(content_len);
@@ -175,6 +188,159 @@ impl<'sr> ScrollableRegion<'sr> {
         Ok(())
     }
 
+    /// Index of the selected line
+    pub fn selected(&self) -> usize {
+        self.top_idx + self.win_idx
+    }
+
+    /// Select the line at `idx`, scrolling only if it is out of view
+    pub fn select(&mut self, idx: usize) {
+        let idx = idx.min(self.lines.len().saturating_sub(1));
+        let height = self.size.y.max(1);
+        if idx < self.top_idx || idx >= self.top_idx + height {
+            self.top_idx = idx.saturating_sub(height / 2);
+        }
+        self.win_idx = idx - self.top_idx;
+    }
+
+    /// The lines as plain text, indented as they are shown
+    pub fn text(&self, width: usize) -> Vec<String> {
+        self.lines
+            .iter()
+            .zip(&self.indents)
+            .map(|(line, &indent)| {
+                let pairs = line.as_pairs(width.saturating_sub(indent)).unwrap_or_default();
+                let text: String = pairs.into_iter().map(|(_, text)| text).collect();
+                format!("{:indent$}{}", "", text).trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Does enter on the line at `idx` open a window?
+    pub fn opens_window(&self, idx: usize) -> bool {
+        matches!(self.lines.get(idx).and_then(|line| line.action_type()), Some(line::ActionType::NewWindow(_)))
+    }
+
+    /// Top line and selected line in the window, to return to later
+    pub fn position(&self) -> (usize, usize) {
+        (self.top_idx, self.win_idx)
+    }
+
+    /// Return to a position, as far as the lines allow
+    pub fn set_position(&mut self, (top_idx, win_idx): (usize, usize)) {
+        let last = self.lines.len().saturating_sub(1);
+        self.top_idx = top_idx.min(last);
+        self.win_idx = win_idx.min(last - self.top_idx);
+    }
+
+    /// Name of the selected line, for the screen it opens
+    pub fn selected_label(&self) -> String {
+        let width = self.size.x.saturating_sub(2);
+        self.lines.get(self.selected()).map(|line| line.label(width)).unwrap_or_default()
+    }
+
+    /// File bytes shown by the selected line
+    pub fn selected_file_range(&self) -> Option<Range<usize>> {
+        self.lines.get(self.top_idx + self.win_idx)?.file_range()
+    }
+
+    pub fn selected_exe(&self) -> Option<ExeRef<'sr>> {
+        self.lines.get(self.top_idx + self.win_idx)?.exe()
+    }
+
+    /// Text of the line at `idx`, to find it by after the lines change
+    pub fn line_text(&self, idx: usize) -> String {
+        let Some(line) = self.lines.get(idx) else {
+            return String::new();
+        };
+        line.as_pairs(FIND_WIDTH).unwrap_or_default().into_iter().map(|(_, text)| text).collect()
+    }
+
+    /// Index of the line reading `text`, the nearest to `idx` if there are
+    /// several
+    pub fn find_line(&self, idx: usize, text: &str) -> Option<usize> {
+        (0..self.lines.len())
+            .filter(|pos| self.line_text(*pos) == text)
+            .min_by_key(|pos| pos.abs_diff(idx))
+    }
+
+    // --------------------------------------------------------------------
+
+    /// Highlight `pattern`, ignoring ASCII case, in the lines shown
+    pub fn set_search(&mut self, pattern: &str) {
+        self.search = pattern.to_ascii_lowercase();
+    }
+
+    /// Select the first line holding the search text from `start` on,
+    /// wrapping around. False if there is none.
+    pub fn find(&mut self, start: usize) -> bool {
+        self.find_from(start, true)
+    }
+
+    /// Select the next or previous line holding the search text
+    pub fn find_next(&mut self, forward: bool) -> bool {
+        let total = self.lines.len();
+        let start = if forward { self.selected() + 1 } else { self.selected() + total.saturating_sub(1) };
+        self.find_from(start, forward)
+    }
+
+    fn find_from(&mut self, start: usize, forward: bool) -> bool {
+        let total = self.lines.len();
+        if self.search.is_empty() || total == 0 {
+            return false;
+        }
+        let width = self.size.x.saturating_sub(2);
+        let start = start % total;
+        for step in 0..total {
+            let idx = if forward { (start + step) % total } else { (start + total - step) % total };
+            let pairs = self.lines[idx].as_pairs(width).unwrap_or_default();
+            let text: String = pairs.iter().map(|(_, text)| text.as_str()).collect();
+            if text.to_ascii_lowercase().contains(&self.search) {
+                self.select(idx);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Spans of a line, with the search matches in bold and underlined
+    fn highlight(&self, pairs: line::PairVec) -> Vec<Span<'static>> {
+        let default = self.window_colors.text;
+        if self.search.is_empty() {
+            return pairs.into_iter().map(|(style, text)| Span::styled(text, style.unwrap_or(default))).collect();
+        }
+
+        let text: String = pairs.iter().map(|(_, text)| text.as_str()).collect::<String>().to_ascii_lowercase();
+        let matches: Vec<Range<usize>> = text
+            .match_indices(&self.search)
+            .map(|(start, m)| start..start + m.len())
+            .collect();
+
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        for (style, text) in pairs {
+            let style = style.unwrap_or(default);
+            let end = pos + text.len();
+            // Split the pair at the edges of the matches within it
+            let mut cuts = Vec::from([pos, end]);
+            for m in matches.iter().filter(|m| m.start < end && m.end > pos) {
+                cuts.extend([m.start.max(pos), m.end.min(end)]);
+            }
+            cuts.sort_unstable();
+            cuts.dedup();
+            for cut in cuts.windows(2) {
+                let matched = matches.iter().any(|m| m.start <= cut[0] && cut[1] <= m.end);
+                let piece = text[cut[0] - pos..cut[1] - pos].to_string();
+                spans.push(match matched {
+                    true => Span::styled(piece, style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+                    false => Span::styled(piece, style),
+                });
+            }
+            pos = end;
+        }
+        spans
+    }
+
     // --------------------------------------------------------------------
 
     pub fn key_enter_handler(&mut self) -> Result<()> {
@@ -182,18 +348,124 @@ impl<'sr> ScrollableRegion<'sr> {
         if idx >= self.lines.len() {
             return Ok(());
         }
-        let line = &mut self.lines[idx];
 
-        if let Some(at) = line.action_type_mut() {
-            match at {
-                line::ActionType::NewWindow(nwf) => nwf()?,
-                line::ActionType::Expandable(_, ref mut num_lines, _) => {
-                    *num_lines = 0;
+        match self.lines[idx].action_type() {
+            Some(line::ActionType::NewWindow(nwf)) => nwf(),
+            Some(line::ActionType::Expandable(_, num_lines, _)) => {
+                if *num_lines > 0 {
+                    self.collapse(idx);
+                } else {
+                    self.expand(idx);
                 }
+                Ok(())
             }
+            None => match self.lines[idx].enter_fn() {
+                Some(enter_fn) => enter_fn(self),
+                None => Ok(()),
+            },
         }
+    }
 
-        Ok(())
+    /// Expand every line, including the lines each expansion adds, in one
+    /// pass. Stops expanding at `MAX_EXPANDED` lines, as a tree of debug
+    /// entries can be far larger than anyone reads, and returns false if
+    /// any line was left collapsed.
+    pub fn expand_all(&mut self) -> bool {
+        let selected = self.selected();
+        let mut pending: Vec<(line::LineItem<'sr>, usize, Option<usize>)> = std::mem::take(&mut self.lines)
+            .into_iter()
+            .zip(std::mem::take(&mut self.indents))
+            .enumerate()
+            .map(|(idx, (line, indent))| (line, indent, Some(idx)))
+            .rev()
+            .collect();
+
+        let mut complete = true;
+        let mut new_selected = 0;
+        while let Some((mut line, indent, old_idx)) = pending.pop() {
+            if old_idx == Some(selected) {
+                new_selected = self.lines.len();
+            }
+            if let Some(line::ActionType::Expandable(expand_fn, num_lines @ 0, child_indent)) = line.action_type_mut() {
+                let children = expand_fn();
+                if self.lines.len() + 1 + pending.len() + children.len() <= MAX_EXPANDED {
+                    *num_lines = children.len();
+                    let indent = indent + *child_indent;
+                    pending.extend(children.into_iter().rev().map(|child| (child, indent, None)));
+                } else {
+                    complete = false;
+                }
+            }
+            self.lines.push(line);
+            self.indents.push(indent);
+        }
+        self.select(new_selected);
+        complete
+    }
+
+    /// Collapse every expanded line, keeping the selection on the line
+    /// that held it
+    pub fn collapse_all(&mut self) {
+        let mut selected = self.selected();
+        let mut idx = 0;
+        while idx < self.lines.len() {
+            let count = self.collapse(idx);
+            if selected > idx + count {
+                selected -= count;
+            } else if selected > idx {
+                selected = idx;
+            }
+            idx += 1;
+        }
+        self.select(selected);
+    }
+
+    /// Insert the lines of a collapsed line's expansion after it, indented
+    /// under it, returning the number of lines added
+    fn expand(&mut self, idx: usize) -> usize {
+        let (children, indent) = match self.lines[idx].action_type() {
+            Some(line::ActionType::Expandable(expand_fn, 0, indent)) => (expand_fn(), *indent),
+            _ => return 0,
+        };
+        let count = children.len();
+        let indent = self.indents[idx] + indent;
+        self.lines.splice(idx + 1..idx + 1, children);
+        self.indents.splice(idx + 1..idx + 1, std::iter::repeat_n(indent, count));
+        self.set_expanded(idx, count);
+        count
+    }
+
+    /// Remove the lines of an expansion, with any expanded descendants,
+    /// returning the number of lines removed
+    fn collapse(&mut self, idx: usize) -> usize {
+        let count = self.expanded_len(idx);
+        self.lines.drain(idx + 1..idx + 1 + count);
+        self.indents.drain(idx + 1..idx + 1 + count);
+        self.set_expanded(idx, 0);
+        count
+    }
+
+    fn set_expanded(&mut self, idx: usize, count: usize) {
+        if let Some(line::ActionType::Expandable(_, num_lines, _)) = self.lines[idx].action_type_mut() {
+            *num_lines = count;
+        }
+    }
+
+    /// Number of lines following `idx` that belong to its expansion
+    fn expanded_len(&self, idx: usize) -> usize {
+        let mut remaining = match self.lines[idx].action_type() {
+            Some(line::ActionType::Expandable(_, num_lines, _)) => *num_lines,
+            _ => 0,
+        };
+        let mut pos = idx + 1;
+        while remaining > 0 && pos < self.lines.len() {
+            if let Some(line::ActionType::Expandable(_, num_lines, _)) = self.lines[pos].action_type() {
+                remaining += num_lines;
+            }
+            remaining -= 1;
+            pos += 1;
+        }
+        pos - idx - 1
     }
 
     // --------------------------------------------------------------------
@@ -278,3 +550,91 @@ impl<'sr> ScrollableRegion<'sr> {
     }
 
 }
+
+// ------------------------------------------------------------------------
+
+impl<'r> Region<'r> for ScrollableRegion<'r> {
+    fn render(&mut self, f: &mut Frame, area: Rect) {
+        ScrollableRegion::render(self, f, area)
+    }
+    fn position(&self) -> (usize, usize) {
+        ScrollableRegion::position(self)
+    }
+    fn set_position(&mut self, position: (usize, usize)) {
+        ScrollableRegion::set_position(self, position)
+    }
+    fn lines(&mut self) -> Option<&mut ScrollableRegion<'r>> {
+        Some(self)
+    }
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::text::TextLine;
+    use ratatui::style::Style;
+
+    const WC: WindowColors = WindowColors {
+        bkgr: Style::new(),
+        title: Style::new(),
+        text: Style::new(),
+        value: Style::new(),
+    };
+
+    /// Line expanding to two lines a level deeper, down to `depth` levels
+    fn node(name: String, depth: usize) -> line::LineItem<'static> {
+        let text = name.clone();
+        let children = move || -> line::LineVec<'static> {
+            match depth {
+                0 => Vec::new(),
+                _ => (0..2).map(|idx| node(format!("{}.{}", name, idx), depth - 1)).collect(),
+            }
+        };
+        TextLine::new(Vec::from([(None, text)]))
+            .action(line::ActionType::Expandable(Box::new(children), 0, 2))
+            .boxed()
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        let mut region = ScrollableRegion::new(&WC, Vec::from([node("a".into(), 2), node("b".into(), 0)]));
+
+        region.key_enter_handler().unwrap();
+        assert_eq!(region.text(80), ["a", "  a.0", "  a.1", "b"]);
+
+        // Everything below the line already expanded too, the selection
+        // stays on its line
+        region.select(3);
+        assert!(region.expand_all());
+        assert_eq!(region.text(80), [
+            "a", "  a.0", "    a.0.0", "    a.0.1", "  a.1", "    a.1.0", "    a.1.1", "b",
+        ]);
+        assert_eq!(region.selected(), 7);
+
+        region.select(2);
+        region.collapse_all();
+        assert_eq!(region.text(80), ["a", "b"]);
+        assert_eq!(region.selected(), 0);
+
+        // Expanding again starts from the collapsed lines
+        region.key_enter_handler().unwrap();
+        assert_eq!(region.text(80), ["a", "  a.0", "  a.1", "b"]);
+    }
+
+    /// Line expanding to two more lines like it without end
+    fn endless() -> line::LineItem<'static> {
+        TextLine::new(Vec::from([(None, String::from("x"))]))
+            .action(line::ActionType::Expandable(Box::new(|| Vec::from([endless(), endless()])), 0, 0))
+            .boxed()
+    }
+
+    #[test]
+    fn too_many_to_expand() {
+        let mut region = ScrollableRegion::new(&WC, Vec::from([endless()]));
+        assert!(!region.expand_all());
+        assert!(region.text(0).len() > MAX_EXPANDED / 2);
+        assert!(region.text(0).len() <= MAX_EXPANDED);
+    }
+}
@@ -0,0 +1,167 @@
+//!
+//! Incremental search of a scrollable region
+//!
+//! `/` starts a search that is typed in the footer, each key moving the
+//! selection to the first matching line. Enter keeps the search, Esc
+//! drops it and goes back to where it started. `n` and `N` then move to
+//! the next and previous matches.
+//!
+
+use crossterm::event::KeyCode;
+
+use super::scrollable_region::ScrollableRegion;
+
+// ------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct Search {
+    /// Text typed so far, while the search is being entered
+    input: Option<String>,
+    /// Line selected when the search started
+    origin: usize,
+    /// Does a line hold the text?
+    found: bool,
+}
+
+impl Search {
+
+    /// Footer text while the search is being entered
+    pub fn prompt(&self) -> Option<String> {
+        self.input.as_ref().map(|input| match self.found || input.is_empty() {
+            true => format!("/{}_", input),
+            false => format!("/{}_ (not found)", input),
+        })
+    }
+
+    /// Handle a key, returning false if it is not for the search
+    pub fn handle_key(&mut self, code: KeyCode, scr_win: &mut ScrollableRegion) -> bool {
+        let Some(input) = &mut self.input else {
+            match code {
+                KeyCode::Char('/') => {
+                    self.input = Some(String::new());
+                    self.origin = scr_win.selected();
+                    self.found = false;
+                    scr_win.set_search("");
+                }
+                KeyCode::Char('n') => {
+                    scr_win.find_next(true);
+                }
+                KeyCode::Char('N') => {
+                    scr_win.find_next(false);
+                }
+                _ => return false,
+            }
+            return true;
+        };
+
+        match code {
+            KeyCode::Enter => self.input = None,
+            KeyCode::Esc => {
+                self.input = None;
+                scr_win.set_search("");
+                scr_win.select(self.origin);
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                self.update(scr_win);
+            }
+            KeyCode::Char(c) => {
+                input.push(c);
+                self.update(scr_win);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Select the first match from where the search started, or go back
+    /// there when nothing matches
+    fn update(&mut self, scr_win: &mut ScrollableRegion) {
+        scr_win.set_search(self.input.as_deref().unwrap_or_default());
+        self.found = scr_win.find(self.origin);
+        if !self.found {
+            scr_win.select(self.origin);
+        }
+    }
+
+}
+
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::WindowColors,
+        windows::{line::LineItem, text::TextLine},
+    };
+    use ratatui::style::Style;
+
+    const WC: WindowColors = WindowColors {
+        bkgr: Style::new(),
+        title: Style::new(),
+        text: Style::new(),
+        value: Style::new(),
+    };
+
+    fn lines(texts: &[&str]) -> Vec<LineItem<'static>> {
+        texts.iter().map(|text| TextLine::new(Vec::from([(None, text.to_string())])).boxed()).collect()
+    }
+
+    fn type_keys(search: &mut Search, scr_win: &mut ScrollableRegion, keys: &str) {
+        keys.chars().for_each(|c| assert!(search.handle_key(KeyCode::Char(c), scr_win)));
+    }
+
+    #[test]
+    fn incremental_search() {
+        let mut scr_win = ScrollableRegion::new(&WC, lines(&["main", "Memcpy", "strlen", "memset", "free"]));
+        let mut search = Search::default();
+        scr_win.select(2);
+
+        // Each key moves to the first match from where the search started,
+        // wrapping around, and ignoring case
+        type_keys(&mut search, &mut scr_win, "/m");
+        assert_eq!(scr_win.selected(), 3);
+        assert_eq!(search.prompt().as_deref(), Some("/m_"));
+        type_keys(&mut search, &mut scr_win, "emc");
+        assert_eq!(scr_win.selected(), 1);
+
+        // Nothing matching goes back to the start, backspace finds it again
+        type_keys(&mut search, &mut scr_win, "x");
+        assert_eq!(scr_win.selected(), 2);
+        assert_eq!(search.prompt().as_deref(), Some("/memcx_ (not found)"));
+        assert!(search.handle_key(KeyCode::Backspace, &mut scr_win));
+        assert_eq!(scr_win.selected(), 1);
+
+        // Esc drops the search and goes back to where it started
+        assert!(search.handle_key(KeyCode::Esc, &mut scr_win));
+        assert_eq!(scr_win.selected(), 2);
+        assert_eq!(search.prompt(), None);
+        assert!(!search.handle_key(KeyCode::Char('x'), &mut scr_win));
+    }
+
+    #[test]
+    fn next_and_previous_wrap_around() {
+        let mut scr_win = ScrollableRegion::new(&WC, lines(&["main", "memcpy", "strlen", "memset", "free"]));
+        let mut search = Search::default();
+
+        type_keys(&mut search, &mut scr_win, "/mem");
+        assert!(search.handle_key(KeyCode::Enter, &mut scr_win));
+        assert_eq!(search.prompt(), None);
+        assert_eq!(scr_win.selected(), 1);
+
+        let mut visited = Vec::new();
+        for _ in 0..3 {
+            type_keys(&mut search, &mut scr_win, "n");
+            visited.push(scr_win.selected());
+        }
+        assert_eq!(visited, [3, 1, 3]);
+
+        visited.clear();
+        for _ in 0..3 {
+            type_keys(&mut search, &mut scr_win, "N");
+            visited.push(scr_win.selected());
+        }
+        assert_eq!(visited, [1, 3, 1]);
+    }
+}
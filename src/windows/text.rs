@@ -0,0 +1,65 @@
+//!
+//! Lines of preformatted text
+//!
+
+use anyhow::Result;
+
+use crate::color::WindowColors;
+
+use super::line::{ActionType, Line, LineItem, PairVec};
+
+// ------------------------------------------------------------------------
+/// Line made up of a fixed set of style/string pairs
+pub struct TextLine<'tl> {
+    pairs: PairVec,
+    action: Option<ActionType<'tl>>,
+}
+
+impl<'tl> TextLine<'tl> {
+
+    pub fn new(pairs: PairVec) -> Self {
+        Self { pairs, action: None }
+    }
+
+    /// A `name : value` line in the same layout as the detail lines
+    pub fn field(
+        name: &str,
+        name_len: usize,
+        value: &str,
+        wc: WindowColors,
+    ) -> Self {
+        Self::new(Vec::from([
+            (Some(wc.text), format!("{name:name_len$.name_len$} :")),
+            (Some(wc.value), format!(" {}", value)),
+        ]))
+    }
+
+    /// A line of text in a single style
+    pub fn text(text: &str, wc: WindowColors) -> Self {
+        Self::new(Vec::from([(Some(wc.text), text.into())]))
+    }
+
+    pub fn action(mut self, action: ActionType<'tl>) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn boxed(self) -> LineItem<'tl> {
+        Box::new(self)
+    }
+
+}
+
+impl<'l> Line<'l> for TextLine<'l> {
+    fn as_pairs(&self, _max_len: usize) -> Result<PairVec> {
+        Ok(self.pairs.clone())
+    }
+
+    fn action_type(&self) -> Option<&ActionType<'l>> {
+        self.action.as_ref()
+    }
+
+    fn action_type_mut(&mut self) -> Option<&mut ActionType<'l>> {
+        self.action.as_mut()
+    }
+}
@@ -0,0 +1,12 @@
+#include <stdio.h>
+#include <string.h>
+
+static int counter;
+
+int square(int x) { return x * x; }
+
+int main(int argc, char **argv) {
+    counter += argc;
+    printf("%d %zu\n", square(counter), strlen(argv[0]));
+    return 0;
+}
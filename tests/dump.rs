@@ -0,0 +1,60 @@
+//!
+//! `--dump` writes the screens of the files named to stdout
+//!
+
+use std::process::{Command, Output};
+
+const SAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/samples/hello-x86_64.elf");
+
+fn ncexe(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ncexe")).args(args).output().unwrap()
+}
+
+/// Lines under the view `name` of the file, one level deeper than it
+fn view<'o>(stdout: &'o str, name: &str) -> Vec<&'o str> {
+    stdout
+        .lines()
+        .skip_while(|line| *line != format!("  {}", name))
+        .skip(1)
+        .take_while(|line| line.starts_with("    "))
+        .collect()
+}
+
+#[test]
+fn all_views() {
+    let output = ncexe(&["--dump", "--view", "all", SAMPLE]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().nth(1), Some(SAMPLE));
+    assert!(stdout.contains("  Entry Point Address          : 0x0000000000000670\n"));
+
+    // Every view of the file is printed with its lines under it
+    let views = ["Hex Dump", "Security", "Disassembly", "Strings", "DWARF Debug Information", "Address Lookup",
+        "Unwind Information", "Symbol Versions", "Symbol Hash Tables"];
+    for name in views {
+        assert!(!view(&stdout, name).is_empty(), "{} is empty", name);
+    }
+    assert!(view(&stdout, "Security").contains(&"    PIE              : Yes"));
+    assert!(view(&stdout, "Disassembly").contains(&"    .text       : 0x670, 294 bytes"));
+
+    // Nothing can be typed in, so the entry point is looked up
+    assert_eq!(view(&stdout, "Address Lookup")[..3], [
+        "    Address 0x670",
+        "    Address    : 0x670",
+        "    Symbol     : _start",
+    ]);
+}
+
+#[test]
+fn chosen_views() {
+    let output = ncexe(&["--dump", "--view", "security", "--view", "STRINGS", SAMPLE]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let views: Vec<&str> = stdout.lines().filter(|line| !line.starts_with("   ") && !line.contains(" : ")).collect();
+    assert_eq!(views, ["", SAMPLE, "  ELF", "  Security", "  Strings"]);
+
+    let output = ncexe(&["--dump", "--view", "nothing", SAMPLE]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown view \"nothing\""));
+}
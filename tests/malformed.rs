@@ -0,0 +1,242 @@
+//!
+//! Truncated and corrupted files must open, decode and, with the `tui`
+//! feature, have every screen shown without a panic
+//!
+//! `corpus/malformed` holds files that made the viewer panic or hang. The
+//! well formed files in `corpus/samples` are also cut short and have bytes
+//! overwritten, in the same way each run. A file taking longer than
+//! `MAX_TIME` fails too, so decoders doing quadratic or amplified work on
+//! hostile input don't pass slowly.
+//!
+
+use std::{
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
+
+use ncexe::{
+    decoders::{
+        addr2line,
+        dwarf::{constants::DW_AT_STMT_LIST, Dwarf},
+        eh_frame::{self, EhFrame, Entry, RegisterSet},
+        rust_info, structures, symbol_hash, unwind_info,
+    },
+    exe_types::{self, macho64, ExeRef},
+};
+#[cfg(feature = "tui")]
+use ncexe::{color, screens::dump};
+
+// ------------------------------------------------------------------------
+
+/// Copies of each sample with bytes overwritten, unless set by
+/// `NCEXE_CORRUPTIONS`
+const CORRUPTIONS: usize = 150;
+
+fn corpus(dir: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus").join(dir);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("{}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Time a file may take to open and decode, far more than any file in
+/// the corpus needs
+const MAX_TIME: Duration = Duration::from_secs(5);
+
+/// Entries read from each unit, more than the samples have but few
+/// enough that a unit of nested entries can't make the test crawl
+const MAX_DIES: usize = 10_000;
+
+/// Open `data` as a file and decode it, keeping the file and failing when
+/// it panics
+fn check(name: &str, data: &[u8]) {
+    #[cfg(feature = "tui")]
+    {
+        static COLORS: std::sync::Once = std::sync::Once::new();
+        COLORS.call_once(|| color::init("dark"));
+    }
+
+    let path = env::temp_dir().join(format!("ncexe-{}-{}", process::id(), name));
+    fs::write(&path, data).unwrap();
+
+    let filename = path.to_str().unwrap();
+    let started = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let exe = exe_types::new(filename);
+        exe.architecture();
+        exe.entry_point();
+        exe.segments();
+        exe.sections();
+        exe.symbols();
+        exe.dependencies();
+        exe.address_space();
+        structures::decode(exe.as_ref());
+        // Errors are expected, they are reported to the user
+        walk_dwarf(exe.as_ref());
+        walk_decoders(exe.as_ref());
+        if let Some(entry) = exe.entry_point() {
+            let _ = addr2line::lookup(exe.as_ref(), entry);
+        }
+        #[cfg(feature = "tui")]
+        let _ = dump::walk(&Vec::from([exe]));
+    }));
+
+    match result {
+        Err(_) => panic!("{} panicked, kept as {}", name, path.display()),
+        Ok(()) if started.elapsed() > MAX_TIME => {
+            panic!("{} took {:?}, kept as {}", name, started.elapsed(), path.display())
+        }
+        Ok(()) => fs::remove_file(&path).unwrap(),
+    }
+}
+
+/// Read every unit, entry, range list and line program
+fn walk_dwarf(exe: ExeRef) {
+    let Some(Ok(dwarf)) = Dwarf::load(exe) else { return };
+    let Ok(units) = dwarf.units() else { return };
+
+    for unit in &units {
+        let _ = dwarf.unit_summary(unit);
+        let Ok(Some(cu)) = dwarf.die(unit, unit.first_die) else { continue };
+        if let Some(offset) = cu.attr(DW_AT_STMT_LIST).and_then(|a| a.udata()) {
+            let _ = dwarf.line_program(offset as usize, unit.addr_size);
+        }
+
+        let mut dies = Vec::from([cu]);
+        for _ in 0..MAX_DIES {
+            let Some(die) = dies.pop() else { break };
+            for attr in &die.attrs {
+                dwarf.value_string(unit, attr);
+            }
+            let _ = dwarf.die_ranges(unit, &die);
+            dies.extend(dwarf.children(unit, &die).unwrap_or_default());
+        }
+    }
+}
+
+/// Decode the unwind information, hash tables and Rust metadata, which
+/// the screens show
+fn walk_decoders(exe: ExeRef) {
+    if let Some(frame) = EhFrame::load(exe) {
+        let regs = RegisterSet::from_exe(exe);
+        let entries = frame.entries().unwrap_or_default();
+        for entry in &entries {
+            let (cie, data, pc) = match entry {
+                Entry::Cie(cie) => (cie, cie.instructions, 0),
+                Entry::Fde(fde) => match entries.iter().find_map(|e| match e {
+                    Entry::Cie(cie) if cie.offset == fde.cie_offset => Some(cie),
+                    _ => None,
+                }) {
+                    Some(cie) => (cie, fde.instructions, fde.pc_begin),
+                    None => continue,
+                },
+            };
+            let _ = eh_frame::instructions(&frame, cie, data, pc, regs);
+        }
+    }
+    let _ = eh_frame::eh_frame_hdr(exe);
+    let _ = unwind_info::decode(exe);
+
+    if let Ok(tables) = symbol_hash::decode(exe) {
+        if let Some(sysv) = &tables.sysv {
+            sysv.chain_lengths();
+        }
+        if let Some(gnu) = &tables.gnu {
+            gnu.chain_lengths();
+        }
+    }
+    let _ = rust_info::decode(exe);
+}
+
+/// Same numbers each run, xorshift
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+// ------------------------------------------------------------------------
+
+#[test]
+fn malformed_files() {
+    for path in corpus("malformed") {
+        check(&file_name(&path), &fs::read(&path).unwrap());
+    }
+}
+
+#[test]
+fn bad_last_load_command() {
+    let sample_path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/samples/hello-x86_64.macho");
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/malformed/macho64-bad-last-command.macho");
+    let sample = exe_types::new(sample_path);
+    let exe = exe_types::new(path);
+
+    // The commands before the bad one still decode
+    let cmds = macho64::load_commands(exe.mmap(), &macho64::LAYOUT64);
+    assert!(cmds.error.is_some());
+    assert_eq!(cmds.commands.len(), 6);
+    assert!(!exe.sections().is_empty());
+    assert_eq!(exe.sections(), sample.sections());
+    assert_eq!(exe.segments(), sample.segments());
+    assert_eq!(exe.dependencies(), sample.dependencies());
+    assert_eq!(exe.entry_point(), sample.entry_point());
+}
+
+#[test]
+fn truncated_samples() {
+    for path in corpus("samples") {
+        let name = file_name(&path);
+        let data = fs::read(&path).unwrap();
+
+        // Byte by byte through the headers, then in steps
+        let lengths = (0..data.len().min(512)).chain((512..data.len()).step_by(97));
+        for len in lengths {
+            check(&format!("{}-{}", name, len), &data[..len]);
+        }
+    }
+}
+
+#[test]
+fn corrupted_samples() {
+    const VALUES: &[u64] = &[0, 1, 0x7f, 0x80, 0xff, 0x7fff_ffff, 0xffff_ffff, u64::MAX];
+
+    let copies = env::var("NCEXE_CORRUPTIONS").map_or(CORRUPTIONS, |n| n.parse().unwrap());
+
+    for path in corpus("samples") {
+        let name = file_name(&path);
+        let sample = fs::read(&path).unwrap();
+        let mut random = Random(0x9e37_79b9_7f4a_7c15 ^ sample.len() as u64);
+
+        for copy in 0..copies {
+            let mut data = sample.clone();
+            for _ in 0..1 + random.below(8) {
+                // Mostly in the headers, where the offsets and counts are
+                let range = if random.below(4) == 0 { data.len() } else { data.len().min(1024) };
+                let pos = random.below(range);
+                let value = VALUES[random.below(VALUES.len())].to_le_bytes();
+                let len = [1, 2, 4, 8][random.below(4)].min(data.len() - pos);
+                data[pos..pos + len].copy_from_slice(&value[..len]);
+            }
+            check(&format!("{}-{}", name, copy), &data);
+        }
+    }
+}